@@ -0,0 +1,41 @@
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use orm::prelude::*;
+use orm::sequel::statement::select::SelectStatement;
+use orm::sequel::term::func::count;
+use orm::sequel::term::table::Table;
+use orm::sequel::visitor::dialect::postgre::PostgreSQL;
+use orm::sequel::visitor::render_cache::RenderCache;
+use orm::sequel::visitor::visitor::postgre;
+
+// 热点查询：每次调用结构相同，只有参数不同
+fn hot_query(users: &Table, orders: &Table, user_id: i64) -> SelectStatement {
+    SelectStatement::from(users)
+        .left_join(orders, users.column("id").eq(orders.column("user_id")))
+        .select(vec![users.column("id"), users.column("name")])
+        .select(count(orders.column("id")).alias("cnt"))
+        .filter(users.column("id").eq(user_id))
+        .filter(users.column("status").in_(vec!["active", "pending"]))
+        .filter(orders.column("total").gt(100))
+        .group_by(vec![users.column("id"), users.column("name")])
+        .order_by(users.column("name"))
+        .limit(20)
+}
+
+fn render(c: &mut Criterion) {
+    let users = Table::new("users");
+    let orders = Table::new("orders");
+    let stmt = hot_query(&users, &orders, 42);
+
+    let mut group = c.benchmark_group("render_select");
+    group.bench_function("visit_select_statement", |b| {
+        b.iter(|| postgre().visit_select_statement(black_box(&stmt)).finish())
+    });
+    let cache = RenderCache::<PostgreSQL>::new();
+    group.bench_function("visit_select_statement_cached", |b| {
+        b.iter(|| postgre().visit_select_statement_cached(black_box(&stmt), &cache).finish())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, render);
+criterion_main!(benches);
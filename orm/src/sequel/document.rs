@@ -0,0 +1,196 @@
+use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::term::column::Column;
+use crate::sequel::term::expr::Expr;
+use crate::sequel::term::func::Func;
+use crate::sequel::term::lock::Locking;
+use crate::sequel::term::order::Order;
+use crate::sequel::term::table::{Table, TableInner};
+use serde::de::{DeserializeOwned, Error as _};
+use serde::ser::{SerializeSeq, SerializeStruct};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// 文档格式的版本，不兼容的修改需要递增
+pub const VERSION: u32 = 1;
+
+// 语句、表达式等序列化为带版本的文档，所有 TableInner 各存一份在 tables 中，
+// 表和列按下标引用，反序列化后引用同一张表的列仍共享同一个 Arc，别名不变
+//   {"version": 1, "root": {... "from_clause": {"table": 0, "alias": null} ...}, "tables": [{"named": ...}]}
+//   let json = serde_json::to_string(&stmt)?;
+//   let stmt: SelectStatement = serde_json::from_str(&json)?;
+// 嵌套在文档中的节点只输出自身的内容，不再包一层文档
+macro_rules! impl_document {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl Serialize for $t {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    if encoding() {
+                        <$t>::serialize(self, serializer)
+                    } else {
+                        serialize_document(self, serializer)
+                    }
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $t {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    if decoding() {
+                        <$t>::deserialize(deserializer)
+                    } else {
+                        deserialize_document(deserializer)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_document!(SelectStatement, Expr, Table, Column, Func, Order, Locking);
+
+thread_local! {
+    static ENCODER: RefCell<Option<Encoder>> = const { RefCell::new(None) };
+    static DECODER: RefCell<Option<Decoder>> = const { RefCell::new(None) };
+}
+
+// 按第一次出现的顺序给表编号，持有 Arc 避免地址被复用
+struct Encoder {
+    ids: HashMap<usize, usize>,
+    tables: Vec<Arc<TableInner>>,
+}
+
+// 表按需构建，文档中键的顺序不影响结果；取出但尚未构建完的表是循环引用
+struct Decoder {
+    raw: Vec<Option<Value>>,
+    built: Vec<Option<Arc<TableInner>>>,
+}
+
+fn encoding() -> bool {
+    ENCODER.with(|e| e.borrow().is_some())
+}
+
+fn decoding() -> bool {
+    DECODER.with(|d| d.borrow().is_some())
+}
+
+// 文档结束或出错时清理
+struct Session<T: 'static>(&'static std::thread::LocalKey<RefCell<Option<T>>>);
+
+impl<T: 'static> Session<T> {
+    fn begin(key: &'static std::thread::LocalKey<RefCell<Option<T>>>, state: T) -> Self {
+        key.with(|s| *s.borrow_mut() = Some(state));
+        Self(key)
+    }
+}
+
+impl<T: 'static> Drop for Session<T> {
+    fn drop(&mut self) {
+        self.0.with(|s| *s.borrow_mut() = None);
+    }
+}
+
+fn serialize_document<T: Serialize, S: Serializer>(root: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    let encoder = Encoder {
+        ids: HashMap::new(),
+        tables: Vec::new(),
+    };
+    let _session = Session::begin(&ENCODER, encoder);
+    let mut doc = serializer.serialize_struct("Document", 3)?;
+    doc.serialize_field("version", &VERSION)?;
+    doc.serialize_field("root", root)?;
+    // 先输出 root 才知道引用了哪些表
+    doc.serialize_field("tables", &Tables)?;
+    doc.end()
+}
+
+// 输出表的过程中会发现新的表，追加在后面，直到没有新的表
+struct Tables;
+
+impl Serialize for Tables {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(None)?;
+        for i in 0.. {
+            let Some(inner) = ENCODER.with(|e| e.borrow().as_ref().and_then(|e| e.tables.get(i).cloned())) else {
+                break;
+            };
+            seq.serialize_element(inner.as_ref())?;
+        }
+        seq.end()
+    }
+}
+
+#[derive(Deserialize)]
+struct Document {
+    version: u32,
+    root: Value,
+    tables: Vec<Value>,
+}
+
+fn deserialize_document<'de, T: DeserializeOwned, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    let doc = Document::deserialize(deserializer)?;
+    if doc.version != VERSION {
+        let msg = format!("unsupported document version {}", doc.version);
+        return Err(D::Error::custom(msg));
+    }
+    let decoder = Decoder {
+        built: vec![None; doc.tables.len()],
+        raw: doc.tables.into_iter().map(Some).collect(),
+    };
+    let _session = Session::begin(&DECODER, decoder);
+    T::deserialize(doc.root).map_err(D::Error::custom)
+}
+
+fn resolve(id: usize) -> Result<Arc<TableInner>, String> {
+    let built = DECODER.with(|d| d.borrow().as_ref().and_then(|d| d.built.get(id).cloned().flatten()));
+    if let Some(inner) = built {
+        return Ok(inner);
+    }
+    let raw = DECODER.with(|d| d.borrow_mut().as_mut().and_then(|d| d.raw.get_mut(id)?.take()));
+    let Some(raw) = raw else {
+        return Err(format!("invalid table reference {}", id));
+    };
+    let inner = Arc::new(TableInner::deserialize(raw).map_err(|e| e.to_string())?);
+    DECODER.with(|d| {
+        if let Some(d) = d.borrow_mut().as_mut() {
+            d.built[id] = Some(inner.clone());
+        }
+    });
+    Ok(inner)
+}
+
+// Table 和 Column 中 Arc<TableInner> 的编码，只在文档中使用
+pub(crate) mod table_ref {
+    use super::{DECODER, ENCODER, resolve};
+    use crate::sequel::term::table::TableInner;
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::sync::Arc;
+
+    pub fn serialize<S: Serializer>(inner: &Arc<TableInner>, serializer: S) -> Result<S::Ok, S::Error> {
+        let id = ENCODER.with(|e| {
+            let mut e = e.borrow_mut();
+            let e = e.as_mut()?;
+            let next = e.tables.len();
+            let id = *e.ids.entry(Arc::as_ptr(inner) as usize).or_insert(next);
+            if id == next {
+                e.tables.push(inner.clone());
+            }
+            Some(id)
+        });
+        match id {
+            Some(id) => serializer.serialize_u64(id as u64),
+            None => Err(S::Error::custom("table reference outside of a document")),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Arc<TableInner>, D::Error> {
+        let id = usize::deserialize(deserializer)?;
+        if DECODER.with(|d| d.borrow().is_none()) {
+            return Err(D::Error::custom("table reference outside of a document"));
+        }
+        resolve(id).map_err(D::Error::custom)
+    }
+}
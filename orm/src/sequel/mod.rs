@@ -1,3 +1,6 @@
+pub mod document;
+pub mod parser;
 pub mod statement;
 pub mod term;
+pub mod traverse;
 pub mod visitor;
@@ -0,0 +1,63 @@
+use std::fmt;
+use thiserror::Error;
+
+// 行列号从 1 开始，offset 为字节偏移
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn of(sql: &str, offset: usize) -> Self {
+        let before = &sql[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = before[line_start..].chars().count() + 1;
+        Self { offset, line, column }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ParseError {
+    #[error("ParseError: expected {expected}, found {found} at {position}")]
+    Unexpected {
+        expected: String,
+        found: String,
+        position: Position,
+    },
+
+    // 列引用的表名或别名不在任何 FROM 中
+    #[error("ParseError: unknown table {name} at {position}")]
+    UnknownTable { name: String, position: Position },
+
+    // 不带表名的列引用无法确定属于 FROM 中的哪张表
+    #[error("ParseError: column {name} is ambiguous at {position}")]
+    AmbiguousColumn { name: String, position: Position },
+
+    #[error("ParseError: no value bound to placeholder {placeholder} at {position}")]
+    MissingParam { placeholder: String, position: Position },
+
+    // 合法的 SQL，但 AST 无法表示
+    #[error("ParseError: {what} is not supported at {position}")]
+    Unsupported { what: String, position: Position },
+}
+
+impl ParseError {
+    pub fn position(&self) -> Position {
+        match self {
+            Self::Unexpected { position, .. }
+            | Self::UnknownTable { position, .. }
+            | Self::AmbiguousColumn { position, .. }
+            | Self::MissingParam { position, .. }
+            | Self::Unsupported { position, .. } => *position,
+        }
+    }
+}
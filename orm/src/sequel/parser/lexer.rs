@@ -0,0 +1,197 @@
+use crate::sequel::parser::error::{ParseError, Position};
+use crate::sequel::visitor::dialect::{Dialect, PlaceHolderStyle};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    // 关键字或未加引号的标识符
+    Word(String),
+    // 加引号的标识符
+    Quoted(String),
+    String(String),
+    Number(String),
+    // e.g. X'0a1b', 0x0a1b
+    Hex(Vec<u8>),
+    // ? 没有序号，$1/@p1/:1 带从 1 开始的序号
+    Placeholder(Option<usize>),
+    // :user_id、@user_id
+    NamedPlaceholder(String),
+    Symbol(&'static str),
+    Eof,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Word(w) => write!(f, "{}", w),
+            Self::Quoted(q) => write!(f, "identifier {}", q),
+            Self::String(s) => write!(f, "'{}'", s),
+            Self::Number(n) => write!(f, "{}", n),
+            Self::Hex(_) => write!(f, "binary literal"),
+            Self::Placeholder(Some(n)) => write!(f, "placeholder {}", n),
+            Self::Placeholder(None) => write!(f, "placeholder ?"),
+            Self::NamedPlaceholder(name) => write!(f, "placeholder {}", name),
+            Self::Symbol(s) => write!(f, "'{}'", s),
+            Self::Eof => write!(f, "end of input"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub offset: usize,
+}
+
+// 按长度降序，保证先匹配 <= 再匹配 <
+const SYMBOLS: [&str; 18] = [
+    "::", "<>", "!=", "<=", ">=", "(", ")", ",", ".", "*", "+", "-", "/", "%", "=", "<", ">", ";",
+];
+
+pub fn tokenize(sql: &str, dialect: &impl Dialect) -> Result<Vec<Token>, ParseError> {
+    let (open, close) = (dialect.quote_char(), dialect.quote_close_char());
+    let style = dialect.placeholder_style();
+    let backslash = dialect.caps().backslash_escape;
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < sql.len() {
+        let rest = &sql[i..];
+        let c = rest.chars().next().unwrap();
+        if c.is_whitespace() {
+            i += c.len_utf8();
+            continue;
+        }
+        let offset = i;
+        let kind = if rest.starts_with(open) {
+            let (name, len) = read_quoted(sql, i, open, close)?;
+            i += len;
+            TokenKind::Quoted(name)
+        } else if (c == 'X' || c == 'x') && rest[1..].starts_with('\'') {
+            let (hex, len) = read_string(sql, i + 1, backslash)?;
+            i += 1 + len;
+            TokenKind::Hex(decode_hex(&hex).ok_or_else(|| unexpected(sql, offset, "hex digits", &hex))?)
+        } else if c == '\'' {
+            let (s, len) = read_string(sql, i, backslash)?;
+            i += len;
+            TokenKind::String(s)
+        } else if rest.starts_with("0x") || rest.starts_with("0X") {
+            let digits: String = rest[2..].chars().take_while(|c| c.is_ascii_hexdigit()).collect();
+            i += 2 + digits.len();
+            TokenKind::Hex(decode_hex(&digits).ok_or_else(|| unexpected(sql, offset, "hex digits", &digits))?)
+        } else if c.is_ascii_digit() {
+            let len = number_len(rest);
+            i += len;
+            TokenKind::Number(rest[..len].to_string())
+        } else if c.is_alphabetic() || c == '_' {
+            let len = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            i += len;
+            TokenKind::Word(rest[..len].to_string())
+        } else if let Some((placeholder, len)) = read_placeholder(rest, style) {
+            i += len;
+            placeholder
+        } else if let Some(symbol) = SYMBOLS.iter().find(|s| rest.starts_with(**s)) {
+            i += symbol.len();
+            TokenKind::Symbol(symbol)
+        } else {
+            return Err(unexpected(sql, offset, "a token", &format!("'{}'", c)));
+        };
+        tokens.push(Token { kind, offset });
+    }
+    tokens.push(Token {
+        kind: TokenKind::Eof,
+        offset: sql.len(),
+    });
+    Ok(tokens)
+}
+
+// 结束引号连写两次表示引号本身，e.g. "a""b"
+fn read_quoted(sql: &str, start: usize, open: &str, close: &str) -> Result<(String, usize), ParseError> {
+    let mut name = String::new();
+    let mut i = start + open.len();
+    loop {
+        let rest = &sql[i..];
+        if rest.is_empty() {
+            return Err(unexpected(sql, sql.len(), &format!("closing {}", close), "end of input"));
+        }
+        if let Some(after) = rest.strip_prefix(close) {
+            if after.starts_with(close) {
+                name.push_str(close);
+                i += close.len() * 2;
+                continue;
+            }
+            return Ok((name, i + close.len() - start));
+        }
+        let c = rest.chars().next().unwrap();
+        name.push(c);
+        i += c.len_utf8();
+    }
+}
+
+// 与 Dialect::render_string 相反：'' 还原为 '，反斜杠转义的方言中 \\ 还原为 \
+fn read_string(sql: &str, start: usize, backslash: bool) -> Result<(String, usize), ParseError> {
+    let mut s = String::new();
+    let mut chars = sql[start + 1..].char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\'' if chars.peek().is_some_and(|(_, c)| *c == '\'') => {
+                chars.next();
+                s.push('\'');
+            },
+            '\'' => return Ok((s, i + 2)),
+            '\\' if backslash && chars.peek().is_some_and(|(_, c)| *c == '\\') => {
+                chars.next();
+                s.push('\\');
+            },
+            c => s.push(c),
+        }
+    }
+    Err(unexpected(sql, sql.len(), "closing '", "end of input"))
+}
+
+fn number_len(rest: &str) -> usize {
+    let bytes = rest.as_bytes();
+    let mut len = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+    if bytes.get(len) == Some(&b'.') && bytes.get(len + 1).is_some_and(|b| b.is_ascii_digit()) {
+        len += 1;
+        len += bytes[len..].iter().take_while(|b| b.is_ascii_digit()).count();
+    }
+    len
+}
+
+fn read_placeholder(rest: &str, style: PlaceHolderStyle) -> Option<(TokenKind, usize)> {
+    let prefix = match style {
+        PlaceHolderStyle::QuestionMark => return rest.starts_with('?').then_some((TokenKind::Placeholder(None), 1)),
+        PlaceHolderStyle::Numbered => "$",
+        PlaceHolderStyle::AtNumbered => "@p",
+        PlaceHolderStyle::ColonNumbered => ":",
+        PlaceHolderStyle::ColonNamed | PlaceHolderStyle::AtNamed => {
+            let name = rest.strip_prefix(if style == PlaceHolderStyle::ColonNamed { ":" } else { "@" })?;
+            if !name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+                return None;
+            }
+            let len = name.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(name.len());
+            return Some((TokenKind::NamedPlaceholder(name[..len].to_string()), 1 + len));
+        },
+    };
+    let digits: String = rest.strip_prefix(prefix)?.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let n = digits.parse().ok()?;
+    Some((TokenKind::Placeholder(Some(n)), prefix.len() + digits.len()))
+}
+
+pub fn decode_hex(digits: &str) -> Option<Vec<u8>> {
+    if !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn unexpected(sql: &str, offset: usize, expected: &str, found: &str) -> ParseError {
+    ParseError::Unexpected {
+        expected: expected.to_string(),
+        found: found.to_string(),
+        position: Position::of(sql, offset),
+    }
+}
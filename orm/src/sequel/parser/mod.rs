@@ -0,0 +1,3 @@
+pub mod error;
+pub(crate) mod lexer;
+pub mod parser;
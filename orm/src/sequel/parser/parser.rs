@@ -0,0 +1,1379 @@
+use crate::model::columns::ColumnType;
+use crate::sequel::parser::error::{ParseError, Position};
+use crate::sequel::parser::lexer::{Token, TokenKind, decode_hex, tokenize};
+use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::term::case::{Case, case, case_of};
+use crate::sequel::term::cte::Cte;
+use crate::sequel::term::distinct::Distinct;
+use crate::sequel::term::expr::{Expr, cast};
+use crate::sequel::term::func::{FuncArg, func};
+use crate::sequel::term::index::Index;
+use crate::sequel::term::join::JoinType;
+use crate::sequel::term::lock::{Lock, Locking, Wait};
+use crate::sequel::term::ops::{BinaryOp, UnaryOp};
+use crate::sequel::term::order::Order;
+use crate::sequel::term::param::{Param, ParamData, lit};
+use crate::sequel::term::sample::Sample;
+use crate::sequel::term::select_item::SelectItem;
+use crate::sequel::term::set_op::{SetOp, SetOperation};
+use crate::sequel::term::table::{Table, TableInner, TableName};
+use crate::sequel::term::values::Values;
+use crate::sequel::term::window::{Frame, FrameBound, FrameUnit, Window};
+use crate::sequel::visitor::builder::is_unnamed_param;
+use crate::sequel::visitor::dialect::Dialect;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// 未加引号时不能作为别名的关键字
+const RESERVED: [&str; 48] = [
+    "SELECT", "FROM", "WHERE", "GROUP", "HAVING", "WINDOW", "QUALIFY", "ORDER", "LIMIT", "OFFSET", "FETCH", "FOR",
+    "UNION", "INTERSECT", "EXCEPT", "JOIN", "INNER", "LEFT", "RIGHT", "FULL", "CROSS", "ON", "AS", "AND", "OR", "NOT",
+    "WITH", "USING", "SAMPLE", "FINAL", "SETTINGS", "FORCE", "USE", "IGNORE", "INDEXED", "CASE", "WHEN", "THEN", "ELSE",
+    "END", "IN", "IS", "LIKE", "BETWEEN", "BY", "ASC", "DESC", "DISTINCT",
+];
+
+// e.g. parse_select(&PostgreSQL {}, "SELECT ... WHERE id = $1", vec![ParamData::Int(1)])
+pub fn parse_select<D: Dialect>(dialect: &D, sql: &str, params: Vec<ParamData>) -> Result<SelectStatement, ParseError> {
+    Parser::new(dialect, sql).params(params).parse_select()
+}
+
+// 同一 SQL 中的占位符按方言的风格识别，绑定到 params 中对应位置的值
+pub struct Parser<'a, D: Dialect> {
+    dialect: &'a D,
+    sql: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    params: Vec<ParamData>,
+    next_param: usize,
+    // 由内向外查找列引用所属的表
+    scopes: Vec<Vec<Table>>,
+    // SQL Server 写在表名之后的提示，每个 FROM 单独收集
+    table_hints: Vec<(Table, Vec<String>)>,
+    // 对同一 CTE 的无别名引用共用一张表，渲染时才会都使用 CTE 名
+    ctes: HashMap<String, Table>,
+    // 以完整表名引用、不在 FROM 中的表
+    qualified: Vec<Table>,
+    // 具名参数对应的位置
+    named_params: HashMap<String, usize>,
+    // 解析 ORDER BY 时当前查询的 SELECT 列表
+    output_columns: Vec<SelectItem>,
+}
+
+impl<'a, D: Dialect> Parser<'a, D> {
+    pub fn new(dialect: &'a D, sql: &'a str) -> Self {
+        Self {
+            dialect,
+            sql,
+            tokens: Vec::new(),
+            pos: 0,
+            params: Vec::new(),
+            next_param: 0,
+            scopes: Vec::new(),
+            table_hints: Vec::new(),
+            ctes: HashMap::new(),
+            qualified: Vec::new(),
+            named_params: HashMap::new(),
+            output_columns: Vec::new(),
+        }
+    }
+
+    pub fn params(mut self, params: Vec<ParamData>) -> Self {
+        self.params = params;
+        self
+    }
+
+    // 独立解析表达式时，列引用按表名或别名在这些表中查找
+    pub fn table(mut self, table: &Table) -> Self {
+        match self.scopes.first_mut() {
+            Some(scope) => scope.push(table.clone()),
+            None => self.scopes.push(vec![table.clone()]),
+        }
+        self
+    }
+
+    pub fn parse_select(mut self) -> Result<SelectStatement, ParseError> {
+        self.tokens = tokenize(self.sql, self.dialect)?;
+        let stmt = self.parse_query()?;
+        self.eat_symbol(";");
+        self.expect_eof()?;
+        Ok(stmt)
+    }
+
+    pub fn parse_expr(mut self) -> Result<Expr, ParseError> {
+        self.tokens = tokenize(self.sql, self.dialect)?;
+        let expr = self.expr()?;
+        self.expect_eof()?;
+        Ok(expr)
+    }
+
+    fn parse_query(&mut self) -> Result<SelectStatement, ParseError> {
+        // 外层 SELECT 的别名在子查询中不可见
+        let outer_columns = std::mem::take(&mut self.output_columns);
+        let (with_clause, recursive) = self.parse_with_clause()?;
+        let mut stmt = self.parse_select_core()?;
+        while let Some(op) = self.parse_set_op() {
+            let operand = if self.is_symbol("(") {
+                self.parse_subquery()?
+            } else {
+                let operand = self.parse_select_core()?;
+                self.scopes.pop();
+                operand
+            };
+            match stmt.set_ops.last_mut() {
+                // INTERSECT 比 UNION / EXCEPT 结合得更紧：a UNION b INTERSECT c => a UNION (b INTERSECT c)
+                Some(last) if op == SetOp::Intersect && last.op != SetOp::Intersect => {
+                    last.stmt = last.stmt.clone().intersect(operand);
+                },
+                _ => stmt.set_ops.push(SetOperation { op, stmt: operand }),
+            }
+        }
+        // 组合查询的 ORDER BY/LIMIT 作用于整个结果，与普通查询共用这些字段
+        self.parse_orders(&mut stmt)?;
+        self.parse_paging(&mut stmt)?;
+        let mut stmt = self.parse_locking(stmt)?;
+        self.parse_settings(&mut stmt)?;
+        self.scopes.pop();
+        self.output_columns = outer_columns;
+        stmt.with_clause = with_clause;
+        stmt.recursive = recursive;
+        Ok(stmt)
+    }
+
+    // (SELECT ...)
+    fn parse_subquery(&mut self) -> Result<SelectStatement, ParseError> {
+        self.expect_symbol("(")?;
+        let stmt = self.parse_query()?;
+        self.expect_symbol(")")?;
+        Ok(stmt)
+    }
+
+    fn is_subquery_start(&self) -> bool {
+        self.is_symbol("(") && (self.is_keyword_at(1, "SELECT") || self.is_keyword_at(1, "WITH"))
+    }
+
+    fn parse_with_clause(&mut self) -> Result<(Vec<Cte>, bool), ParseError> {
+        let mut ctes = Vec::new();
+        if !self.eat_keyword("WITH") {
+            return Ok((ctes, false));
+        }
+        let recursive = self.eat_keyword("RECURSIVE");
+        loop {
+            let name = self.ident()?;
+            self.ctes.insert(name.clone(), Table::new(name.clone()));
+            let mut columns = Vec::new();
+            if self.eat_symbol("(") {
+                columns.push(self.ident()?);
+                while self.eat_symbol(",") {
+                    columns.push(self.ident()?);
+                }
+                self.expect_symbol(")")?;
+            }
+            self.expect_keyword("AS")?;
+            let materialized = if self.eat_keywords(&["NOT", "MATERIALIZED"]) {
+                Some(false)
+            } else if self.eat_keyword("MATERIALIZED") {
+                Some(true)
+            } else {
+                None
+            };
+            let query = self.parse_subquery()?;
+            let mut cte = Cte::new(name, query).columns(columns);
+            cte.materialized = materialized;
+            ctes.push(cte);
+            if !self.eat_symbol(",") {
+                break;
+            }
+        }
+        Ok((ctes, recursive))
+    }
+
+    fn parse_set_op(&mut self) -> Option<SetOp> {
+        if self.eat_keywords(&["UNION", "ALL"]) {
+            Some(SetOp::UnionAll)
+        } else if self.eat_keyword("UNION") {
+            Some(SetOp::Union)
+        } else if self.eat_keyword("INTERSECT") {
+            Some(SetOp::Intersect)
+        } else if self.eat_keyword("EXCEPT") {
+            Some(SetOp::Except)
+        } else {
+            None
+        }
+    }
+
+    // SELECT ... FROM ... WHERE ... GROUP BY ... HAVING ... WINDOW ... QUALIFY ...
+    // 返回后当前作用域仍保留，由调用方在解析完 ORDER BY 等子句后弹出
+    fn parse_select_core(&mut self) -> Result<SelectStatement, ParseError> {
+        self.expect_keyword("SELECT")?;
+        // 先解析 FROM，SELECT 列表中的列引用才能找到所属的表
+        let list_start = self.pos;
+        self.pos = self.find_from()?;
+        self.expect_keyword("FROM")?;
+        self.scopes.push(Vec::new());
+        let outer_hints = std::mem::take(&mut self.table_hints);
+        let from = self.parse_from()?;
+        let hints = std::mem::replace(&mut self.table_hints, outer_hints);
+
+        let mut stmt = SelectStatement::from(&from);
+        self.apply_table_hints(&mut stmt, hints);
+        if self.eat_keyword("FINAL") {
+            stmt = stmt.final_();
+        }
+        if self.eat_keyword("SAMPLE") {
+            stmt.sample = Some(self.parse_sample_ratio()?);
+        }
+        self.parse_index_hints(&mut stmt)?;
+        let from_end = self.pos;
+
+        self.pos = list_start;
+        stmt.distinct = if self.eat_keywords(&["DISTINCT", "ON"]) {
+            self.expect_symbol("(")?;
+            let cols = self.expr_list()?;
+            self.expect_symbol(")")?;
+            Distinct::On(cols)
+        } else if self.eat_keyword("DISTINCT") {
+            Distinct::All
+        } else {
+            Distinct::None
+        };
+        if self.eat_keyword("TOP") {
+            stmt.limit = Some(self.usize()?);
+        }
+        if !self.eat_symbol("*") {
+            loop {
+                let item = self.select_item()?;
+                stmt = stmt.select(item);
+                if !self.eat_symbol(",") {
+                    break;
+                }
+            }
+        }
+        if !self.is_keyword("FROM") {
+            return Err(self.unexpected("',' or FROM"));
+        }
+        self.pos = from_end;
+
+        if self.eat_keyword("WHERE") {
+            stmt.where_clause = conjuncts(self.expr()?);
+        }
+        if self.eat_keywords(&["GROUP", "BY", "ALL"]) {
+            stmt = stmt.group_by_all();
+        } else if self.eat_keywords(&["GROUP", "BY"]) {
+            stmt.groups = self.expr_list()?;
+        }
+        if self.eat_keyword("HAVING") {
+            stmt.having_clause = conjuncts(self.expr()?);
+        }
+        if self.eat_keyword("WINDOW") {
+            loop {
+                let name = self.ident()?;
+                self.expect_keyword("AS")?;
+                let window = self.window()?;
+                stmt = stmt.window(name, window);
+                if !self.eat_symbol(",") {
+                    break;
+                }
+            }
+        }
+        if self.eat_keyword("QUALIFY") {
+            stmt.qualify_clause = conjuncts(self.expr()?);
+        }
+        if self.eat_keywords(&["USING", "SAMPLE"]) {
+            let n = self.usize()?;
+            stmt.sample = Some(if self.eat_symbol("%") {
+                Sample::Percent(n as f64)
+            } else {
+                self.expect_keyword("ROWS")?;
+                Sample::Rows(n)
+            });
+        }
+        Ok(stmt)
+    }
+
+    // 从当前位置找到同一层括号内的 FROM
+    fn find_from(&self) -> Result<usize, ParseError> {
+        let mut depth = 0;
+        for (i, token) in self.tokens.iter().enumerate().skip(self.pos) {
+            match &token.kind {
+                TokenKind::Symbol("(") => depth += 1,
+                TokenKind::Symbol(")") if depth == 0 => break,
+                TokenKind::Symbol(")") => depth -= 1,
+                TokenKind::Word(w) if depth == 0 && w.eq_ignore_ascii_case("FROM") => return Ok(i),
+                TokenKind::Eof => break,
+                _ => {},
+            }
+        }
+        Err(self.unexpected("FROM"))
+    }
+
+    // ClickHouse: SAMPLE 0.1 按比例，SAMPLE 1000 按行数
+    fn parse_sample_ratio(&mut self) -> Result<Sample, ParseError> {
+        let sample = match self.advance().kind {
+            TokenKind::Number(n) if n.contains('.') => n.parse::<f64>().ok().map(|r| Sample::Percent(r * 100.0)),
+            TokenKind::Number(n) => n.parse().ok().map(Sample::Rows),
+            _ => None,
+        };
+        sample.ok_or_else(|| self.unexpected_prev("a sample ratio or row count"))
+    }
+
+    fn parse_from(&mut self) -> Result<Table, ParseError> {
+        let mut table = self.table_factor()?;
+        loop {
+            let join_type = if self.eat_keywords(&["CROSS", "JOIN"]) || self.eat_symbol(",") {
+                JoinType::Cross
+            } else if self.eat_keywords(&["INNER", "JOIN"]) || self.eat_keyword("JOIN") {
+                JoinType::Inner
+            } else if self.eat_keywords(&["LEFT", "OUTER", "JOIN"]) || self.eat_keywords(&["LEFT", "JOIN"]) {
+                JoinType::Left
+            } else if self.eat_keywords(&["RIGHT", "OUTER", "JOIN"]) || self.eat_keywords(&["RIGHT", "JOIN"]) {
+                JoinType::Right
+            } else if self.eat_keywords(&["FULL", "OUTER", "JOIN"]) || self.eat_keywords(&["FULL", "JOIN"]) {
+                JoinType::Full
+            } else {
+                return Ok(table);
+            };
+            // e.g. a INNER JOIN (b INNER JOIN c ON ...) ON ...
+            let right = if self.is_symbol("(") && !self.is_subquery_start() && !self.is_keyword_at(1, "VALUES") {
+                self.expect_symbol("(")?;
+                let right = self.parse_from()?;
+                self.expect_symbol(")")?;
+                right
+            } else {
+                self.table_factor()?
+            };
+            table = match join_type {
+                JoinType::Cross => table.cross_join(&right),
+                _ => {
+                    self.expect_keyword("ON")?;
+                    let on = self.expr()?;
+                    match join_type {
+                        JoinType::Inner => table.join(&right, on),
+                        JoinType::Left => table.left_join(&right, on),
+                        JoinType::Right => table.right_join(&right, on),
+                        _ => table.full_join(&right, on),
+                    }
+                },
+            };
+        }
+    }
+
+    fn table_factor(&mut self) -> Result<Table, ParseError> {
+        let table = if self.is_subquery_start() {
+            let table = Table::from(self.parse_subquery()?);
+            match self.alias()? {
+                Some(alias) => table.alias(alias),
+                None => table,
+            }
+        } else if self.is_symbol("(") && self.is_keyword_at(1, "VALUES") {
+            self.values_table()?
+        } else {
+            let name = self.table_name()?;
+            let cte = if name.is_qualified() { None } else { self.ctes.get(&name.name).cloned() };
+            match (self.alias()?, cte) {
+                (Some(alias), _) => Table::from(name).alias(alias),
+                (None, Some(cte)) => cte,
+                (None, None) => Table::from(name),
+            }
+        };
+        if self.is_keyword("WITH") && self.is_symbol_at(1, "(") {
+            self.advance();
+            self.advance();
+            let hints = self.table_hint_list()?;
+            self.table_hints.push((table.clone(), hints));
+        }
+        self.scopes.last_mut().unwrap().push(table.clone());
+        Ok(table)
+    }
+
+    // (VALUES (1, 'a'), (2, 'b')) [AS] v [(id, name)]
+    fn values_table(&mut self) -> Result<Table, ParseError> {
+        self.expect_symbol("(")?;
+        self.expect_keyword("VALUES")?;
+        let mut rows = Vec::new();
+        loop {
+            self.expect_symbol("(")?;
+            rows.push(self.expr_list()?);
+            self.expect_symbol(")")?;
+            if !self.eat_symbol(",") {
+                break;
+            }
+        }
+        self.expect_symbol(")")?;
+        let alias = self.alias()?;
+        let mut columns = Vec::new();
+        if self.eat_symbol("(") {
+            columns.push(self.ident()?);
+            while self.eat_symbol(",") {
+                columns.push(self.ident()?);
+            }
+            self.expect_symbol(")")?;
+        }
+        let table = Table::from(Values::new(columns, rows));
+        Ok(match alias {
+            Some(alias) => table.alias(alias),
+            None => table,
+        })
+    }
+
+    // WITH (INDEX([idx]), UPDLOCK, ROWLOCK)，INDEX 的参数保留原样
+    fn table_hint_list(&mut self) -> Result<Vec<String>, ParseError> {
+        let mut hints = Vec::new();
+        loop {
+            let hint = self.word()?.to_uppercase();
+            if hint == "INDEX" {
+                self.expect_symbol("(")?;
+                loop {
+                    hints.push(format!("INDEX:{}", self.ident()?));
+                    if !self.eat_symbol(",") {
+                        break;
+                    }
+                }
+                self.expect_symbol(")")?;
+            } else {
+                hints.push(hint);
+            }
+            if !self.eat_symbol(",") {
+                break;
+            }
+        }
+        self.expect_symbol(")")?;
+        Ok(hints)
+    }
+
+    // 索引提示只渲染在主表上，锁提示只出现在部分表上时等价于 FOR UPDATE OF
+    fn apply_table_hints(&mut self, stmt: &mut SelectStatement, hints: Vec<(Table, Vec<String>)>) {
+        if hints.is_empty() {
+            return;
+        }
+        let mut locked = Vec::new();
+        let (mut share, mut wait) = (false, Wait::Default);
+        for (table, hints) in &hints {
+            let mut has_lock = false;
+            for hint in hints {
+                match hint.as_str() {
+                    "UPDLOCK" => has_lock = true,
+                    "HOLDLOCK" => {
+                        has_lock = true;
+                        share = true;
+                    },
+                    "NOWAIT" => wait = Wait::NoWait,
+                    "READPAST" => wait = Wait::SkipLocked,
+                    h => {
+                        if let Some(index) = h.strip_prefix("INDEX:") {
+                            stmt.indexes.push_force(Index::from(index));
+                        }
+                    },
+                }
+            }
+            if has_lock {
+                locked.push(table.clone());
+            }
+        }
+        if locked.is_empty() {
+            return;
+        }
+        let all_locked = self.scopes.last().is_some_and(|scope| {
+            scope
+                .iter()
+                .filter(|t| matches!(*t.inner, TableInner::Named(_)))
+                .all(|t| locked.iter().any(|l| Arc::ptr_eq(&l.inner, &t.inner)))
+        });
+        let mut locking = stmt.locking.take().unwrap_or_else(Locking::new);
+        locking.lock = Some(match (share, all_locked) {
+            (true, _) => Lock::Share,
+            (false, true) => Lock::Update,
+            (false, false) => Lock::UpdateOf(locked),
+        });
+        locking.wait = Some(wait);
+        stmt.locking = Some(locking);
+    }
+
+    fn parse_index_hints(&mut self, stmt: &mut SelectStatement) -> Result<(), ParseError> {
+        loop {
+            if self.eat_keywords(&["INDEXED", "BY"]) {
+                let name = self.ident()?;
+                stmt.indexes.push_force(Index::from(name));
+                continue;
+            }
+            let push: fn(&mut SelectStatement, Index) = if self.eat_keywords(&["FORCE", "INDEX"]) {
+                |stmt, index| stmt.indexes.push_force(index)
+            } else if self.eat_keywords(&["USE", "INDEX"]) {
+                |stmt, index| stmt.indexes.push_use(index)
+            } else if self.eat_keywords(&["IGNORE", "INDEX"]) {
+                |stmt, index| stmt.indexes.push_ignore(index)
+            } else {
+                return Ok(());
+            };
+            self.expect_symbol("(")?;
+            loop {
+                let name = self.ident()?;
+                push(stmt, Index::from(name));
+                if !self.eat_symbol(",") {
+                    break;
+                }
+            }
+            self.expect_symbol(")")?;
+        }
+    }
+
+    fn parse_orders(&mut self, stmt: &mut SelectStatement) -> Result<(), ParseError> {
+        if !self.eat_keywords(&["ORDER", "BY"]) {
+            return Ok(());
+        }
+        // SQL Server 分页时补上的 ORDER BY (SELECT NULL)
+        if self.is_symbol("(") && self.is_keyword_at(1, "SELECT") && self.is_keyword_at(2, "NULL") {
+            self.pos += 3;
+            return self.expect_symbol(")");
+        }
+        let outer = std::mem::replace(&mut self.output_columns, stmt.select_clause.clone());
+        let orders = self.order_list();
+        self.output_columns = outer;
+        stmt.orders = orders?;
+        Ok(())
+    }
+
+    fn order_list(&mut self) -> Result<Vec<Order>, ParseError> {
+        let mut orders = Vec::new();
+        loop {
+            let expr = self.expr()?;
+            if self.eat_keyword("DESC") {
+                orders.push(Order::desc(expr));
+            } else {
+                self.eat_keyword("ASC");
+                orders.push(Order::asc(expr));
+            }
+            if !self.eat_symbol(",") {
+                return Ok(orders);
+            }
+        }
+    }
+
+    // LIMIT n [BY ...] / LIMIT n OFFSET m / OFFSET m ROWS FETCH FIRST n ROWS ONLY
+    fn parse_paging(&mut self, stmt: &mut SelectStatement) -> Result<(), ParseError> {
+        if self.is_keyword("LIMIT") && self.is_keyword_at(2, "BY") {
+            self.advance();
+            let n = self.usize()?;
+            self.expect_keyword("BY")?;
+            stmt.limit_by = Some((n, self.expr_list()?));
+        }
+        if self.eat_keyword("LIMIT") {
+            let n = self.usize()?;
+            if self.eat_symbol(",") {
+                stmt.offset = Some(n);
+                stmt.limit = Some(self.usize()?);
+            } else {
+                stmt.limit = Some(n);
+            }
+        }
+        if self.eat_keyword("OFFSET") {
+            stmt.offset = Some(self.usize()?);
+            if !self.eat_keyword("ROWS") {
+                self.eat_keyword("ROW");
+            }
+        }
+        if self.eat_keyword("FETCH") {
+            if !self.eat_keyword("FIRST") {
+                self.expect_keyword("NEXT")?;
+            }
+            stmt.limit = Some(self.usize()?);
+            if !self.eat_keyword("ROWS") {
+                self.expect_keyword("ROW")?;
+            }
+            self.expect_keyword("ONLY")?;
+        }
+        Ok(())
+    }
+
+    fn parse_locking(&mut self, stmt: SelectStatement) -> Result<SelectStatement, ParseError> {
+        let stmt = if self.eat_keywords(&["FOR", "SHARE"]) || self.eat_keywords(&["LOCK", "IN", "SHARE", "MODE"]) {
+            stmt.for_share()
+        } else if self.eat_keywords(&["FOR", "UPDATE", "OF"]) {
+            self.parse_lock_of(stmt)?
+        } else if self.eat_keywords(&["FOR", "UPDATE"]) {
+            stmt.for_update()
+        } else {
+            return Ok(stmt);
+        };
+        if self.eat_keyword("NOWAIT") {
+            Ok(stmt.no_wait())
+        } else if self.eat_keywords(&["SKIP", "LOCKED"]) {
+            Ok(stmt.skip())
+        } else {
+            Ok(stmt)
+        }
+    }
+
+    // OF 后面是表（别名）或者 Oracle 的列
+    fn parse_lock_of(&mut self, stmt: SelectStatement) -> Result<SelectStatement, ParseError> {
+        let mut tables = Vec::new();
+        let mut columns = Vec::new();
+        loop {
+            let offset = self.peek().offset;
+            let quoted = matches!(self.peek().kind, TokenKind::Quoted(_));
+            let name = self.ident()?;
+            if self.eat_symbol(".") {
+                let table = self.resolve_table(&name, quoted, offset)?;
+                columns.push(table.column(self.ident()?));
+            } else {
+                tables.push(self.resolve_table(&name, quoted, offset)?);
+            }
+            if !self.eat_symbol(",") {
+                break;
+            }
+        }
+        Ok(if columns.is_empty() {
+            stmt.for_update_of(tables)
+        } else {
+            stmt.for_update_of_columns(columns)
+        })
+    }
+
+    // SETTINGS max_threads = 8, ...
+    fn parse_settings(&mut self, stmt: &mut SelectStatement) -> Result<(), ParseError> {
+        if !self.eat_keyword("SETTINGS") {
+            return Ok(());
+        }
+        loop {
+            let name = self.word()?;
+            self.expect_symbol("=")?;
+            let value = match self.primary()? {
+                Expr::Param(p) => p,
+                _ => return Err(self.unexpected_prev("a literal setting value")),
+            };
+            stmt.settings.push((name, lit(value)));
+            if !self.eat_symbol(",") {
+                return Ok(());
+            }
+        }
+    }
+
+    fn select_item(&mut self) -> Result<SelectItem, ParseError> {
+        let expr = self.expr()?;
+        Ok(match self.alias()? {
+            Some(alias) => expr.alias(alias),
+            None => SelectItem::from(expr),
+        })
+    }
+
+    // [AS] alias，不带 AS 时只接受非关键字
+    fn alias(&mut self) -> Result<Option<String>, ParseError> {
+        if self.eat_keyword("AS") {
+            return self.ident().map(Some);
+        }
+        match &self.peek().kind {
+            TokenKind::Quoted(_) => self.ident().map(Some),
+            TokenKind::Word(w) if !is_reserved(w) => self.ident().map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn window(&mut self) -> Result<Window, ParseError> {
+        self.expect_symbol("(")?;
+        let mut window = Window::new();
+        let is_clause = |p: &Self| ["PARTITION", "ORDER", "ROWS", "RANGE", "GROUPS"].iter().any(|k| p.is_keyword(k));
+        if !self.is_symbol(")") && !is_clause(self) {
+            window.name = Some(self.ident()?);
+        }
+        if self.eat_keywords(&["PARTITION", "BY"]) {
+            window.partition_by = self.expr_list()?;
+        }
+        if self.eat_keywords(&["ORDER", "BY"]) {
+            window.orders = self.order_list()?;
+        }
+        let unit = if self.eat_keyword("ROWS") {
+            Some(FrameUnit::Rows)
+        } else if self.eat_keyword("RANGE") {
+            Some(FrameUnit::Range)
+        } else if self.eat_keyword("GROUPS") {
+            Some(FrameUnit::Groups)
+        } else {
+            None
+        };
+        if let Some(unit) = unit {
+            let (start, end) = if self.eat_keyword("BETWEEN") {
+                let start = self.frame_bound()?;
+                self.expect_keyword("AND")?;
+                (start, Some(self.frame_bound()?))
+            } else {
+                (self.frame_bound()?, None)
+            };
+            window.frame = Some(Frame { unit, start, end });
+        }
+        self.expect_symbol(")")?;
+        Ok(window)
+    }
+
+    fn frame_bound(&mut self) -> Result<FrameBound, ParseError> {
+        if self.eat_keywords(&["UNBOUNDED", "PRECEDING"]) {
+            Ok(FrameBound::UnboundedPreceding)
+        } else if self.eat_keywords(&["UNBOUNDED", "FOLLOWING"]) {
+            Ok(FrameBound::UnboundedFollowing)
+        } else if self.eat_keywords(&["CURRENT", "ROW"]) {
+            Ok(FrameBound::CurrentRow)
+        } else {
+            let n = self.usize()?;
+            if self.eat_keyword("PRECEDING") {
+                Ok(FrameBound::Preceding(n))
+            } else {
+                self.expect_keyword("FOLLOWING")?;
+                Ok(FrameBound::Following(n))
+            }
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // 表达式，优先级由低到高：OR、AND、NOT、比较、加减、乘除、一元正负、::
+    // ------------------------------------------------------------------
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.and_expr()?;
+        while self.eat_keyword("OR") {
+            left = binary(left, BinaryOp::Or, self.and_expr()?);
+        }
+        Ok(left)
+    }
+
+    fn and_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.not_expr()?;
+        while self.eat_keyword("AND") {
+            left = binary(left, BinaryOp::And, self.not_expr()?);
+        }
+        Ok(left)
+    }
+
+    fn not_expr(&mut self) -> Result<Expr, ParseError> {
+        if self.eat_keyword("NOT") {
+            return Ok(!self.not_expr()?);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.additive()?;
+        loop {
+            let op = match &self.peek().kind {
+                TokenKind::Symbol("=") => BinaryOp::Eq,
+                TokenKind::Symbol("<>") | TokenKind::Symbol("!=") => BinaryOp::NotEq,
+                TokenKind::Symbol("<") => BinaryOp::Lt,
+                TokenKind::Symbol("<=") => BinaryOp::Lte,
+                TokenKind::Symbol(">") => BinaryOp::Gt,
+                TokenKind::Symbol(">=") => BinaryOp::Gte,
+                _ => {
+                    if self.eat_keywords(&["IS", "NOT"]) {
+                        left = binary(left, BinaryOp::IsNot, self.additive()?);
+                        continue;
+                    }
+                    if self.eat_keyword("IS") {
+                        left = binary(left, BinaryOp::Is, self.additive()?);
+                        continue;
+                    }
+                    let negated = self.is_keyword("NOT")
+                        && ["LIKE", "IN", "BETWEEN"].iter().any(|k| self.is_keyword_at(1, k));
+                    if negated {
+                        self.advance();
+                    }
+                    if self.eat_keyword("LIKE") {
+                        let op = if negated { BinaryOp::NotLike } else { BinaryOp::Like };
+                        left = binary(left, op, self.additive()?);
+                    } else if self.eat_keyword("IN") {
+                        // x IN (SELECT ...) 与 x IN ((SELECT ...)) 相同
+                        let list = if self.is_subquery_start() {
+                            vec![Expr::from(self.parse_subquery()?)]
+                        } else {
+                            self.expect_symbol("(")?;
+                            let list = self.expr_list()?;
+                            self.expect_symbol(")")?;
+                            list
+                        };
+                        left = Expr::In {
+                            expr: Box::new(left),
+                            list,
+                            negated,
+                        };
+                    } else if self.eat_keyword("BETWEEN") {
+                        let low = self.additive()?;
+                        self.expect_keyword("AND")?;
+                        let high = self.additive()?;
+                        left = Expr::Between {
+                            expr: Box::new(left),
+                            low: Box::new(low),
+                            high: Box::new(high),
+                            negated,
+                        };
+                    } else {
+                        return Ok(left);
+                    }
+                    continue;
+                },
+            };
+            self.advance();
+            left = binary(left, op, self.additive()?);
+        }
+    }
+
+    fn additive(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let op = if self.eat_symbol("+") {
+                BinaryOp::Add
+            } else if self.eat_symbol("-") {
+                BinaryOp::Sub
+            } else {
+                return Ok(left);
+            };
+            left = binary(left, op, self.multiplicative()?);
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.unary()?;
+        loop {
+            let op = if self.eat_symbol("*") {
+                BinaryOp::Mul
+            } else if self.eat_symbol("/") {
+                BinaryOp::Div
+            } else if self.eat_symbol("%") {
+                BinaryOp::Rem
+            } else {
+                return Ok(left);
+            };
+            left = binary(left, op, self.unary()?);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        let op = if self.is_symbol("-") {
+            UnaryOp::Neg
+        } else if self.is_symbol("+") {
+            UnaryOp::Pos
+        } else {
+            return self.postfix();
+        };
+        let sign = self.advance();
+        // 紧跟数字的负号属于字面量本身，e.g. -5
+        if let TokenKind::Number(n) = &self.peek().kind
+            && op == UnaryOp::Neg
+            && self.peek().offset == sign.offset + 1
+        {
+            let n = format!("-{}", n);
+            self.advance();
+            return Ok(Expr::Param(number(&n)));
+        }
+        Ok(Expr::Unary {
+            op,
+            expr: Box::new(self.unary()?),
+        })
+    }
+
+    fn postfix(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.primary()?;
+        while self.eat_symbol("::") {
+            let column_type = self.column_type(false)?;
+            expr = match (&expr, &column_type) {
+                // PostgreSQL 与 DuckDB 的二进制字面量，e.g. '\x01ab'::bytea
+                (Expr::Param(Param::Literal(ParamData::String(s))), ColumnType::Blob) if s.starts_with("\\x") => {
+                    match decode_hex(&s.replace("\\x", "")) {
+                        Some(bytes) => Expr::Param(lit(bytes)),
+                        None => cast(expr, column_type),
+                    }
+                },
+                _ => cast(expr, column_type),
+            };
+        }
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        let token = self.peek().clone();
+        match token.kind {
+            TokenKind::Number(n) => {
+                self.advance();
+                Ok(Expr::Param(number(&n)))
+            },
+            TokenKind::String(s) => {
+                self.advance();
+                Ok(Expr::Param(lit(s)))
+            },
+            TokenKind::Hex(bytes) => {
+                self.advance();
+                Ok(Expr::Param(lit(bytes)))
+            },
+            TokenKind::Placeholder(n) => {
+                self.advance();
+                self.placeholder(n, token.offset).map(Expr::Param)
+            },
+            TokenKind::NamedPlaceholder(name) => {
+                self.advance();
+                self.named_placeholder(name, token.offset).map(Expr::Param)
+            },
+            TokenKind::Symbol("(") if self.is_subquery_start() => Ok(Expr::from(self.parse_subquery()?)),
+            TokenKind::Symbol("(") => {
+                self.advance();
+                let expr = self.expr()?;
+                self.expect_symbol(")")?;
+                Ok(expr)
+            },
+            TokenKind::Word(w) if w.eq_ignore_ascii_case("NULL") => {
+                self.advance();
+                Ok(Expr::Param(Param::Null))
+            },
+            TokenKind::Word(w) if w.eq_ignore_ascii_case("TRUE") || w.eq_ignore_ascii_case("FALSE") => {
+                self.advance();
+                Ok(Expr::Param(lit(w.eq_ignore_ascii_case("TRUE"))))
+            },
+            TokenKind::Word(w) if w.eq_ignore_ascii_case("CASE") => {
+                self.advance();
+                self.case().map(Expr::Case)
+            },
+            TokenKind::Word(w) if w.eq_ignore_ascii_case("CAST") && self.is_symbol_at(1, "(") => {
+                self.pos += 2;
+                let expr = self.expr()?;
+                self.expect_keyword("AS")?;
+                let column_type = self.column_type(true)?;
+                self.expect_symbol(")")?;
+                Ok(cast(expr, column_type))
+            },
+            TokenKind::Word(name) if self.is_symbol_at(1, "(") && !is_reserved(&name) => {
+                self.pos += 2;
+                self.function(name)
+            },
+            TokenKind::Word(_) | TokenKind::Quoted(_) => self.column_ref(),
+            _ => Err(self.unexpected("an expression")),
+        }
+    }
+
+    fn placeholder(&mut self, n: Option<usize>, offset: usize) -> Result<Param, ParseError> {
+        let index = match n {
+            Some(n) => n.wrapping_sub(1),
+            None => {
+                self.next_param += 1;
+                self.next_param - 1
+            },
+        };
+        match self.params.get(index) {
+            Some(data) => Ok(Param::Value(data.clone())),
+            None => Err(ParseError::MissingParam {
+                placeholder: n.map_or("?".to_string(), |n| n.to_string()),
+                position: Position::of(self.sql, offset),
+            }),
+        }
+    }
+
+    // 参数按名字第一次出现的顺序排列，_p1 等为渲染时生成的未命名参数
+    fn named_placeholder(&mut self, name: String, offset: usize) -> Result<Param, ParseError> {
+        let index = match self.named_params.get(&name) {
+            Some(&index) => index,
+            None => {
+                self.next_param += 1;
+                self.named_params.insert(name.clone(), self.next_param - 1);
+                self.next_param - 1
+            },
+        };
+        match self.params.get(index) {
+            Some(data) if is_unnamed_param(&name) => Ok(Param::Value(data.clone())),
+            Some(data) => Ok(Param::Named(name, data.clone())),
+            None => Err(ParseError::MissingParam {
+                placeholder: name,
+                position: Position::of(self.sql, offset),
+            }),
+        }
+    }
+
+    // FUNC([DISTINCT] args) [OVER w | OVER (...)]，名字之后的左括号已经被消费
+    fn function(&mut self, name: String) -> Result<Expr, ParseError> {
+        // Oracle 的二进制字面量 HEXTORAW('01ab')
+        if name.eq_ignore_ascii_case("HEXTORAW")
+            && let (TokenKind::String(hex), TokenKind::Symbol(")")) = (&self.peek().kind, &self.peek_at(1).kind)
+            && let Some(bytes) = decode_hex(hex)
+        {
+            self.pos += 2;
+            return Ok(Expr::Param(lit(bytes)));
+        }
+        let distinct = self.eat_keyword("DISTINCT");
+        let mut args: Vec<FuncArg> = Vec::new();
+        // EXISTS (SELECT ...)，子查询的左括号就是函数的左括号
+        if self.is_keyword("SELECT") || self.is_keyword("WITH") {
+            args.push(FuncArg::from(Expr::from(self.parse_query()?)));
+        } else if self.eat_symbol("*") {
+            args.push(FuncArg::Wildcard);
+        } else if !self.is_symbol(")") {
+            // PostgreSQL 的多列 COUNT(DISTINCT (a, b))
+            if let Some(tuple) = self.try_tuple(distinct)? {
+                args.extend(tuple.into_iter().map(FuncArg::from));
+            } else {
+                args.extend(self.expr_list()?.into_iter().map(FuncArg::from));
+            }
+        }
+        self.expect_symbol(")")?;
+        let mut f = func(name, args);
+        f.distinct = distinct;
+        if self.eat_keyword("OVER") {
+            f = if self.is_symbol("(") {
+                f.over(self.window()?)
+            } else {
+                f.over_window(self.ident()?)
+            };
+        }
+        Ok(Expr::Func(f))
+    }
+
+    fn try_tuple(&mut self, distinct: bool) -> Result<Option<Vec<Expr>>, ParseError> {
+        if !distinct || !self.is_symbol("(") || self.is_subquery_start() {
+            return Ok(None);
+        }
+        let start = self.pos;
+        self.advance();
+        let list = self.expr_list()?;
+        if list.len() > 1 && self.eat_symbol(")") && self.is_symbol(")") {
+            return Ok(Some(list));
+        }
+        self.pos = start;
+        Ok(None)
+    }
+
+    // CASE 已经被消费
+    fn case(&mut self) -> Result<Case, ParseError> {
+        let mut c = if self.is_keyword("WHEN") { case() } else { case_of(self.expr()?) };
+        while self.eat_keyword("WHEN") {
+            let condition = self.expr()?;
+            self.expect_keyword("THEN")?;
+            c = c.when(condition, self.expr()?);
+        }
+        if c.conditions.is_empty() {
+            return Err(self.unexpected("WHEN"));
+        }
+        if self.eat_keyword("ELSE") {
+            c = c.otherwise(self.expr()?);
+        }
+        self.expect_keyword("END")?;
+        Ok(c)
+    }
+
+    // 把类型名交给方言反查，e.g. PostgreSQL 的 NUMERIC(10, 2) -> Decimal
+    // 在 CAST(... AS T) 中类型可以由多个单词组成，:: 之后遇到关键字即结束
+    fn column_type(&mut self, in_cast: bool) -> Result<ColumnType, ParseError> {
+        let offset = self.peek().offset;
+        let mut words = vec![self.word()?];
+        while let TokenKind::Word(w) = &self.peek().kind {
+            if !in_cast && is_reserved(w) {
+                break;
+            }
+            words.push(w.clone());
+            self.advance();
+        }
+        let mut args = Vec::new();
+        if self.eat_symbol("(") {
+            loop {
+                match self.advance().kind {
+                    TokenKind::Number(n) | TokenKind::Word(n) => args.push(n),
+                    _ => return Err(self.unexpected_prev("a type argument")),
+                }
+                if !self.eat_symbol(",") {
+                    break;
+                }
+            }
+            self.expect_symbol(")")?;
+        }
+        let name = if args.is_empty() {
+            words.join(" ")
+        } else {
+            format!("{}({})", words.join(" "), args.join(", "))
+        };
+        let numbers: Vec<usize> = args.iter().filter_map(|a| a.parse().ok()).collect();
+        let (first, second) = (numbers.first().copied(), numbers.get(1).copied());
+        let candidates = [
+            ColumnType::Char { max_length: first },
+            ColumnType::Char { max_length: None },
+            ColumnType::Text,
+            ColumnType::Int,
+            ColumnType::BigInt,
+            ColumnType::SmallInt,
+            ColumnType::UnsignedInt,
+            ColumnType::UnsignedSmallInt,
+            ColumnType::Float,
+            ColumnType::Double,
+            ColumnType::Decimal {
+                precision: first,
+                scale: second,
+            },
+            ColumnType::Bool,
+            ColumnType::Date,
+            ColumnType::DateTime,
+            ColumnType::Time,
+            ColumnType::Json,
+            ColumnType::Uuid,
+            ColumnType::Blob,
+        ];
+        candidates
+            .into_iter()
+            .find(|t| self.dialect.cast_type(t).is_ok_and(|n| n.eq_ignore_ascii_case(&name)))
+            .ok_or_else(|| ParseError::Unsupported {
+                what: format!("CAST to {}", name),
+                position: Position::of(self.sql, offset),
+            })
+    }
+
+    // t.col 或 col，不带表名时先匹配 ORDER BY 可见的 SELECT 别名，再属于当前 FROM 中唯一的表
+    fn column_ref(&mut self) -> Result<Expr, ParseError> {
+        let offset = self.peek().offset;
+        let quoted = matches!(self.peek().kind, TokenKind::Quoted(_));
+        let first = self.ident()?;
+        if self.eat_symbol(".") {
+            let second = self.ident()?;
+            if !self.is_symbol(".") {
+                let table = self.resolve_table(&first, quoted, offset)?;
+                return Ok(Expr::Column(table.column(second)));
+            }
+            // schema.table.col，对应不在 FROM 中的表
+            let mut parts = vec![first, second];
+            while self.eat_symbol(".") {
+                parts.push(self.ident()?);
+            }
+            let column = parts.pop().unwrap();
+            if parts.len() > 3 {
+                return Err(self.unexpected("a column reference"));
+            }
+            let mut name = TableName::new(parts.pop().unwrap());
+            name.schema = parts.pop();
+            name.catalog = parts.pop();
+            return Ok(Expr::Column(self.qualified_table(name).column(column)));
+        }
+        if let Some(expr) = self.output_column(&first, quoted) {
+            return Ok(expr);
+        }
+        match self.scopes.iter().rev().find(|scope| !scope.is_empty()).map(Vec::as_slice) {
+            Some([table]) => Ok(Expr::Column(table.column(first))),
+            Some(_) => Err(ParseError::AmbiguousColumn {
+                name: first,
+                position: Position::of(self.sql, offset),
+            }),
+            None => Err(ParseError::UnknownTable {
+                name: format!("of column {}", first),
+                position: Position::of(self.sql, offset),
+            }),
+        }
+    }
+
+    // SELECT 列表中同名的别名或列，别名按不属于任何表的列输出
+    fn output_column(&self, name: &str, quoted: bool) -> Option<Expr> {
+        self.output_columns.iter().find_map(|item| match (&item.alias, &item.expr) {
+            (Some(alias), _) if same_ident(alias, name, quoted) => {
+                Some(Expr::Column(Table::new(alias.clone()).column(alias.clone())))
+            },
+            (None, Expr::Column(col)) if same_ident(&col.name, name, quoted) => Some(item.expr.clone()),
+            _ => None,
+        })
+    }
+
+    // [catalog.][schema.]table
+    fn table_name(&mut self) -> Result<TableName, ParseError> {
+        let mut parts = vec![self.ident()?];
+        while parts.len() < 3 && self.eat_symbol(".") {
+            parts.push(self.ident()?);
+        }
+        let mut name = TableName::new(parts.pop().unwrap());
+        name.schema = parts.pop();
+        name.catalog = parts.pop();
+        Ok(name)
+    }
+
+    // 同名的完整表名共用一个 Table，保持列之间的表身份一致
+    fn qualified_table(&mut self, name: TableName) -> Table {
+        let found = self.scopes.iter().rev().flatten().chain(&self.qualified).find(|t| {
+            matches!(t.inner.as_ref(), TableInner::Named(n) if *n == name)
+        });
+        if let Some(table) = found {
+            return table.clone();
+        }
+        let table = Table::from(name);
+        self.qualified.push(table.clone());
+        table
+    }
+
+    fn resolve_table(&self, name: &str, quoted: bool, offset: usize) -> Result<Table, ParseError> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.iter().find(|t| same_ident(&t.visible_name(), name, quoted)))
+            .cloned()
+            .ok_or_else(|| ParseError::UnknownTable {
+                name: name.to_string(),
+                position: Position::of(self.sql, offset),
+            })
+    }
+
+    fn expr_list(&mut self) -> Result<Vec<Expr>, ParseError> {
+        let mut list = vec![self.expr()?];
+        while self.eat_symbol(",") {
+            list.push(self.expr()?);
+        }
+        Ok(list)
+    }
+
+    // ------------------------------------------------------------------
+    // token 辅助方法
+    // ------------------------------------------------------------------
+
+    fn peek(&self) -> &Token {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, n: usize) -> &Token {
+        let last = self.tokens.len() - 1;
+        &self.tokens[(self.pos + n).min(last)]
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.peek().clone();
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.is_keyword_at(0, keyword)
+    }
+
+    fn is_keyword_at(&self, n: usize, keyword: &str) -> bool {
+        matches!(&self.peek_at(n).kind, TokenKind::Word(w) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.eat_keywords(&[keyword])
+    }
+
+    // 整组匹配才消费，e.g. ["UNION", "ALL"]
+    fn eat_keywords(&mut self, keywords: &[&str]) -> bool {
+        if keywords.iter().enumerate().all(|(i, k)| self.is_keyword_at(i, k)) {
+            self.pos += keywords.len();
+            return true;
+        }
+        false
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        if self.eat_keyword(keyword) {
+            return Ok(());
+        }
+        Err(self.unexpected(keyword))
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        self.is_symbol_at(0, symbol)
+    }
+
+    fn is_symbol_at(&self, n: usize, symbol: &str) -> bool {
+        matches!(self.peek_at(n).kind, TokenKind::Symbol(s) if s == symbol)
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        if self.is_symbol(symbol) {
+            self.advance();
+            return true;
+        }
+        false
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), ParseError> {
+        if self.eat_symbol(symbol) {
+            return Ok(());
+        }
+        Err(self.unexpected(&format!("'{}'", symbol)))
+    }
+
+    fn expect_eof(&self) -> Result<(), ParseError> {
+        match self.peek().kind {
+            TokenKind::Eof => Ok(()),
+            _ => Err(self.unexpected("end of input")),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, ParseError> {
+        match self.peek().kind.clone() {
+            TokenKind::Quoted(name) | TokenKind::Word(name) => {
+                self.advance();
+                Ok(name)
+            },
+            _ => Err(self.unexpected("an identifier")),
+        }
+    }
+
+    fn word(&mut self) -> Result<String, ParseError> {
+        match self.peek().kind.clone() {
+            TokenKind::Word(w) => {
+                self.advance();
+                Ok(w)
+            },
+            _ => Err(self.unexpected("a keyword")),
+        }
+    }
+
+    fn usize(&mut self) -> Result<usize, ParseError> {
+        if let TokenKind::Number(n) = &self.peek().kind
+            && let Ok(n) = n.parse()
+        {
+            self.advance();
+            return Ok(n);
+        }
+        Err(self.unexpected("a non-negative integer"))
+    }
+
+    fn unexpected(&self, expected: &str) -> ParseError {
+        let token = self.peek();
+        ParseError::Unexpected {
+            expected: expected.to_string(),
+            found: token.kind.to_string(),
+            position: Position::of(self.sql, token.offset),
+        }
+    }
+
+    fn unexpected_prev(&mut self, expected: &str) -> ParseError {
+        self.pos = self.pos.saturating_sub(1);
+        self.unexpected(expected)
+    }
+}
+
+fn binary(left: Expr, op: BinaryOp, right: Expr) -> Expr {
+    Expr::Binary {
+        left: Box::new(left),
+        op,
+        right: Box::new(right),
+    }
+}
+
+// WHERE a AND b 拆成多个 filter
+fn conjuncts(expr: Expr) -> Vec<Expr> {
+    match expr {
+        Expr::Binary {
+            left,
+            op: BinaryOp::And,
+            right,
+        } => {
+            let mut list = conjuncts(*left);
+            list.extend(conjuncts(*right));
+            list
+        },
+        expr => vec![expr],
+    }
+}
+
+fn number(n: &str) -> Param {
+    match n.parse::<i64>() {
+        Ok(v) => lit(v),
+        Err(_) => lit(n.parse::<f64>().unwrap_or(f64::NAN)),
+    }
+}
+
+// 未加引号的标识符不区分大小写
+fn same_ident(name: &str, reference: &str, quoted: bool) -> bool {
+    if quoted { name == reference } else { name.eq_ignore_ascii_case(reference) }
+}
+
+fn is_reserved(word: &str) -> bool {
+    RESERVED.iter().any(|k| k.eq_ignore_ascii_case(word))
+}
+
+#[cfg(test)]
+#[path = "./parser_test.rs"]
+mod tests;
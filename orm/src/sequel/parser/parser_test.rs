@@ -0,0 +1,186 @@
+use crate::sequel::parser::error::{ParseError, Position};
+use crate::sequel::parser::parser::{Parser, parse_select};
+use crate::sequel::term::expr::Expr;
+use crate::sequel::term::param::ParamData;
+use crate::sequel::term::table::Table;
+use crate::sequel::visitor::dialect::mssql::MsSql;
+use crate::sequel::visitor::dialect::mysql::MySQL;
+use crate::sequel::visitor::dialect::oracle::Oracle;
+use crate::sequel::visitor::dialect::postgre::PostgreSQL;
+use crate::sequel::visitor::dialect::sqlite::SQLite;
+use crate::sequel::visitor::visitor::{mssql, mysql, oracle, postgre, sqlite};
+
+#[test]
+fn test_parse__select_with_join_and_filters() {
+    let sql = "SELECT u.id, COUNT(o.id) AS cnt FROM users AS u LEFT JOIN orders o ON o.user_id = u.id \
+               WHERE u.age >= ? AND (u.name LIKE ? OR u.name IS NULL) GROUP BY u.id ORDER BY cnt DESC LIMIT 10";
+    let stmt = parse_select(&MySQL {}, sql, vec![ParamData::Int(18), ParamData::String("a%".to_string())]).unwrap();
+    assert_eq!(stmt.where_clause.len(), 2);
+    assert_eq!(stmt.limit, Some(10));
+    let (sql, params, _) = mysql().visit_select_statement(&stmt).finish();
+    assert_eq!(
+        sql,
+        "SELECT `u`.`id`, COUNT(`o`.`id`) AS `cnt` FROM `users` AS `u` LEFT JOIN `orders` AS `o` ON `o`.`user_id` = `u`.`id` \
+         WHERE `u`.`age` >= ? AND (`u`.`name` LIKE ? OR `u`.`name` IS NULL) GROUP BY `u`.`id` ORDER BY `cnt` DESC LIMIT 10"
+    );
+    assert_eq!(params, vec![ParamData::Int(18), ParamData::String("a%".to_string())]);
+}
+
+#[test]
+fn test_parse__placeholder_styles() {
+    let params = || vec![ParamData::Int(1), ParamData::Int(2)];
+
+    // 带序号的占位符按序号取值，可以重复引用
+    let stmt = parse_select(&PostgreSQL {}, r#"SELECT * FROM "users" WHERE "id" = $2 OR "id" = $1"#, params()).unwrap();
+    let (sql, binds, _) = postgre().visit_select_statement(&stmt).finish();
+    assert_eq!(sql, r#"SELECT * FROM "users" AS "users0" WHERE "users0"."id" = $1 OR "users0"."id" = $2"#);
+    assert_eq!(binds, vec![ParamData::Int(2), ParamData::Int(1)]);
+
+    let stmt = parse_select(&MsSql {}, "SELECT * FROM [users] WHERE [id] = @p1 AND [age] > @p2", params()).unwrap();
+    let (sql, binds, _) = mssql().visit_select_statement(&stmt).finish();
+    assert_eq!(sql, "SELECT * FROM [users] AS [users0] WHERE [users0].[id] = @p1 AND [users0].[age] > @p2");
+    assert_eq!(binds, params());
+
+    let stmt = parse_select(&Oracle {}, r#"SELECT * FROM "USERS" WHERE "ID" = :1 AND "AGE" > :2"#, params()).unwrap();
+    let (sql, binds, _) = oracle().visit_select_statement(&stmt).finish();
+    assert_eq!(sql, r#"SELECT * FROM "USERS" "USERS0" WHERE "USERS0"."ID" = :1 AND "USERS0"."AGE" > :2"#);
+    assert_eq!(binds, params());
+}
+
+#[test]
+fn test_parse__quoted_identifier_escapes() {
+    let stmt = parse_select(&MsSql {}, "SELECT [a]]b] FROM [t]", vec![]).unwrap();
+    assert!(format!("{:?}", stmt.select_clause).contains(r#"name: "a]b""#));
+
+    let stmt = parse_select(&SQLite {}, r#"SELECT "x", 'it''s' FROM "t""#, vec![]).unwrap();
+    let (sql, _, _) = sqlite().visit_select_statement(&stmt).finish();
+    assert_eq!(sql, r#"SELECT "t0"."x", 'it''s' FROM "t" AS "t0""#);
+
+    // 只有 MySQL 等方言把反斜杠当作转义符
+    let stmt = parse_select(&MySQL {}, r"SELECT 'C:\dir' FROM t", vec![]).unwrap();
+    assert!(format!("{:?}", stmt.select_clause).contains(r#"String("C:\\dir")"#));
+    let stmt = parse_select(&PostgreSQL {}, r"SELECT 'C:\dir' FROM t", vec![]).unwrap();
+    let (sql, _, _) = postgre().visit_select_statement(&stmt).finish();
+    assert_eq!(sql, r#"SELECT 'C:\dir' FROM "t" AS "t0""#);
+}
+
+#[test]
+fn test_parse__expr() {
+    let users = Table::new("users");
+    let expr = Parser::new(&MySQL {}, "age BETWEEN 18 AND ? AND NOT name IN ('a', 'b')")
+        .params(vec![ParamData::Int(60)])
+        .table(&users)
+        .parse_expr()
+        .unwrap();
+    let Expr::Binary { left, right, .. } = expr else { panic!("expected AND") };
+    assert!(matches!(*left, Expr::Between { negated: false, .. }));
+    assert!(matches!(*right, Expr::Unary { .. }));
+}
+
+#[test]
+fn test_parse__error_position() {
+    let err = parse_select(&MySQL {}, "SELECT id\nFROM users\nWHERE id = = 1", vec![]).unwrap_err();
+    assert_eq!(err.position(), Position { offset: 32, line: 3, column: 12 });
+    assert_eq!(err.to_string(), "ParseError: expected an expression, found '=' at line 3, column 12");
+
+    let err = parse_select(&MySQL {}, "SELECT id FROM users LIMIT", vec![]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "ParseError: expected a non-negative integer, found end of input at line 1, column 27"
+    );
+
+    let err = parse_select(&MySQL {}, "SELECT 'abc FROM users", vec![]).unwrap_err();
+    assert!(matches!(err, ParseError::Unexpected { .. }));
+}
+
+#[test]
+fn test_parse__unknown_table() {
+    let err = parse_select(&MySQL {}, "SELECT o.id FROM users AS u", vec![]).unwrap_err();
+    assert_eq!(
+        err,
+        ParseError::UnknownTable {
+            name: "o".to_string(),
+            position: Position { offset: 7, line: 1, column: 8 }
+        }
+    );
+}
+
+#[test]
+fn test_parse__missing_param() {
+    let sql = "SELECT * FROM users WHERE id = ? AND age > ?";
+    let err = parse_select(&MySQL {}, sql, vec![ParamData::Int(1)]).unwrap_err();
+    assert_eq!(err.to_string(), "ParseError: no value bound to placeholder ? at line 1, column 44");
+
+    let err = parse_select(&PostgreSQL {}, "SELECT * FROM users WHERE id = $3", vec![]).unwrap_err();
+    assert!(matches!(err, ParseError::MissingParam { ref placeholder, .. } if placeholder == "3"));
+}
+
+#[test]
+fn test_parse__unsupported_cast() {
+    let err = parse_select(&PostgreSQL {}, "SELECT CAST(id AS GEOMETRY) FROM users", vec![]).unwrap_err();
+    assert!(matches!(err, ParseError::Unsupported { .. }));
+    assert_eq!(err.position().column, 19);
+}
+
+#[test]
+fn test_parse__intersect_precedence() {
+    // 按标准 SQL 解析：a UNION b INTERSECT c => a UNION (b INTERSECT c)
+    let sql = "SELECT id FROM a UNION SELECT id FROM b INTERSECT SELECT id FROM c";
+    let stmt = parse_select(&PostgreSQL {}, sql, vec![]).unwrap();
+    let (sql, _, _) = postgre().visit_select_statement(&stmt).finish();
+    assert_eq!(
+        sql,
+        r#"SELECT "a0"."id" FROM "a" AS "a0" UNION (SELECT "b0"."id" FROM "b" AS "b0" INTERSECT SELECT "c0"."id" FROM "c" AS "c0")"#
+    );
+}
+
+#[test]
+fn test_parse__unqualified_column() {
+    // 多张表时不带表名的列无法确定所属的表
+    let sql = "SELECT total FROM users u JOIN orders o ON o.user_id = u.id WHERE total > 10";
+    let err = parse_select(&MySQL {}, sql, vec![]).unwrap_err();
+    assert_eq!(
+        err,
+        ParseError::AmbiguousColumn {
+            name: "total".to_string(),
+            position: Position { offset: 7, line: 1, column: 8 }
+        }
+    );
+
+    // ORDER BY 先匹配 SELECT 列表中的别名和列名
+    let sql = "SELECT u.id, o.total AS amount FROM users u JOIN orders o ON o.user_id = u.id ORDER BY amount, id";
+    let stmt = parse_select(&MySQL {}, sql, vec![]).unwrap();
+    let (sql, _, _) = mysql().visit_select_statement(&stmt).finish();
+    assert_eq!(
+        sql,
+        "SELECT `u`.`id`, `o`.`total` AS `amount` FROM `users` AS `u` INNER JOIN `orders` AS `o` ON `o`.`user_id` = `u`.`id` ORDER BY `amount`, `u`.`id`"
+    );
+
+    // 未加引号的表名和别名不区分大小写
+    let stmt = parse_select(&MySQL {}, "SELECT id FROM users u WHERE U.id = 1", vec![]).unwrap();
+    let (sql, _, _) = mysql().visit_select_statement(&stmt).finish();
+    assert_eq!(sql, "SELECT `u`.`id` FROM `users` AS `u` WHERE `u`.`id` = 1");
+    let err = parse_select(&PostgreSQL {}, r#"SELECT "U".id FROM users u"#, vec![]).unwrap_err();
+    assert!(matches!(err, ParseError::UnknownTable { ref name, .. } if name == "U"));
+}
+
+#[test]
+fn test_parse__in_and_exists_subquery() {
+    let sql = "SELECT id FROM users WHERE id IN (SELECT user_id FROM orders) \
+               AND NOT EXISTS (SELECT 1 FROM bans WHERE bans.user_id = users.id)";
+    let stmt = parse_select(&PostgreSQL {}, sql, vec![]).unwrap();
+    let (sql, _, _) = postgre().visit_select_statement(&stmt).finish();
+    assert_eq!(
+        sql,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."id" IN ((SELECT "orders0"."user_id" FROM "orders" AS "orders0")) AND NOT EXISTS((SELECT 1 FROM "bans" AS "bans0" WHERE "bans0"."user_id" = "users0"."id"))"#
+    );
+}
+
+#[test]
+fn test_parse__sample_overflow() {
+    let err = parse_select(&MySQL {}, "SELECT id FROM users SAMPLE 99999999999999999999999", vec![]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "ParseError: expected a sample ratio or row count, found 99999999999999999999999 at line 1, column 29"
+    );
+}
@@ -0,0 +1,80 @@
+use crate::sequel::term::expr::Expr;
+use crate::sequel::term::order::Order;
+use crate::sequel::term::select_item::SelectItem;
+use crate::sequel::term::table::Table;
+use utils::into_vec::IntoVec;
+
+#[derive(Clone, Debug)]
+pub struct DeleteStatement {
+    pub table: Table,
+    pub joins: Vec<(Table, Expr)>,
+    pub where_clause: Vec<Expr>,
+    pub orders: Vec<Order>,
+    pub limit: Option<usize>,
+    pub returning: Vec<SelectItem>,
+}
+
+impl DeleteStatement {
+    pub fn from<T>(t: &T) -> Self
+    where
+        T: Clone + Into<Table>,
+    {
+        Self {
+            table: t.clone().into(),
+            joins: Vec::new(),
+            where_clause: Vec::new(),
+            orders: Vec::new(),
+            limit: None,
+            returning: Vec::new(),
+        }
+    }
+
+    // 多表删除：PostgreSQL 渲染为 DELETE ... USING，MySQL 渲染为 DELETE t FROM t JOIN ...，
+    // SQLite 改写为 WHERE EXISTS (相关子查询)
+    pub fn join<T>(mut self, other: &T, on: Expr) -> Self
+    where
+        T: Clone + Into<Table>,
+    {
+        self.joins.push((other.clone().into(), on));
+        self
+    }
+
+    pub fn filter<T>(mut self, c: T) -> Self
+    where
+        T: Into<Expr> + Clone,
+    {
+        self.where_clause.push(c.into());
+        self
+    }
+
+    pub fn order_by(mut self, cs: impl IntoVec<Expr>) -> Self {
+        for item in cs.into_vec() {
+            self.orders.push(Order::asc(item));
+        }
+        self
+    }
+    pub fn order_by_desc(mut self, cs: impl IntoVec<Expr>) -> Self {
+        for item in cs.into_vec() {
+            self.orders.push(Order::desc(item));
+        }
+        self
+    }
+
+    pub fn limit(mut self, n: usize) -> Self {
+        if n > 0 {
+            self.limit = Some(n);
+        }
+        self
+    }
+
+    pub fn returning(mut self, items: impl IntoVec<SelectItem>) -> Self {
+        for item in items.into_vec() {
+            self.returning.push(item);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+#[path = "./delete_test.rs"]
+mod tests;
@@ -0,0 +1,195 @@
+use crate::prelude::*;
+use crate::sequel::statement::delete::DeleteStatement;
+use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::term::table::Table;
+use crate::sequel::visitor::error::RenderError;
+use crate::sequel::visitor::visitor::{duckdb, mysql, postgre, sqlite};
+use std::sync::LazyLock;
+
+test_macros!($ visit_delete_statement);
+
+pub static USERS: LazyLock<Table> = LazyLock::new(|| Table::new("users"));
+pub static ORDERS: LazyLock<Table> = LazyLock::new(|| Table::new("orders"));
+pub static BANNED: LazyLock<Table> = LazyLock::new(|| Table::new("banned"));
+
+#[test]
+fn test_delete__all() {
+    let stmt = DeleteStatement::from(&*USERS);
+    assert_mysql!(&stmt, "DELETE FROM `users` AS `users0`");
+    assert_pg!(&stmt, r#"DELETE FROM "users" AS "users0""#);
+    assert_sqlite!(&stmt, r#"DELETE FROM "users" AS "users0""#);
+}
+
+#[test]
+fn test_delete__filter() {
+    let stmt = DeleteStatement::from(&*USERS)
+        .filter(USERS.column("age").lt(18))
+        .filter(USERS.column("name").like("%bot%"));
+    assert_mysql!(
+        &stmt,
+        "DELETE FROM `users` AS `users0` WHERE `users0`.`age` < ? AND `users0`.`name` LIKE ?",
+        [18_i64, "%bot%"]
+    );
+    assert_pg!(
+        &stmt,
+        r#"DELETE FROM "users" AS "users0" WHERE "users0"."age" < $1 AND "users0"."name" LIKE $2"#,
+        [18_i64, "%bot%"]
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"DELETE FROM "users" AS "users0" WHERE "users0"."age" < ? AND "users0"."name" LIKE ?"#,
+        [18_i64, "%bot%"]
+    );
+}
+
+#[test]
+fn test_delete__in_subquery() {
+    let banned = SelectStatement::from(&*BANNED).select(BANNED.column("user_id"));
+    let stmt = DeleteStatement::from(&*ORDERS).filter(ORDERS.column("user_id").in_(vec![banned]));
+    assert_pg!(
+        &stmt,
+        r#"DELETE FROM "orders" AS "orders0" WHERE "orders0"."user_id" IN ((SELECT "banned0"."user_id" FROM "banned" AS "banned0"))"#
+    );
+}
+
+#[test]
+fn test_delete__subquery_passes() {
+    use crate::sequel::visitor::dialect::Dialect;
+
+    let banned = SelectStatement::from(&*BANNED).select(BANNED.column("user_id"));
+    let stmt = DeleteStatement::from(&*ORDERS).filter(ORDERS.column("user_id").in_(vec![banned]));
+    let (sql, params, _) = postgre()
+        .pass(|stmt: &mut SelectStatement, _: &dyn Dialect| stmt.limit = Some(10))
+        .visit_delete_statement(&stmt)
+        .finish();
+    assert_eq!(
+        sql,
+        r#"DELETE FROM "orders" AS "orders0" WHERE "orders0"."user_id" IN ((SELECT "banned0"."user_id" FROM "banned" AS "banned0" LIMIT 10))"#
+    );
+    assert!(params.is_empty());
+}
+
+#[test]
+fn test_delete__order_by_and_limit() {
+    let stmt = DeleteStatement::from(&*ORDERS)
+        .filter(ORDERS.column("status").eq("expired"))
+        .order_by(ORDERS.column("created_at"))
+        .limit(100);
+    assert_mysql!(
+        &stmt,
+        "DELETE FROM `orders` AS `orders0` WHERE `orders0`.`status` = ? ORDER BY `orders0`.`created_at` LIMIT 100",
+        ["expired"]
+    );
+    // 丢掉 ORDER BY / LIMIT 会删除所有匹配的行，宽松模式下也报错
+    let expected =
+        RenderError::unsupported("ORDER BY / LIMIT", "the dialect does not allow them in this UPDATE / DELETE");
+    assert_eq!(postgre().try_visit_delete_statement(&stmt).err(), Some(expected.clone()));
+    assert_eq!(sqlite().try_visit_delete_statement(&stmt).err(), Some(expected.clone()));
+    assert_eq!(duckdb().try_visit_delete_statement(&stmt).err(), Some(expected));
+}
+
+#[test]
+fn test_delete__multi_table_rejects_limit() {
+    let stmt = DeleteStatement::from(&*ORDERS)
+        .join(&*USERS, USERS.column("id").eq(ORDERS.column("user_id")))
+        .order_by(ORDERS.column("id"))
+        .limit(10);
+    // 任何方言的多表删除都不支持 ORDER BY / LIMIT
+    assert!(mysql().try_visit_delete_statement(&stmt).is_err());
+    assert!(postgre().try_visit_delete_statement(&stmt).is_err());
+    assert!(sqlite().try_visit_delete_statement(&stmt).is_err());
+}
+
+#[test]
+fn test_delete__multi_table() {
+    let stmt = DeleteStatement::from(&*ORDERS)
+        .join(&*USERS, USERS.column("id").eq(ORDERS.column("user_id")))
+        .filter(USERS.column("active").eq(false));
+    assert_mysql!(
+        &stmt,
+        "DELETE `orders0` FROM `orders` AS `orders0` INNER JOIN `users` AS `users0` ON `users0`.`id` = `orders0`.`user_id` WHERE `users0`.`active` = ?",
+        [false]
+    );
+    assert_pg!(
+        &stmt,
+        r#"DELETE FROM "orders" AS "orders0" USING "users" AS "users0" WHERE "users0"."id" = "orders0"."user_id" AND "users0"."active" = $1"#,
+        [false]
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"DELETE FROM "orders" AS "orders0" WHERE EXISTS (SELECT 1 FROM "users" AS "users0" WHERE "users0"."id" = "orders0"."user_id" AND "users0"."active" = ?)"#,
+        [false]
+    );
+}
+
+#[test]
+fn test_delete__multi_table_multiple_joins() {
+    let stmt = DeleteStatement::from(&*ORDERS)
+        .join(&*USERS, USERS.column("id").eq(ORDERS.column("user_id")))
+        .join(&*BANNED, BANNED.column("user_id").eq(USERS.column("id")));
+    assert_mysql!(
+        &stmt,
+        "DELETE `orders0` FROM `orders` AS `orders0` INNER JOIN `users` AS `users0` ON `users0`.`id` = `orders0`.`user_id` INNER JOIN `banned` AS `banned0` ON `banned0`.`user_id` = `users0`.`id`"
+    );
+    assert_pg!(
+        &stmt,
+        r#"DELETE FROM "orders" AS "orders0" USING "users" AS "users0", "banned" AS "banned0" WHERE "users0"."id" = "orders0"."user_id" AND "banned0"."user_id" = "users0"."id""#
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"DELETE FROM "orders" AS "orders0" WHERE EXISTS (SELECT 1 FROM "users" AS "users0" CROSS JOIN "banned" AS "banned0" WHERE "users0"."id" = "orders0"."user_id" AND "banned0"."user_id" = "users0"."id")"#
+    );
+}
+
+#[test]
+fn test_delete__returning() {
+    let stmt = DeleteStatement::from(&*USERS)
+        .filter(USERS.column("id").eq(1))
+        .returning([USERS.column("id"), USERS.column("name")]);
+    assert_mysql!(&stmt, "DELETE FROM `users` AS `users0` WHERE `users0`.`id` = ?", [1_i64]);
+    assert_pg!(
+        &stmt,
+        r#"DELETE FROM "users" AS "users0" WHERE "users0"."id" = $1 RETURNING "users0"."id", "users0"."name""#,
+        [1_i64]
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"DELETE FROM "users" AS "users0" WHERE "users0"."id" = ? RETURNING "users0"."id", "users0"."name""#,
+        [1_i64]
+    );
+}
+
+#[test]
+fn test_delete__multi_table_returning() {
+    let stmt = DeleteStatement::from(&*ORDERS)
+        .join(&*USERS, USERS.column("id").eq(ORDERS.column("user_id")))
+        .returning(ORDERS.column("id"));
+    assert_pg!(
+        &stmt,
+        r#"DELETE FROM "orders" AS "orders0" USING "users" AS "users0" WHERE "users0"."id" = "orders0"."user_id" RETURNING "orders0"."id""#
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"DELETE FROM "orders" AS "orders0" WHERE EXISTS (SELECT 1 FROM "users" AS "users0" WHERE "users0"."id" = "orders0"."user_id") RETURNING "orders0"."id""#
+    );
+}
+
+#[test]
+fn test_delete__mssql_alias_in_from() {
+    // T-SQL 不能直接给 DELETE 的目标表起别名，按别名删除
+    let stmt = DeleteStatement::from(&*USERS).filter(USERS.column("id").eq(1));
+    assert_mssql!(
+        &stmt,
+        "DELETE [users0] FROM [users] AS [users0] WHERE [users0].[id] = @p1",
+        [1_i64]
+    );
+
+    let stmt = DeleteStatement::from(&*ORDERS)
+        .join(&*USERS, USERS.column("id").eq(ORDERS.column("user_id")))
+        .filter(USERS.column("active").eq(false));
+    assert_mssql!(
+        &stmt,
+        "DELETE [orders0] FROM [orders] AS [orders0] INNER JOIN [users] AS [users0] ON [users0].[id] = [orders0].[user_id] WHERE [users0].[active] = @p1",
+        [false]
+    );
+}
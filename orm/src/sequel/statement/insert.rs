@@ -0,0 +1,145 @@
+use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::statement::update::Assignment;
+use crate::sequel::term::column::Column;
+use crate::sequel::term::expr::Expr;
+use crate::sequel::term::select_item::SelectItem;
+use crate::sequel::term::table::Table;
+use utils::into_vec::IntoVec;
+
+#[derive(Clone, Debug)]
+pub enum InsertSource {
+    // e.g. INSERT INTO users (name, age) VALUES ('a', 1), ('b', 2);
+    Values(Vec<Vec<Expr>>),
+    // e.g. INSERT INTO archived (id, name) SELECT id, name FROM users;
+    Select(Box<SelectStatement>),
+}
+
+#[derive(Clone, Debug)]
+pub enum ConflictTarget {
+    // e.g. ON CONFLICT (email)
+    Columns(Vec<Column>),
+    // e.g. ON CONFLICT ON CONSTRAINT users_email_key
+    Constraint(String),
+}
+
+#[derive(Clone, Debug)]
+pub enum ConflictAction {
+    // PostgreSQL/SQLite: ON CONFLICT DO NOTHING; MySQL: INSERT IGNORE
+    DoNothing,
+    // PostgreSQL/SQLite: ON CONFLICT DO UPDATE SET ...; MySQL: ON DUPLICATE KEY UPDATE ...
+    DoUpdate(Vec<Assignment>),
+}
+
+#[derive(Clone, Debug)]
+pub struct OnConflict {
+    pub target: Option<ConflictTarget>,
+    pub action: ConflictAction,
+}
+
+#[derive(Clone, Debug)]
+pub struct InsertStatement {
+    pub table: Table,
+    pub columns: Vec<Column>,
+    pub source: InsertSource,
+    pub on_conflict: Option<OnConflict>,
+    pub returning: Vec<SelectItem>,
+}
+
+impl InsertStatement {
+    pub fn into<T>(t: &T) -> Self
+    where
+        T: Clone + Into<Table>,
+    {
+        Self {
+            table: t.clone().into(),
+            columns: Vec::new(),
+            source: InsertSource::Values(Vec::new()),
+            on_conflict: None,
+            returning: Vec::new(),
+        }
+    }
+
+    pub fn columns(mut self, columns: impl IntoVec<Column>) -> Self {
+        for col in columns.into_vec() {
+            self.columns.push(col);
+        }
+        self
+    }
+
+    // 每调用一次追加一行；若之前是 INSERT ... SELECT，则切换回 VALUES
+    pub fn values(mut self, row: impl IntoVec<Expr>) -> Self {
+        let row = row.into_vec();
+        match &mut self.source {
+            InsertSource::Values(rows) => rows.push(row),
+            InsertSource::Select(_) => self.source = InsertSource::Values(vec![row]),
+        }
+        self
+    }
+
+    pub fn select(mut self, stmt: SelectStatement) -> Self {
+        self.source = InsertSource::Select(Box::new(stmt));
+        self
+    }
+
+    pub fn on_conflict(self, columns: impl IntoVec<Column>) -> Self {
+        self.set_conflict_target(ConflictTarget::Columns(columns.into_vec()))
+    }
+
+    pub fn on_conflict_constraint(self, name: impl Into<String>) -> Self {
+        self.set_conflict_target(ConflictTarget::Constraint(name.into()))
+    }
+
+    fn set_conflict_target(mut self, target: ConflictTarget) -> Self {
+        self.on_conflict_mut().target = Some(target);
+        self
+    }
+
+    pub fn do_nothing(mut self) -> Self {
+        self.on_conflict_mut().action = ConflictAction::DoNothing;
+        self
+    }
+
+    // e.g. .do_update(count, count.clone() + excluded(count)) => SET count = count + EXCLUDED.count
+    pub fn do_update(mut self, column: Column, value: impl Into<Expr>) -> Self {
+        let assignment = Assignment {
+            column,
+            value: value.into(),
+        };
+        let on_conflict = self.on_conflict_mut();
+        match &mut on_conflict.action {
+            ConflictAction::DoUpdate(assignments) => assignments.push(assignment),
+            ConflictAction::DoNothing => on_conflict.action = ConflictAction::DoUpdate(vec![assignment]),
+        }
+        self
+    }
+
+    fn on_conflict_mut(&mut self) -> &mut OnConflict {
+        self.on_conflict.get_or_insert(OnConflict {
+            target: None,
+            action: ConflictAction::DoNothing,
+        })
+    }
+
+    // 用待插入行的值覆盖：SET col = EXCLUDED.col
+    pub fn do_update_excluded(mut self, columns: impl IntoVec<Column>) -> Self {
+        for col in columns.into_vec() {
+            self = self.do_update(col.clone(), excluded(col));
+        }
+        self
+    }
+
+    pub fn returning(mut self, items: impl IntoVec<SelectItem>) -> Self {
+        for item in items.into_vec() {
+            self.returning.push(item);
+        }
+        self
+    }
+}
+
+pub fn excluded(column: Column) -> Expr {
+    Expr::Excluded(column)
+}
+
+#[cfg(test)]
+#[path = "./insert_test.rs"]
+mod tests;
@@ -0,0 +1,310 @@
+use crate::prelude::*;
+use crate::sequel::statement::insert::{InsertStatement, excluded};
+use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::term::expr::Expr;
+use crate::sequel::term::func::func;
+use crate::sequel::term::param::lit;
+use crate::sequel::term::table::Table;
+use crate::sequel::visitor::error::RenderError;
+use crate::sequel::visitor::visitor::{mssql, mysql, oracle, postgre, sqlite};
+use std::sync::LazyLock;
+
+test_macros!($ visit_insert_statement);
+
+pub static USERS: LazyLock<Table> = LazyLock::new(|| Table::new("users"));
+pub static ARCHIVED_USERS: LazyLock<Table> = LazyLock::new(|| Table::new("archived_users"));
+
+#[test]
+fn test_insert__single_row() {
+    let stmt = InsertStatement::into(&*USERS)
+        .columns([USERS.column("name"), USERS.column("age")])
+        .values([Expr::from("Lucy"), Expr::from(18)]);
+    assert_mysql!(
+        &stmt,
+        "INSERT INTO `users` (`name`, `age`) VALUES (?, ?)",
+        ["Lucy", 18_i64]
+    );
+    assert_pg!(
+        &stmt,
+        r#"INSERT INTO "users" ("name", "age") VALUES ($1, $2)"#,
+        ["Lucy", 18_i64]
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"INSERT INTO "users" ("name", "age") VALUES (?, ?)"#,
+        ["Lucy", 18_i64]
+    );
+}
+
+#[test]
+fn test_insert__multiple_rows() {
+    let stmt = InsertStatement::into(&*USERS)
+        .columns([USERS.column("name"), USERS.column("age")])
+        .values([Expr::from("Lucy"), Expr::from(18)])
+        .values([Expr::from("Lily"), Expr::from(20)]);
+    assert_mysql!(
+        &stmt,
+        "INSERT INTO `users` (`name`, `age`) VALUES (?, ?), (?, ?)",
+        ["Lucy", 18_i64, "Lily", 20_i64]
+    );
+    assert_pg!(
+        &stmt,
+        r#"INSERT INTO "users" ("name", "age") VALUES ($1, $2), ($3, $4)"#,
+        ["Lucy", 18_i64, "Lily", 20_i64]
+    );
+}
+
+#[test]
+fn test_insert__literal_and_func_values() {
+    let stmt = InsertStatement::into(&*USERS)
+        .columns([USERS.column("name"), USERS.column("active"), USERS.column("created_at")])
+        .values([Expr::from(lit("Lucy")), Expr::from(lit(true)), func("now", Vec::<Expr>::new()).into()]);
+    assert_mysql!(
+        &stmt,
+        "INSERT INTO `users` (`name`, `active`, `created_at`) VALUES ('Lucy', 1, NOW())"
+    );
+    assert_pg!(
+        &stmt,
+        r#"INSERT INTO "users" ("name", "active", "created_at") VALUES ('Lucy', true, NOW())"#
+    );
+}
+
+#[test]
+fn test_insert__null_value() {
+    let stmt = InsertStatement::into(&*USERS)
+        .columns([USERS.column("name"), USERS.column("email")])
+        .values([Expr::from("Lucy"), Expr::from(None::<&str>)]);
+    assert_sqlite!(
+        &stmt,
+        r#"INSERT INTO "users" ("name", "email") VALUES (?, NULL)"#,
+        ["Lucy"]
+    );
+}
+
+#[test]
+fn test_insert__select() {
+    let select = SelectStatement::from(&*USERS)
+        .select([USERS.column("id"), USERS.column("name")])
+        .filter(USERS.column("deleted").eq(true));
+    let stmt = InsertStatement::into(&*ARCHIVED_USERS)
+        .columns([ARCHIVED_USERS.column("id"), ARCHIVED_USERS.column("name")])
+        .select(select);
+    assert_mysql!(
+        &stmt,
+        "INSERT INTO `archived_users` (`id`, `name`) SELECT `users0`.`id`, `users0`.`name` FROM `users` AS `users0` WHERE `users0`.`deleted` = ?",
+        [true]
+    );
+    assert_pg!(
+        &stmt,
+        r#"INSERT INTO "archived_users" ("id", "name") SELECT "users0"."id", "users0"."name" FROM "users" AS "users0" WHERE "users0"."deleted" = $1"#,
+        [true]
+    );
+}
+
+#[test]
+fn test_insert__returning() {
+    let stmt = InsertStatement::into(&*USERS)
+        .columns([USERS.column("name")])
+        .values([Expr::from("Lucy")])
+        .returning([USERS.column("id"), USERS.column("name")]);
+    // MySQL 不支持 RETURNING
+    assert_mysql!(&stmt, "INSERT INTO `users` (`name`) VALUES (?)", ["Lucy"]);
+    assert_pg!(
+        &stmt,
+        r#"INSERT INTO "users" ("name") VALUES ($1) RETURNING "id", "name""#,
+        ["Lucy"]
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"INSERT INTO "users" ("name") VALUES (?) RETURNING "id", "name""#,
+        ["Lucy"]
+    );
+}
+
+#[test]
+fn test_insert__returning_alias() {
+    let stmt = InsertStatement::into(&*USERS)
+        .columns([USERS.column("name")])
+        .values([Expr::from("Lucy")])
+        .returning(USERS.column("id").alias("user_id"));
+    assert_pg!(
+        &stmt,
+        r#"INSERT INTO "users" ("name") VALUES ($1) RETURNING "id" AS "user_id""#,
+        ["Lucy"]
+    );
+}
+
+#[test]
+fn test_insert__select_returning() {
+    // SELECT 中自动生成的别名在 RETURNING 中不可见
+    let stmt = InsertStatement::into(&*USERS)
+        .columns([USERS.column("name")])
+        .select(
+            SelectStatement::from(&*USERS)
+                .select(USERS.column("name"))
+                .filter(USERS.column("id").eq(1)),
+        )
+        .returning([USERS.column("id")]);
+    assert_pg!(
+        &stmt,
+        r#"INSERT INTO "users" ("name") SELECT "users0"."name" FROM "users" AS "users0" WHERE "users0"."id" = $1 RETURNING "id""#,
+        [1_i64]
+    );
+}
+
+#[test]
+fn test_insert__on_conflict_do_update() {
+    let stmt = InsertStatement::into(&*USERS)
+        .columns([USERS.column("email"), USERS.column("name")])
+        .values([Expr::from("lucy@example.com"), Expr::from("Lucy")])
+        .on_conflict(USERS.column("email"))
+        .do_update_excluded(USERS.column("name"));
+    assert_mysql!(
+        &stmt,
+        "INSERT INTO `users` (`email`, `name`) VALUES (?, ?) ON DUPLICATE KEY UPDATE `name` = VALUES(`name`)",
+        ["lucy@example.com", "Lucy"]
+    );
+    assert_pg!(
+        &stmt,
+        r#"INSERT INTO "users" ("email", "name") VALUES ($1, $2) ON CONFLICT ("email") DO UPDATE SET "name" = EXCLUDED."name""#,
+        ["lucy@example.com", "Lucy"]
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"INSERT INTO "users" ("email", "name") VALUES (?, ?) ON CONFLICT ("email") DO UPDATE SET "name" = EXCLUDED."name""#,
+        ["lucy@example.com", "Lucy"]
+    );
+}
+
+#[test]
+fn test_insert__on_conflict_do_update_expression() {
+    let visits = USERS.column("visits");
+    let stmt = InsertStatement::into(&*USERS)
+        .columns([USERS.column("id"), visits.clone()])
+        .values([Expr::from(1), Expr::from(1)])
+        .on_conflict(USERS.column("id"))
+        .do_update(visits.clone(), excluded(visits.clone()) + visits)
+        .do_update(USERS.column("updated_at"), func("now", Vec::<Expr>::new()));
+    assert_mysql!(
+        &stmt,
+        "INSERT INTO `users` (`id`, `visits`) VALUES (?, ?) ON DUPLICATE KEY UPDATE `visits` = VALUES(`visits`) + `visits`, `updated_at` = NOW()",
+        [1_i64, 1_i64]
+    );
+    assert_pg!(
+        &stmt,
+        r#"INSERT INTO "users" ("id", "visits") VALUES ($1, $2) ON CONFLICT ("id") DO UPDATE SET "visits" = EXCLUDED."visits" + "users"."visits", "updated_at" = NOW()"#,
+        [1_i64, 1_i64]
+    );
+}
+
+#[test]
+fn test_insert__on_conflict_constraint() {
+    let stmt = InsertStatement::into(&*USERS)
+        .columns([USERS.column("email"), USERS.column("name")])
+        .values([Expr::from("lucy@example.com"), Expr::from("Lucy")])
+        .on_conflict_constraint("users_email_key")
+        .do_update_excluded(USERS.column("name"));
+    assert_pg!(
+        &stmt,
+        r#"INSERT INTO "users" ("email", "name") VALUES ($1, $2) ON CONFLICT ON CONSTRAINT "users_email_key" DO UPDATE SET "name" = EXCLUDED."name""#,
+        ["lucy@example.com", "Lucy"]
+    );
+}
+
+#[test]
+fn test_insert__on_conflict_do_nothing() {
+    let stmt = InsertStatement::into(&*USERS)
+        .columns([USERS.column("email")])
+        .values([Expr::from("lucy@example.com")])
+        .on_conflict(USERS.column("email"))
+        .do_nothing();
+    assert_mysql!(
+        &stmt,
+        "INSERT IGNORE INTO `users` (`email`) VALUES (?)",
+        ["lucy@example.com"]
+    );
+    assert_pg!(
+        &stmt,
+        r#"INSERT INTO "users" ("email") VALUES ($1) ON CONFLICT ("email") DO NOTHING"#,
+        ["lucy@example.com"]
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"INSERT INTO "users" ("email") VALUES (?) ON CONFLICT ("email") DO NOTHING"#,
+        ["lucy@example.com"]
+    );
+
+    let stmt = InsertStatement::into(&*USERS)
+        .columns([USERS.column("email")])
+        .values([Expr::from("lucy@example.com")])
+        .do_nothing();
+    assert_pg!(
+        &stmt,
+        r#"INSERT INTO "users" ("email") VALUES ($1) ON CONFLICT DO NOTHING"#,
+        ["lucy@example.com"]
+    );
+}
+
+#[test]
+fn test_insert__on_conflict_returning() {
+    let stmt = InsertStatement::into(&*USERS)
+        .columns([USERS.column("email"), USERS.column("name")])
+        .values([Expr::from("lucy@example.com"), Expr::from("Lucy")])
+        .on_conflict(USERS.column("email"))
+        .do_update_excluded(USERS.column("name"))
+        .returning(USERS.column("id"));
+    assert_pg!(
+        &stmt,
+        r#"INSERT INTO "users" ("email", "name") VALUES ($1, $2) ON CONFLICT ("email") DO UPDATE SET "name" = EXCLUDED."name" RETURNING "id""#,
+        ["lucy@example.com", "Lucy"]
+    );
+}
+
+#[test]
+fn test_insert__invalid_values() {
+    let stmt = InsertStatement::into(&*USERS).columns([USERS.column("name")]);
+    let err = postgre().try_visit_insert_statement(&stmt).err().unwrap();
+    assert_eq!(err, RenderError::invalid("VALUES", "an INSERT needs at least one row"));
+
+    let stmt = InsertStatement::into(&*USERS)
+        .columns([USERS.column("name"), USERS.column("age")])
+        .values([Expr::from("Lucy"), Expr::from(18)])
+        .values([Expr::from("Lily")]);
+    let err = mysql().try_visit_insert_statement(&stmt).err().unwrap();
+    assert_eq!(err, RenderError::invalid("VALUES", "every row must have one value per column"));
+}
+
+#[test]
+fn test_insert__invalid_upsert() {
+    let row = [Expr::from("lucy@example.com"), Expr::from("Lucy")];
+    let columns = [USERS.column("email"), USERS.column("name")];
+
+    // 没有冲突目标时无法 DO UPDATE
+    let stmt = InsertStatement::into(&*USERS)
+        .columns(columns.clone())
+        .values(row.clone())
+        .do_update_excluded(USERS.column("name"));
+    let err = postgre().try_visit_insert_statement(&stmt).err().unwrap();
+    assert_eq!(err, RenderError::invalid("ON CONFLICT DO UPDATE", "it needs a conflict target"));
+
+    let stmt = InsertStatement::into(&*USERS)
+        .columns(columns.clone())
+        .values(row.clone())
+        .on_conflict_constraint("users_email_key")
+        .do_nothing();
+    let err = sqlite().try_visit_insert_statement(&stmt).err().unwrap();
+    assert_eq!(
+        err,
+        RenderError::unsupported("ON CONFLICT ON CONSTRAINT", "the dialect only accepts conflict columns")
+    );
+
+    let stmt = InsertStatement::into(&*USERS)
+        .columns(columns)
+        .values(row)
+        .on_conflict(USERS.column("email"))
+        .do_nothing();
+    let err = mssql().try_visit_insert_statement(&stmt).err().unwrap();
+    assert_eq!(err, RenderError::unsupported("ON CONFLICT", "the dialect has no upsert"));
+    let err = oracle().try_visit_insert_statement(&stmt).err().unwrap();
+    assert_eq!(err, RenderError::unsupported("ON CONFLICT", "the dialect has no upsert"));
+}
@@ -1 +1,8 @@
+#[cfg(test)]
+#[macro_use]
+mod test_macros;
+
+pub mod delete;
+pub mod insert;
 pub mod select;
+pub mod update;
@@ -1,26 +1,44 @@
 use crate::prelude::*;
+use crate::sequel::term::column::Column;
+use crate::sequel::term::cte::Cte;
 use crate::sequel::term::distinct::Distinct;
 use crate::sequel::term::expr::Expr;
 use crate::sequel::term::index::{Index, Indexes};
 use crate::sequel::term::lock::{Lock, Locking, Wait};
 use crate::sequel::term::order::Order;
+use crate::sequel::term::param::Param;
+use crate::sequel::term::sample::Sample;
 use crate::sequel::term::select_item::SelectItem;
+use crate::sequel::term::set_op::{SetOp, SetOperation};
 use crate::sequel::term::table::Table;
+use crate::sequel::term::window::Window;
+use serde::{Deserialize, Serialize};
 use utils::into_vec::IntoVec;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct SelectStatement {
+    pub with_clause: Vec<Cte>,
+    pub recursive: bool,
     pub distinct: Distinct,
     pub select_clause: Vec<SelectItem>,
     pub from_clause: Table,
+    pub final_modifier: bool,
+    pub sample: Option<Sample>,
     pub where_clause: Vec<Expr>,
     pub having_clause: Vec<Expr>,
     pub groups: Vec<Expr>,
+    pub group_by_all: bool,
+    pub windows: Vec<(String, Window)>,
+    pub qualify_clause: Vec<Expr>,
     pub orders: Vec<Order>,
+    pub limit_by: Option<(usize, Vec<Expr>)>,
     pub limit: Option<usize>,
     pub offset: Option<usize>,
     pub locking: Option<Locking>,
     pub indexes: Indexes,
+    pub set_ops: Vec<SetOperation>,
+    pub settings: Vec<(String, Param)>,
 }
 
 impl SelectStatement {
@@ -29,19 +47,44 @@ impl SelectStatement {
         T: Clone + Into<Table>,
     {
         Self {
+            with_clause: Vec::new(),
+            recursive: false,
             distinct: Distinct::None,
             select_clause: Vec::new(),
             from_clause: t.clone().into(),
+            final_modifier: false,
+            sample: None,
             where_clause: Vec::new(),
             having_clause: Vec::new(),
             groups: Vec::new(),
+            group_by_all: false,
+            windows: Vec::new(),
+            qualify_clause: Vec::new(),
             orders: Vec::new(),
+            limit_by: None,
             limit: None,
             offset: None,
             locking: Some(Locking::new()),
             indexes: Indexes::new(),
+            set_ops: Vec::new(),
+            settings: Vec::new(),
         }
     }
+    pub fn with(self, name: impl Into<String>, stmt: SelectStatement) -> Self {
+        self.with_cte(Cte::new(name, stmt))
+    }
+
+    // RECURSIVE 作用于整个 WITH 子句
+    pub fn with_recursive(mut self, name: impl Into<String>, stmt: SelectStatement) -> Self {
+        self.recursive = true;
+        self.with_cte(Cte::new(name, stmt))
+    }
+
+    pub fn with_cte(mut self, cte: Cte) -> Self {
+        self.with_clause.push(cte);
+        self
+    }
+
     pub fn distinct(mut self) -> Self {
         self.distinct = Distinct::All;
         self
@@ -86,6 +129,54 @@ impl SelectStatement {
         self
     }
 
+    // e.g. GROUP BY ALL，按 SELECT 中所有非聚合列分组（DuckDB/ClickHouse）
+    pub fn group_by_all(mut self) -> Self {
+        self.group_by_all = true;
+        self
+    }
+
+    // e.g. WINDOW w AS (PARTITION BY dept ORDER BY salary)
+    pub fn window(mut self, name: impl Into<String>, window: Window) -> Self {
+        self.windows.push((name.into(), window));
+        self
+    }
+
+    // 过滤窗口函数的结果，e.g. QUALIFY ROW_NUMBER() OVER (...) = 1（DuckDB/ClickHouse）
+    pub fn qualify<T>(mut self, c: T) -> Self
+    where
+        T: Into<Expr> + Clone,
+    {
+        self.qualify_clause.push(c.into());
+        self
+    }
+
+    // 只读取合并后的数据，e.g. FROM t FINAL（ClickHouse）
+    pub fn final_(mut self) -> Self {
+        self.final_modifier = true;
+        self
+    }
+
+    pub fn sample_percent(mut self, percent: f64) -> Self {
+        self.sample = Some(Sample::Percent(percent));
+        self
+    }
+    pub fn sample_rows(mut self, rows: usize) -> Self {
+        self.sample = Some(Sample::Rows(rows));
+        self
+    }
+
+    // 每组最多 n 行，e.g. LIMIT 3 BY domain（ClickHouse）
+    pub fn limit_by(mut self, n: usize, cs: impl IntoVec<Expr>) -> Self {
+        self.limit_by = Some((n, cs.into_vec()));
+        self
+    }
+
+    // e.g. SETTINGS max_threads = 8（ClickHouse），值总是按字面量输出
+    pub fn setting(mut self, name: impl Into<String>, value: impl Into<Param>) -> Self {
+        self.settings.push((name.into(), lit(value)));
+        self
+    }
+
     pub fn order_by(mut self, cs: impl IntoVec<Expr>) -> Self {
         for item in cs.into_vec() {
             self.orders.push(Order::asc(item));
@@ -149,6 +240,10 @@ impl SelectStatement {
         let tables = tables.into_vec();
         self.set_lock_of_locker(Lock::UpdateOf(tables))
     }
+    pub fn for_update_of_columns(self, columns: impl IntoVec<Column>) -> Self {
+        let columns = columns.into_vec();
+        self.set_lock_of_locker(Lock::UpdateOfColumns(columns))
+    }
 
     fn set_lock_of_locker(mut self, lock: Lock) -> Self {
         let mut locking = self.locking.unwrap_or_else(|| Locking::new());
@@ -207,6 +302,44 @@ impl SelectStatement {
         self
     }
 
+    // 组合查询：之后调用的 order_by/limit/offset 作用于整个组合结果
+    pub fn union(self, other: SelectStatement) -> Self {
+        self.set_operation(SetOp::Union, other)
+    }
+    pub fn union_all(self, other: SelectStatement) -> Self {
+        self.set_operation(SetOp::UnionAll, other)
+    }
+    pub fn intersect(self, other: SelectStatement) -> Self {
+        self.set_operation(SetOp::Intersect, other)
+    }
+    pub fn except(self, other: SelectStatement) -> Self {
+        self.set_operation(SetOp::Except, other)
+    }
+
+    fn set_operation(self, op: SetOp, other: SelectStatement) -> Self {
+        // 第一个操作数自带的 ORDER BY/LIMIT/OFFSET 只属于它自己，包成派生表以免被当成组合结果的
+        let mut this = if self.set_ops.is_empty() && self.has_own_ordering() {
+            let with_clause = self.with_clause.clone();
+            let recursive = self.recursive;
+            let mut stmt = SelectStatement::from(&Table::from(SelectStatement {
+                with_clause: Vec::new(),
+                recursive: false,
+                ..self
+            }));
+            stmt.with_clause = with_clause;
+            stmt.recursive = recursive;
+            stmt
+        } else {
+            self
+        };
+        this.set_ops.push(SetOperation { op, stmt: other });
+        this
+    }
+
+    pub fn has_own_ordering(&self) -> bool {
+        !self.orders.is_empty() || self.limit.is_some() || self.offset.is_some() || self.limit_by.is_some()
+    }
+
     pub fn alias(self, name: &str) -> Table {
         Table::from(self).alias(name)
     }
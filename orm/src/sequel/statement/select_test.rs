@@ -1,50 +1,24 @@
+use crate::model::columns::ColumnType;
 use crate::prelude::*;
 use crate::sequel::statement::select::SelectStatement;
 use crate::sequel::term::calendar::{Date, DateTime, Time};
-use crate::sequel::term::expr::Expr;
+use crate::sequel::term::case::{case, case_of};
+use crate::sequel::term::expr::{Expr, cast};
 use crate::sequel::term::func::{
-    abs, avg, ceil, coalesce, count, count_all, exists, floor, func, lower, max, min, sqrt, sum, upper,
+    abs, avg, ceil, coalesce, count, count_all, dense_rank, exists, first_value, floor, func, lag, lower, max, min,
+    ntile, rank, row_number, sqrt, sum, upper,
 };
-use crate::sequel::term::param::{lit, value};
+use crate::sequel::term::param::{lit, named, value};
 use crate::sequel::term::table::Table;
+use crate::sequel::term::window::{FrameBound, Window};
+use crate::sequel::visitor::error::{RenderError, RenderMode};
+use crate::sequel::visitor::visitor::{mssql, mysql, oracle, postgre, sqlite};
 use rust_decimal::Decimal;
 use uuid::Uuid;
+use std::collections::HashMap;
 use std::sync::LazyLock;
 
-macro_rules! assert_dialect {
-    ($visitor:expr, $stmt:expr, $expected_sql:expr, [$($params:expr),*]) => {{
-        let (sql, params_relt) = $visitor.visit_select_statement($stmt).finish();
-        assert_eq!(sql, $expected_sql.to_string());
-        let expected: Vec<crate::sequel::term::param::ParamData> = vec![$($params.into()),*]
-            .into_iter()
-            .map(|p: crate::sequel::term::param::Param| p.data().unwrap().clone())
-            .collect();
-        assert_eq!(params_relt, expected);
-    }};
-    ($visitor:expr, $stmt:expr, $expected_sql:expr) => {{
-        let (sql, params_relt) = $visitor.visit_select_statement($stmt).finish();
-        assert_eq!(sql, $expected_sql.to_string());
-        assert_eq!(params_relt, Vec::<crate::sequel::term::param::ParamData>::new());
-    }};
-}
-
-macro_rules! assert_mysql {
-    ($stmt:expr, $expected_sql:expr $(, [$($params:expr),*])?) => {
-        assert_dialect!(crate::sequel::visitor::visitor::mysql(), $stmt, $expected_sql $(, [$($params),*])?)
-    };
-}
-
-macro_rules! assert_pg {
-    ($stmt:expr, $expected_sql:expr $(, [$($params:expr),*])?) => {
-        assert_dialect!(crate::sequel::visitor::visitor::postgre(), $stmt, $expected_sql $(, [$($params),*])?)
-    };
-}
-
-macro_rules! assert_sqlite {
-    ($stmt:expr, $expected_sql:expr $(, [$($params:expr),*])?) => {
-        assert_dialect!(crate::sequel::visitor::visitor::sqlite(), $stmt, $expected_sql $(, [$($params),*])?)
-    };
-}
+test_macros!($ visit_select_statement);
 
 // ============================================================================
 // 基础表定义
@@ -178,6 +152,18 @@ fn test_filter__complex_precedence_auto_grouping() {
     );
 }
 
+#[test]
+fn test_filter__or_in_multiple_filters() {
+    let stmt = SelectStatement::from(&*USERS)
+        .filter(USERS.column("age").lt(18).or(USERS.column("age").gt(60)))
+        .filter(USERS.column("status").eq("active"));
+    assert_mysql!(
+        &stmt,
+        "SELECT * FROM `users` AS `users0` WHERE (`users0`.`age` < ? OR `users0`.`age` > ?) AND `users0`.`status` = ?",
+        [18, 60, "active"]
+    );
+}
+
 #[test]
 fn test_filter__nested_not_precedence() {
     let condition = !USERS
@@ -1429,7 +1415,7 @@ fn test_select__param_data_types() {
         .select(value(json))
         .select(value(binary));
 
-    let (_, params) = crate::sequel::visitor::visitor::mysql().visit_select_statement(&stmt).finish();
+    let (_, params, _) = crate::sequel::visitor::visitor::mysql().visit_select_statement(&stmt).finish();
     assert_eq!(params.len(), 11);
     assert!(matches!(params[0], crate::sequel::term::param::ParamData::Int(42)));
     assert!(matches!(params[1], crate::sequel::term::param::ParamData::Float(f) if (f - 3.14).abs() < 0.0001));
@@ -1467,7 +1453,7 @@ fn test_select__param_data_types_literal() {
         .select(lit(json))
         .select(lit(binary));
 
-    let (sql, _) = crate::sequel::visitor::visitor::mysql().visit_select_statement(&stmt).finish();
+    let (sql, _, _) = crate::sequel::visitor::visitor::mysql().visit_select_statement(&stmt).finish();
 
     assert!(sql.contains("42"));
     assert!(sql.contains("3.14"));
@@ -1481,3 +1467,2511 @@ fn test_select__param_data_types_literal() {
     assert!(sql.contains(r#"{"key":"value"}"#));
     assert!(sql.contains("X'010203'"));
 }
+
+#[test]
+fn test_cte__with() {
+    let recent = Table::new("recent_orders");
+    let cte = SelectStatement::from(&*ORDERS)
+        .select([ORDERS.column("user_id"), ORDERS.column("total")])
+        .filter(ORDERS.column("status").eq("paid"));
+    let stmt = SelectStatement::from(&recent)
+        .with("recent_orders", cte)
+        .select(recent.column("user_id"))
+        .filter(recent.column("total").gt(100));
+
+    assert_mysql!(
+        &stmt,
+        "WITH `recent_orders` AS (SELECT `orders0`.`user_id`, `orders0`.`total` FROM `orders` AS `orders0` WHERE `orders0`.`status` = ?) SELECT `recent_orders`.`user_id` FROM `recent_orders` WHERE `recent_orders`.`total` > ?",
+        ["paid", 100_i64]
+    );
+    assert_pg!(
+        &stmt,
+        r#"WITH "recent_orders" AS (SELECT "orders0"."user_id", "orders0"."total" FROM "orders" AS "orders0" WHERE "orders0"."status" = $1) SELECT "recent_orders"."user_id" FROM "recent_orders" WHERE "recent_orders"."total" > $2"#,
+        ["paid", 100_i64]
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"WITH "recent_orders" AS (SELECT "orders0"."user_id", "orders0"."total" FROM "orders" AS "orders0" WHERE "orders0"."status" = ?) SELECT "recent_orders"."user_id" FROM "recent_orders" WHERE "recent_orders"."total" > ?"#,
+        ["paid", 100_i64]
+    );
+}
+
+#[test]
+fn test_cte__referenced_twice() {
+    use crate::sequel::term::cte::Cte;
+
+    let cte = Cte::new("totals", SelectStatement::from(&*ORDERS).select(ORDERS.column("total")));
+    let a = cte.table();
+    let b = a.fork();
+    let stmt = SelectStatement::from(&a)
+        .with_cte(cte)
+        .select([a.column("total"), b.column("total")])
+        .join(&b, a.column("total").lt(b.column("total")));
+
+    assert_pg!(
+        &stmt,
+        r#"WITH "totals" AS (SELECT "orders0"."total" FROM "orders" AS "orders0") SELECT "totals"."total", "totals1"."total" FROM "totals" INNER JOIN "totals" AS "totals1" ON "totals"."total" < "totals1"."total""#
+    );
+}
+
+#[test]
+fn test_cte__join_with_named_table() {
+    let recent = Table::new("recent");
+    let cte = SelectStatement::from(&*ORDERS)
+        .select(ORDERS.column("user_id"))
+        .filter(ORDERS.column("total").gt(100));
+    let stmt = SelectStatement::from(&*USERS)
+        .with("recent", cte)
+        .select(USERS.column("name"))
+        .join(&recent, recent.column("user_id").eq(USERS.column("id")));
+
+    assert_mysql!(
+        &stmt,
+        "WITH `recent` AS (SELECT `orders0`.`user_id` FROM `orders` AS `orders0` WHERE `orders0`.`total` > ?) SELECT `users0`.`name` FROM `users` AS `users0` INNER JOIN `recent` ON `recent`.`user_id` = `users0`.`id`",
+        [100_i64]
+    );
+}
+
+#[test]
+fn test_cte__columns_and_materialized() {
+    use crate::sequel::term::cte::Cte;
+
+    let cte = Cte::new(
+        "stats",
+        SelectStatement::from(&*ORDERS)
+            .select([ORDERS.column("user_id").into(), Expr::from(count(ORDERS.column("id")))])
+            .group_by(ORDERS.column("user_id")),
+    )
+    .columns(["uid", "cnt"])
+    .materialized();
+    let stats = cte.table();
+    let stmt = SelectStatement::from(&stats).with_cte(cte).select(stats.column("cnt"));
+
+    assert_pg!(
+        &stmt,
+        r#"WITH "stats" ("uid", "cnt") AS MATERIALIZED (SELECT "orders0"."user_id", COUNT("orders0"."id") FROM "orders" AS "orders0" GROUP BY "orders0"."user_id") SELECT "stats"."cnt" FROM "stats""#
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"WITH "stats" ("uid", "cnt") AS MATERIALIZED (SELECT "orders0"."user_id", COUNT("orders0"."id") FROM "orders" AS "orders0" GROUP BY "orders0"."user_id") SELECT "stats"."cnt" FROM "stats""#
+    );
+    // MySQL 不支持 MATERIALIZED 提示
+    assert_mysql!(
+        &stmt,
+        "WITH `stats` (`uid`, `cnt`) AS (SELECT `orders0`.`user_id`, COUNT(`orders0`.`id`) FROM `orders` AS `orders0` GROUP BY `orders0`.`user_id`) SELECT `stats`.`cnt` FROM `stats`"
+    );
+
+    let cte = Cte::new("stats", SelectStatement::from(&*ORDERS)).not_materialized();
+    let stats = cte.table();
+    let stmt = SelectStatement::from(&stats).with_cte(cte);
+    assert_pg!(
+        &stmt,
+        r#"WITH "stats" AS NOT MATERIALIZED (SELECT * FROM "orders" AS "orders0") SELECT * FROM "stats""#
+    );
+}
+
+#[test]
+fn test_cte__multiple() {
+    let a = Table::new("a");
+    let b = Table::new("b");
+    let stmt = SelectStatement::from(&a)
+        .with("a", SelectStatement::from(&*USERS).select(USERS.column("id")))
+        .with("b", SelectStatement::from(&*ORDERS).select(ORDERS.column("user_id")))
+        .select(a.column("id"))
+        .join(&b, b.column("user_id").eq(a.column("id")));
+
+    assert_pg!(
+        &stmt,
+        r#"WITH "a" AS (SELECT "users0"."id" FROM "users" AS "users0"), "b" AS (SELECT "orders0"."user_id" FROM "orders" AS "orders0") SELECT "a"."id" FROM "a" INNER JOIN "b" ON "b"."user_id" = "a"."id""#
+    );
+}
+
+#[test]
+fn test_cte__recursive() {
+    let tree = Table::new("tree");
+    let base = SelectStatement::from(&*CATEGORIES)
+        .select([CATEGORIES.column("id"), CATEGORIES.column("parent_id")])
+        .filter(CATEGORIES.column("id").eq(1));
+    let stmt = SelectStatement::from(&tree)
+        .with_recursive("tree", base)
+        .with("leaf", SelectStatement::from(&tree).select(tree.column("id")))
+        .select(tree.column("id"));
+
+    assert_pg!(
+        &stmt,
+        r#"WITH RECURSIVE "tree" AS (SELECT "categories0"."id", "categories0"."parent_id" FROM "categories" AS "categories0" WHERE "categories0"."id" = $1), "leaf" AS (SELECT "tree"."id" FROM "tree") SELECT "tree"."id" FROM "tree""#,
+        [1_i64]
+    );
+}
+
+#[test]
+fn test_set_op__union() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("email"))
+        .union(SelectStatement::from(&*COMPANIES).select(COMPANIES.column("email")));
+    assert_mysql!(
+        &stmt,
+        "SELECT `users0`.`email` FROM `users` AS `users0` UNION SELECT `companies0`.`email` FROM `companies` AS `companies0`"
+    );
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."email" FROM "users" AS "users0" UNION SELECT "companies0"."email" FROM "companies" AS "companies0""#
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"SELECT "users0"."email" FROM "users" AS "users0" UNION SELECT "companies0"."email" FROM "companies" AS "companies0""#
+    );
+}
+
+#[test]
+fn test_set_op__all_kinds() {
+    let a = SelectStatement::from(&*USERS).select(USERS.column("id"));
+    let b = SelectStatement::from(&*ORDERS).select(ORDERS.column("user_id"));
+    let c = SelectStatement::from(&*COMPANIES).select(COMPANIES.column("owner_id"));
+    let d = SelectStatement::from(&*PRODUCTS).select(PRODUCTS.column("owner_id"));
+    let stmt = a.union_all(b).intersect(c).except(d);
+    // INTERSECT 在 PostgreSQL 中结合得更紧，在 SQLite 中则不然，之前的部分单独成组，各方言都按从左到右求值
+    assert_pg!(
+        &stmt,
+        r#"SELECT * FROM (SELECT "users0"."id" FROM "users" AS "users0" UNION ALL SELECT "orders0"."user_id" FROM "orders" AS "orders0") AS "sq0" INTERSECT SELECT "companies0"."owner_id" FROM "companies" AS "companies0" EXCEPT SELECT "products0"."owner_id" FROM "products" AS "products0""#
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"SELECT * FROM (SELECT "users0"."id" FROM "users" AS "users0" UNION ALL SELECT "orders0"."user_id" FROM "orders" AS "orders0") AS "sq0" INTERSECT SELECT "companies0"."owner_id" FROM "companies" AS "companies0" EXCEPT SELECT "products0"."owner_id" FROM "products" AS "products0""#
+    );
+}
+
+#[test]
+fn test_set_op__order_by_alias() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id").alias("uid"))
+        .union(SelectStatement::from(&*ORDERS).select(ORDERS.column("user_id")))
+        .order_by_desc(USERS.column("id"));
+    // 组合结果的列名取自第一个操作数，带别名时按别名排序
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."id" AS "uid" FROM "users" AS "users0" UNION SELECT "orders0"."user_id" FROM "orders" AS "orders0" ORDER BY "uid" DESC"#
+    );
+}
+
+#[test]
+fn test_set_op__with_params() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("age").gt(18))
+        .union_all(
+            SelectStatement::from(&*ORDERS)
+                .select(ORDERS.column("user_id"))
+                .filter(ORDERS.column("total").gt(100)),
+        );
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."age" > $1 UNION ALL SELECT "orders0"."user_id" FROM "orders" AS "orders0" WHERE "orders0"."total" > $2"#,
+        [18_i64, 100_i64]
+    );
+}
+
+#[test]
+fn test_set_op__outer_order_by_and_limit() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .union(SelectStatement::from(&*ORDERS).select(ORDERS.column("user_id")))
+        .order_by_desc(USERS.column("id"))
+        .limit(10)
+        .offset(20);
+    assert_mysql!(
+        &stmt,
+        "SELECT `users0`.`id` FROM `users` AS `users0` UNION SELECT `orders0`.`user_id` FROM `orders` AS `orders0` ORDER BY `id` DESC LIMIT 10 OFFSET 20"
+    );
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" UNION SELECT "orders0"."user_id" FROM "orders" AS "orders0" ORDER BY "id" DESC LIMIT 10 OFFSET 20"#
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" UNION SELECT "orders0"."user_id" FROM "orders" AS "orders0" ORDER BY "id" DESC LIMIT 10 OFFSET 20"#
+    );
+}
+
+#[test]
+fn test_set_op__operand_with_limit() {
+    let top = SelectStatement::from(&*ORDERS)
+        .select(ORDERS.column("user_id"))
+        .order_by_desc(ORDERS.column("total"))
+        .limit(3);
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).union(top);
+    assert_mysql!(
+        &stmt,
+        "SELECT `users0`.`id` FROM `users` AS `users0` UNION (SELECT `orders0`.`user_id` FROM `orders` AS `orders0` ORDER BY `orders0`.`total` DESC LIMIT 3)"
+    );
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" UNION (SELECT "orders0"."user_id" FROM "orders" AS "orders0" ORDER BY "orders0"."total" DESC LIMIT 3)"#
+    );
+    // SQLite 不接受带括号的操作数，包成派生表
+    assert_sqlite!(
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" UNION SELECT * FROM (SELECT "orders0"."user_id" FROM "orders" AS "orders0" ORDER BY "orders0"."total" DESC LIMIT 3) AS "sq0""#
+    );
+}
+
+#[test]
+fn test_set_op__first_operand_with_limit() {
+    let top = SelectStatement::from(&*ORDERS).select(ORDERS.column("user_id")).limit(3);
+    let stmt = top
+        .union(SelectStatement::from(&*USERS).select(USERS.column("id")))
+        .limit(10);
+    assert_pg!(
+        &stmt,
+        r#"SELECT * FROM (SELECT "orders0"."user_id" FROM "orders" AS "orders0" LIMIT 3) AS "sq0" UNION SELECT "users0"."id" FROM "users" AS "users0" LIMIT 10"#
+    );
+}
+
+#[test]
+fn test_set_op__nested() {
+    let a = SelectStatement::from(&*USERS).select(USERS.column("id"));
+    let b = SelectStatement::from(&*ORDERS).select(ORDERS.column("user_id"));
+    let c = SelectStatement::from(&*COMPANIES).select(COMPANIES.column("owner_id"));
+    let stmt = a.except(b.union(c));
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" EXCEPT (SELECT "orders0"."user_id" FROM "orders" AS "orders0" UNION SELECT "companies0"."owner_id" FROM "companies" AS "companies0")"#
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" EXCEPT SELECT * FROM (SELECT "orders0"."user_id" FROM "orders" AS "orders0" UNION SELECT "companies0"."owner_id" FROM "companies" AS "companies0") AS "sq0""#
+    );
+}
+
+#[test]
+fn test_set_op__as_table() {
+    let ids = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .union(SelectStatement::from(&*ORDERS).select(ORDERS.column("user_id")));
+    let t = Table::from(ids);
+    let stmt = SelectStatement::from(&t).select(count_all());
+    assert_pg!(
+        &stmt,
+        r#"SELECT COUNT(*) FROM (SELECT "users0"."id" FROM "users" AS "users0" UNION SELECT "orders0"."user_id" FROM "orders" AS "orders0") AS "sq0""#
+    );
+}
+
+#[test]
+fn test_set_op__as_subquery_and_in() {
+    let ids = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("vip").eq(true))
+        .union(SelectStatement::from(&*ORDERS).select(ORDERS.column("user_id")));
+    let stmt = SelectStatement::from(&*COMPANIES)
+        .select(COMPANIES.column("name"))
+        .filter(COMPANIES.column("owner_id").in_(vec![ids.clone()]));
+    assert_mysql!(
+        &stmt,
+        "SELECT `companies0`.`name` FROM `companies` AS `companies0` WHERE `companies0`.`owner_id` IN ((SELECT `users0`.`id` FROM `users` AS `users0` WHERE `users0`.`vip` = ? UNION SELECT `orders0`.`user_id` FROM `orders` AS `orders0`))",
+        [true]
+    );
+
+    let stmt = SelectStatement::from(&*COMPANIES).select(Expr::from(ids));
+    assert_pg!(
+        &stmt,
+        r#"SELECT (SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."vip" = $1 UNION SELECT "orders0"."user_id" FROM "orders" AS "orders0") FROM "companies" AS "companies0""#,
+        [true]
+    );
+}
+
+#[test]
+fn test_set_op__recursive_cte() {
+    let tree = Table::new("tree");
+    let categories = CATEGORIES.fork();
+    let base = SelectStatement::from(&*CATEGORIES)
+        .select([CATEGORIES.column("id"), CATEGORIES.column("parent_id")])
+        .filter(CATEGORIES.column("id").eq(1));
+    let step = SelectStatement::from(&categories)
+        .select([categories.column("id"), categories.column("parent_id")])
+        .join(&tree, categories.column("parent_id").eq(tree.column("id")));
+    let stmt = SelectStatement::from(&tree)
+        .with_recursive("tree", base.union_all(step))
+        .select(tree.column("id"));
+
+    assert_pg!(
+        &stmt,
+        r#"WITH RECURSIVE "tree" AS (SELECT "categories0"."id", "categories0"."parent_id" FROM "categories" AS "categories0" WHERE "categories0"."id" = $1 UNION ALL SELECT "categories1"."id", "categories1"."parent_id" FROM "categories" AS "categories1" INNER JOIN "tree" ON "categories1"."parent_id" = "tree"."id") SELECT "tree"."id" FROM "tree""#,
+        [1_i64]
+    );
+    // Oracle 同样只写 WITH，递归 CTE 必须写出列名
+    let err = oracle().strict().visit_select_statement(&stmt).try_finish().unwrap_err();
+    assert_eq!(
+        err,
+        RenderError::unsupported("WITH RECURSIVE", "a recursive CTE needs a column list in the dialect")
+    );
+    let mut with_columns = stmt.clone();
+    with_columns.with_clause[0].columns = vec!["id".to_string(), "parent_id".to_string()];
+    let (sql, _, _) = oracle().strict().visit_select_statement(&with_columns).try_finish().unwrap();
+    assert!(sql.starts_with(r#"WITH "TREE" ("ID", "PARENT_ID") AS (SELECT"#));
+    // SQL Server 没有 RECURSIVE 关键字
+    assert_mssql!(
+        &stmt,
+        "WITH [tree] AS (SELECT [categories0].[id], [categories0].[parent_id] FROM [categories] AS [categories0] WHERE [categories0].[id] = @p1 UNION ALL SELECT [categories1].[id], [categories1].[parent_id] FROM [categories] AS [categories1] INNER JOIN [tree] ON [categories1].[parent_id] = [tree].[id]) SELECT [tree].[id] FROM [tree]",
+        [1_i64]
+    );
+}
+
+#[test]
+fn test_window__row_number_partition_order() {
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("name")).select(
+        row_number()
+            .over(Window::new().partition_by(USERS.column("dept")).order_by_desc(USERS.column("salary")))
+            .alias("rn"),
+    );
+    assert_mysql!(
+        &stmt,
+        "SELECT `users0`.`name`, ROW_NUMBER() OVER (PARTITION BY `users0`.`dept` ORDER BY `users0`.`salary` DESC) AS `rn` FROM `users` AS `users0`"
+    );
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."name", ROW_NUMBER() OVER (PARTITION BY "users0"."dept" ORDER BY "users0"."salary" DESC) AS "rn" FROM "users" AS "users0""#
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"SELECT "users0"."name", ROW_NUMBER() OVER (PARTITION BY "users0"."dept" ORDER BY "users0"."salary" DESC) AS "rn" FROM "users" AS "users0""#
+    );
+}
+
+#[test]
+fn test_window__empty_over() {
+    let stmt = SelectStatement::from(&*USERS).select(count_all().over(Window::new()).alias("total"));
+    assert_pg!(
+        &stmt,
+        r#"SELECT COUNT(*) OVER () AS "total" FROM "users" AS "users0""#
+    );
+}
+
+#[test]
+fn test_window__ranking_functions() {
+    let w = Window::new().order_by_desc(USERS.column("score"));
+    let stmt = SelectStatement::from(&*USERS).select([
+        rank().over(w.clone()).alias("r"),
+        dense_rank().over(w.clone()).alias("dr"),
+        ntile(lit(4)).over(w).alias("quartile"),
+    ]);
+    assert_mysql!(
+        &stmt,
+        "SELECT RANK() OVER (ORDER BY `users0`.`score` DESC) AS `r`, DENSE_RANK() OVER (ORDER BY `users0`.`score` DESC) AS `dr`, NTILE(4) OVER (ORDER BY `users0`.`score` DESC) AS `quartile` FROM `users` AS `users0`"
+    );
+}
+
+#[test]
+fn test_window__lag_and_first_value() {
+    let w = Window::new().partition_by(ORDERS.column("user_id")).order_by(ORDERS.column("created_at"));
+    let stmt = SelectStatement::from(&*ORDERS).select([
+        lag(vec![Expr::from(ORDERS.column("amount")), Expr::from(1)]).over(w.clone()).alias("prev"),
+        first_value(ORDERS.column("amount")).over(w).alias("first"),
+    ]);
+    assert_pg!(
+        &stmt,
+        r#"SELECT LAG("orders0"."amount", $1) OVER (PARTITION BY "orders0"."user_id" ORDER BY "orders0"."created_at") AS "prev", FIRST_VALUE("orders0"."amount") OVER (PARTITION BY "orders0"."user_id" ORDER BY "orders0"."created_at") AS "first" FROM "orders" AS "orders0""#,
+        [1_i64]
+    );
+}
+
+#[test]
+fn test_window__running_total_frame() {
+    let stmt = SelectStatement::from(&*ORDERS).select(
+        sum(ORDERS.column("amount"))
+            .over(
+                Window::new()
+                    .partition_by(ORDERS.column("user_id"))
+                    .order_by(ORDERS.column("created_at"))
+                    .rows_between(FrameBound::UnboundedPreceding, FrameBound::CurrentRow),
+            )
+            .alias("running_total"),
+    );
+    assert_mysql!(
+        &stmt,
+        "SELECT SUM(`orders0`.`amount`) OVER (PARTITION BY `orders0`.`user_id` ORDER BY `orders0`.`created_at` ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) AS `running_total` FROM `orders` AS `orders0`"
+    );
+}
+
+#[test]
+fn test_window__frame_variants() {
+    let stmt = SelectStatement::from(&*ORDERS).select([
+        avg(ORDERS.column("amount"))
+            .over(Window::new().order_by(ORDERS.column("id")).rows_between(FrameBound::Preceding(2), FrameBound::Following(2)))
+            .alias("a"),
+        max(ORDERS.column("amount"))
+            .over(Window::new().order_by(ORDERS.column("id")).range(FrameBound::UnboundedPreceding))
+            .alias("b"),
+        min(ORDERS.column("amount"))
+            .over(
+                Window::new()
+                    .order_by(ORDERS.column("id"))
+                    .groups_between(FrameBound::CurrentRow, FrameBound::UnboundedFollowing),
+            )
+            .alias("c"),
+    ]);
+    assert_pg!(
+        &stmt,
+        r#"SELECT AVG("orders0"."amount") OVER (ORDER BY "orders0"."id" ROWS BETWEEN 2 PRECEDING AND 2 FOLLOWING) AS "a", MAX("orders0"."amount") OVER (ORDER BY "orders0"."id" RANGE UNBOUNDED PRECEDING) AS "b", MIN("orders0"."amount") OVER (ORDER BY "orders0"."id" GROUPS BETWEEN CURRENT ROW AND UNBOUNDED FOLLOWING) AS "c" FROM "orders" AS "orders0""#
+    );
+    // MySQL 没有 GROUPS 帧
+    let err = mysql().try_visit_select_statement(&stmt).err().unwrap();
+    assert_eq!(err, RenderError::unsupported("GROUPS", "the dialect has no GROUPS window frame"));
+}
+
+#[test]
+fn test_window__named_window_clause() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select([
+            row_number().over_window("w").alias("rn"),
+            sum(USERS.column("salary"))
+                .over(Window::named("w").rows(FrameBound::UnboundedPreceding))
+                .alias("acc"),
+        ])
+        .window("w", Window::new().partition_by(USERS.column("dept")).order_by(USERS.column("salary")));
+    assert_mysql!(
+        &stmt,
+        "SELECT ROW_NUMBER() OVER `w` AS `rn`, SUM(`users0`.`salary`) OVER (`w` ROWS UNBOUNDED PRECEDING) AS `acc` FROM `users` AS `users0` WINDOW `w` AS (PARTITION BY `users0`.`dept` ORDER BY `users0`.`salary`)"
+    );
+    assert_pg!(
+        &stmt,
+        r#"SELECT ROW_NUMBER() OVER "w" AS "rn", SUM("users0"."salary") OVER ("w" ROWS UNBOUNDED PRECEDING) AS "acc" FROM "users" AS "users0" WINDOW "w" AS (PARTITION BY "users0"."dept" ORDER BY "users0"."salary")"#
+    );
+}
+
+#[test]
+fn test_window__in_derived_table_for_top_n() {
+    let ranked = SelectStatement::from(&*USERS).select(USERS.column("name")).select(
+        row_number()
+            .over(Window::new().partition_by(USERS.column("dept")).order_by_desc(USERS.column("salary")))
+            .alias("rn"),
+    );
+    let sq = Table::from(ranked);
+    let stmt = SelectStatement::from(&sq)
+        .select(sq.column("name"))
+        .filter(sq.column("rn").lte(3));
+    assert_pg!(
+        &stmt,
+        r#"SELECT "sq0"."name" FROM (SELECT "users0"."name", ROW_NUMBER() OVER (PARTITION BY "users0"."dept" ORDER BY "users0"."salary" DESC) AS "rn" FROM "users" AS "users0") AS "sq0" WHERE "sq0"."rn" <= $1"#,
+        [3_i64]
+    );
+}
+
+#[test]
+fn test_case__searched() {
+    let stmt = SelectStatement::from(&*PRODUCTS).select(
+        case()
+            .when(PRODUCTS.column("price").gt(100), lit("expensive"))
+            .when(PRODUCTS.column("price").gt(10), lit("normal"))
+            .otherwise(lit("cheap"))
+            .alias("level"),
+    );
+    assert_mysql!(
+        &stmt,
+        "SELECT CASE WHEN `products0`.`price` > ? THEN 'expensive' WHEN `products0`.`price` > ? THEN 'normal' ELSE 'cheap' END AS `level` FROM `products` AS `products0`",
+        [100_i64, 10_i64]
+    );
+    assert_pg!(
+        &stmt,
+        r#"SELECT CASE WHEN "products0"."price" > $1 THEN 'expensive' WHEN "products0"."price" > $2 THEN 'normal' ELSE 'cheap' END AS "level" FROM "products" AS "products0""#,
+        [100_i64, 10_i64]
+    );
+}
+
+#[test]
+fn test_case__simple_without_else() {
+    let stmt = SelectStatement::from(&*USERS).select(
+        case_of(USERS.column("status"))
+            .when(1, "active")
+            .when(2, "banned")
+            .alias("status_name"),
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"SELECT CASE "users0"."status" WHEN ? THEN ? WHEN ? THEN ? END AS "status_name" FROM "users" AS "users0""#,
+        [1_i64, "active", 2_i64, "banned"]
+    );
+}
+
+#[test]
+fn test_case__in_aggregate() {
+    let stmt = SelectStatement::from(&*ORDERS).select(
+        sum(case()
+            .when(ORDERS.column("status").eq("paid"), ORDERS.column("amount"))
+            .otherwise(lit(0)))
+        .alias("paid_total"),
+    );
+    assert_pg!(
+        &stmt,
+        r#"SELECT SUM(CASE WHEN "orders0"."status" = $1 THEN "orders0"."amount" ELSE 0 END) AS "paid_total" FROM "orders" AS "orders0""#,
+        ["paid"]
+    );
+}
+
+#[test]
+fn test_case__order_by_and_group_by() {
+    let bucket = case().when(USERS.column("age").lt(18), lit("minor")).otherwise(lit("adult"));
+    let stmt = SelectStatement::from(&*USERS)
+        .select(bucket.clone())
+        .select(count_all().alias("n"))
+        .group_by(bucket.clone())
+        .order_by(bucket);
+    assert_mysql!(
+        &stmt,
+        "SELECT CASE WHEN `users0`.`age` < ? THEN 'minor' ELSE 'adult' END, COUNT(*) AS `n` FROM `users` AS `users0` GROUP BY CASE WHEN `users0`.`age` < ? THEN 'minor' ELSE 'adult' END ORDER BY CASE WHEN `users0`.`age` < ? THEN 'minor' ELSE 'adult' END",
+        [18_i64, 18_i64, 18_i64]
+    );
+}
+
+#[test]
+fn test_case__compare_and_filter() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(case_of(USERS.column("role")).when("admin", 1).otherwise(0).eq(1));
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE CASE "users0"."role" WHEN $1 THEN $2 ELSE $3 END = $4"#,
+        ["admin", 1_i64, 0_i64, 1_i64]
+    );
+}
+
+#[test]
+fn test_case__subquery_in_branch() {
+    let latest = SelectStatement::from(&*ORDERS)
+        .select(max(ORDERS.column("amount")))
+        .filter(ORDERS.column("user_id").eq(USERS.column("id")));
+    let stmt = SelectStatement::from(&*USERS).select(
+        case()
+            .when(USERS.column("vip").eq(true), latest)
+            .otherwise(lit(0))
+            .alias("amount"),
+    );
+    assert_pg!(
+        &stmt,
+        r#"SELECT CASE WHEN "users0"."vip" = $1 THEN (SELECT MAX("orders0"."amount") FROM "orders" AS "orders0" WHERE "orders0"."user_id" = "users0"."id") ELSE 0 END AS "amount" FROM "users" AS "users0""#,
+        [true]
+    );
+}
+
+#[test]
+fn test_case__without_when() {
+    let stmt = SelectStatement::from(&*USERS).select(case().otherwise(lit(0)).alias("amount"));
+    let err = postgre().try_visit_select_statement(&stmt).err().unwrap();
+    assert_eq!(err, RenderError::invalid("CASE", "it needs at least one WHEN"));
+}
+
+#[test]
+fn test_cast__integer_and_text() {
+    let stmt = SelectStatement::from(&*USERS).select([
+        cast(USERS.column("age"), ColumnType::Text).alias("age_text"),
+        cast(USERS.column("score"), ColumnType::Int).alias("score_int"),
+    ]);
+    assert_mysql!(
+        &stmt,
+        "SELECT CAST(`users0`.`age` AS CHAR) AS `age_text`, CAST(`users0`.`score` AS SIGNED) AS `score_int` FROM `users` AS `users0`"
+    );
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."age"::TEXT AS "age_text", "users0"."score"::INTEGER AS "score_int" FROM "users" AS "users0""#
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"SELECT CAST("users0"."age" AS TEXT) AS "age_text", CAST("users0"."score" AS INTEGER) AS "score_int" FROM "users" AS "users0""#
+    );
+}
+
+#[test]
+fn test_cast__decimal_and_date() {
+    let stmt = SelectStatement::from(&*ORDERS).select([
+        cast(ORDERS.column("amount"), ColumnType::Decimal { precision: Some(10), scale: Some(2) }),
+        cast(ORDERS.column("created_at"), ColumnType::Date),
+    ]);
+    assert_mysql!(
+        &stmt,
+        "SELECT CAST(`orders0`.`amount` AS DECIMAL(10, 2)), CAST(`orders0`.`created_at` AS DATE) FROM `orders` AS `orders0`"
+    );
+    assert_pg!(
+        &stmt,
+        r#"SELECT "orders0"."amount"::NUMERIC(10, 2), "orders0"."created_at"::DATE FROM "orders" AS "orders0""#
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"SELECT CAST("orders0"."amount" AS NUMERIC), CAST("orders0"."created_at" AS TEXT) FROM "orders" AS "orders0""#
+    );
+}
+
+#[test]
+fn test_cast__expression_operand() {
+    let stmt = SelectStatement::from(&*ORDERS)
+        .select(cast(ORDERS.column("price") * ORDERS.column("quantity"), ColumnType::BigInt))
+        .filter(cast(ORDERS.column("paid_at"), ColumnType::Date).eq("2024-01-01"));
+    assert_mysql!(
+        &stmt,
+        "SELECT CAST(`orders0`.`price` * `orders0`.`quantity` AS SIGNED) FROM `orders` AS `orders0` WHERE CAST(`orders0`.`paid_at` AS DATE) = ?",
+        ["2024-01-01"]
+    );
+    assert_pg!(
+        &stmt,
+        r#"SELECT ("orders0"."price" * "orders0"."quantity")::BIGINT FROM "orders" AS "orders0" WHERE "orders0"."paid_at"::DATE = $1"#,
+        ["2024-01-01"]
+    );
+}
+
+#[test]
+fn test_cast__param_operand() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("id").eq(cast("42", ColumnType::Int)));
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."id" = $1::INTEGER"#,
+        ["42"]
+    );
+}
+
+#[test]
+fn test_cast__unsupported_type() {
+    let stmt = SelectStatement::from(&*USERS).select(cast(USERS.column("age"), ColumnType::UnsignedInt));
+    let err = postgre().visit_select_statement(&stmt).try_finish().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "RenderError: UnsupportedCast: PostgreSQL can not CAST to UnsignedInt"
+    );
+}
+
+#[test]
+fn test_cast__unsupported_type_finish_keeps_errors() {
+    let stmt = SelectStatement::from(&*USERS).select(cast(USERS.column("age"), ColumnType::UnsignedInt));
+    // finish 不会 panic，错误留在 errors() 中
+    let mut visitor = postgre();
+    let (sql, _, _) = visitor.visit_select_statement(&stmt).finish();
+    assert!(sql.starts_with("SELECT "));
+    assert_eq!(visitor.errors().len(), 1);
+}
+
+#[test]
+fn test_strict__lenient_is_default() {
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).for_update();
+    let (sql, _, _) = sqlite().try_visit_select_statement(&stmt).unwrap().try_finish().unwrap();
+    assert_eq!(sql, r#"SELECT "users0"."id" FROM "users" AS "users0""#);
+}
+
+#[test]
+fn test_strict__locking_on_sqlite() {
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).for_update();
+    let err = sqlite().strict().try_visit_select_statement(&stmt).err().unwrap();
+    assert_eq!(
+        err,
+        RenderError::unsupported("FOR UPDATE", "the dialect has no row-level locking")
+    );
+    assert_eq!(
+        err.to_string(),
+        "RenderError: FOR UPDATE is not supported, the dialect has no row-level locking"
+    );
+
+    // 支持的方言不受影响
+    let (sql, _, _) = mysql().strict().visit_select_statement(&stmt).try_finish().unwrap();
+    assert_eq!(sql, "SELECT `users0`.`id` FROM `users` AS `users0` FOR UPDATE");
+}
+
+#[test]
+fn test_strict__locking_on_compound_query() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .union(SelectStatement::from(&*ORDERS).select(ORDERS.column("user_id")))
+        .for_update();
+    let err = postgre().mode(RenderMode::Strict).visit_select_statement(&stmt).try_finish().unwrap_err();
+    assert_eq!(
+        err,
+        RenderError::unsupported("FOR UPDATE", "a compound query can not be locked")
+    );
+}
+
+#[test]
+fn test_strict__standalone_offset_on_mysql() {
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).offset(20);
+    let err = mysql().strict().visit_select_statement(&stmt).try_finish().unwrap_err();
+    assert_eq!(
+        err,
+        RenderError::unsupported("OFFSET", "the dialect requires a LIMIT before OFFSET")
+    );
+    assert!(sqlite().strict().visit_select_statement(&stmt).try_finish().is_ok());
+}
+
+#[test]
+fn test_strict__index_hints() {
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).use_index("idx_name");
+    let err = sqlite().strict().visit_select_statement(&stmt).try_finish().unwrap_err();
+    assert_eq!(
+        err,
+        RenderError::unsupported("USE INDEX", "the dialect has no such index hint")
+    );
+
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .force_index(["idx_name", "idx_age"]);
+    let err = sqlite().strict().visit_select_statement(&stmt).try_finish().unwrap_err();
+    assert_eq!(
+        err,
+        RenderError::unsupported("FORCE INDEX", "the dialect accepts only one index, the rest are dropped")
+    );
+    assert!(mysql().strict().visit_select_statement(&stmt).try_finish().is_ok());
+}
+
+#[test]
+fn test_strict__distinct_on() {
+    let stmt = SelectStatement::from(&*USERS)
+        .distinct_on([USERS.column("email")])
+        .select(USERS.column("id"));
+    let err = mysql().strict().visit_select_statement(&stmt).try_finish().unwrap_err();
+    assert_eq!(
+        err,
+        RenderError::unsupported("DISTINCT ON", "the dialect falls back to a plain DISTINCT")
+    );
+    assert!(postgre().strict().visit_select_statement(&stmt).try_finish().is_ok());
+}
+
+#[test]
+fn test_strict__count_distinct_outside_select_list() {
+    // SQLite 只能改写 SELECT 列表顶层的 COUNT(DISTINCT a, b)，无论哪种模式都报错
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("dept"))
+        .group_by(USERS.column("dept"))
+        .having(count([USERS.column("name"), USERS.column("email")]).distinct().gt(1));
+    let mut visitor = sqlite();
+    let err = visitor.visit_select_statement(&stmt).try_finish().unwrap_err();
+    assert_eq!(
+        err,
+        RenderError::unsupported(
+            "COUNT(DISTINCT) with multiple columns",
+            "it can only be rewritten as a top-level select item"
+        )
+    );
+    assert_eq!(visitor.errors().len(), 1);
+}
+
+#[test]
+fn test_mssql__quote_and_placeholder() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select([USERS.column("id"), USERS.column("name")])
+        .filter(USERS.column("age").gt(18))
+        .filter(USERS.column("active").eq(true));
+    assert_mssql!(
+        &stmt,
+        "SELECT [users0].[id], [users0].[name] FROM [users] AS [users0] WHERE [users0].[age] > @p1 AND [users0].[active] = @p2",
+        [18_i64, true]
+    );
+
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).filter(USERS.column("active").eq(lit(true)));
+    assert_mssql!(
+        &stmt,
+        "SELECT [users0].[id] FROM [users] AS [users0] WHERE [users0].[active] = 1"
+    );
+}
+
+#[test]
+fn test_mssql__top() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .order_by_desc(USERS.column("score"))
+        .limit(10);
+    assert_mssql!(
+        &stmt,
+        "SELECT TOP 10 [users0].[id] FROM [users] AS [users0] ORDER BY [users0].[score] DESC"
+    );
+
+    let stmt = SelectStatement::from(&*USERS).distinct().select(USERS.column("name")).limit(5);
+    assert_mssql!(&stmt, "SELECT DISTINCT TOP 5 [users0].[name] FROM [users] AS [users0]");
+}
+
+#[test]
+fn test_mssql__offset_fetch() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .order_by(USERS.column("id"))
+        .limit(10)
+        .offset(20);
+    assert_mssql!(
+        &stmt,
+        "SELECT [users0].[id] FROM [users] AS [users0] ORDER BY [users0].[id] OFFSET 20 ROWS FETCH NEXT 10 ROWS ONLY"
+    );
+
+    // OFFSET ... FETCH 必须有 ORDER BY
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).offset(20);
+    assert_mssql!(
+        &stmt,
+        "SELECT [users0].[id] FROM [users] AS [users0] ORDER BY (SELECT NULL) OFFSET 20 ROWS"
+    );
+}
+
+#[test]
+fn test_mssql__compound_paging() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .union(SelectStatement::from(&*ORDERS).select(ORDERS.column("user_id")))
+        .limit(10);
+    assert_mssql!(
+        &stmt,
+        "SELECT [users0].[id] FROM [users] AS [users0] UNION SELECT [orders0].[user_id] FROM [orders] AS [orders0] ORDER BY (SELECT NULL) OFFSET 0 ROWS FETCH NEXT 10 ROWS ONLY"
+    );
+}
+
+#[test]
+fn test_mssql__lock_hints() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("id").eq(1))
+        .for_update();
+    assert_mssql!(
+        &stmt,
+        "SELECT [users0].[id] FROM [users] AS [users0] WITH (UPDLOCK, ROWLOCK) WHERE [users0].[id] = @p1",
+        [1_i64]
+    );
+
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).for_update().skip();
+    assert_mssql!(
+        &stmt,
+        "SELECT [users0].[id] FROM [users] AS [users0] WITH (UPDLOCK, ROWLOCK, READPAST)"
+    );
+
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).for_share().no_wait();
+    assert_mssql!(
+        &stmt,
+        "SELECT [users0].[id] FROM [users] AS [users0] WITH (HOLDLOCK, ROWLOCK, NOWAIT)"
+    );
+}
+
+#[test]
+fn test_mssql__lock_hints_with_join() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .join(&*ORDERS, ORDERS.column("user_id").eq(USERS.column("id")))
+        .for_update();
+    assert_mssql!(
+        &stmt,
+        "SELECT [users0].[id] FROM [users] AS [users0] WITH (UPDLOCK, ROWLOCK) INNER JOIN [orders] AS [orders0] WITH (UPDLOCK, ROWLOCK) ON [orders0].[user_id] = [users0].[id]"
+    );
+
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .join(&*ORDERS, ORDERS.column("user_id").eq(USERS.column("id")))
+        .for_update_of(ORDERS.clone());
+    assert_mssql!(
+        &stmt,
+        "SELECT [users0].[id] FROM [users] AS [users0] INNER JOIN [orders] AS [orders0] WITH (UPDLOCK, ROWLOCK) ON [orders0].[user_id] = [users0].[id]"
+    );
+}
+
+#[test]
+fn test_mssql__index_hints() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .force_index(["idx_users_email", "idx_user_name"])
+        .for_update();
+    assert_mssql!(
+        &stmt,
+        "SELECT [users0].[id] FROM [users] AS [users0] WITH (INDEX([idx_users_email], [idx_user_name]), UPDLOCK, ROWLOCK)"
+    );
+
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).use_index("idx_users_email");
+    assert_mssql!(&stmt, "SELECT [users0].[id] FROM [users] AS [users0]");
+    let err = mssql().strict().visit_select_statement(&stmt).try_finish().unwrap_err();
+    assert_eq!(
+        err,
+        RenderError::unsupported("USE INDEX", "the dialect has no such index hint")
+    );
+}
+
+#[test]
+fn test_mssql__cast() {
+    let stmt = SelectStatement::from(&*USERS).select(cast(USERS.column("age"), ColumnType::Text));
+    assert_mssql!(&stmt, "SELECT CAST([users0].[age] AS NVARCHAR(MAX)) FROM [users] AS [users0]");
+}
+
+#[test]
+fn test_locker__for_update_of() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .join(&*ORDERS, ORDERS.column("user_id").eq(USERS.column("id")))
+        .for_update_of(ORDERS.clone())
+        .no_wait();
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" INNER JOIN "orders" AS "orders0" ON "orders0"."user_id" = "users0"."id" FOR UPDATE OF "orders0" NOWAIT"#
+    );
+
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .join(&*ORDERS, ORDERS.column("user_id").eq(USERS.column("id")))
+        .for_update_of_columns([ORDERS.column("status"), ORDERS.column("amount")]);
+    assert_mysql!(
+        &stmt,
+        "SELECT `users0`.`id` FROM `users` AS `users0` INNER JOIN `orders` AS `orders0` ON `orders0`.`user_id` = `users0`.`id` FOR UPDATE OF `orders0`"
+    );
+}
+
+#[test]
+fn test_oracle__quote_alias_and_placeholder() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .select(USERS.column("name").alias("user_name"))
+        .join(&*ORDERS, ORDERS.column("user_id").eq(USERS.column("id")))
+        .filter(USERS.column("age").gt(18))
+        .filter(USERS.column("active").eq(true));
+    assert_oracle!(
+        &stmt,
+        r#"SELECT "USERS0"."ID", "USERS0"."NAME" AS "USER_NAME" FROM "USERS" "USERS0" INNER JOIN "ORDERS" "ORDERS0" ON "ORDERS0"."USER_ID" = "USERS0"."ID" WHERE "USERS0"."AGE" > :1 AND "USERS0"."ACTIVE" = :2"#,
+        [18_i64, true]
+    );
+}
+
+#[test]
+fn test_oracle__bool_literal() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("active").eq(lit(true)))
+        .filter(USERS.column("deleted").eq(lit(false)));
+    assert_oracle!(
+        &stmt,
+        r#"SELECT "USERS0"."ID" FROM "USERS" "USERS0" WHERE "USERS0"."ACTIVE" = 1 AND "USERS0"."DELETED" = 0"#
+    );
+}
+
+#[test]
+fn test_oracle__fetch_paging() {
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).limit(10);
+    assert_oracle!(
+        &stmt,
+        r#"SELECT "USERS0"."ID" FROM "USERS" "USERS0" FETCH FIRST 10 ROWS ONLY"#
+    );
+
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .order_by(USERS.column("id"))
+        .limit(10)
+        .offset(20);
+    assert_oracle!(
+        &stmt,
+        r#"SELECT "USERS0"."ID" FROM "USERS" "USERS0" ORDER BY "USERS0"."ID" OFFSET 20 ROWS FETCH NEXT 10 ROWS ONLY"#
+    );
+
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).offset(20);
+    assert_oracle!(&stmt, r#"SELECT "USERS0"."ID" FROM "USERS" "USERS0" OFFSET 20 ROWS"#);
+}
+
+#[test]
+fn test_oracle__subquery_alias() {
+    let sq = Table::from(SelectStatement::from(&*ORDERS).select(ORDERS.column("user_id")));
+    let stmt = SelectStatement::from(&sq).select(sq.column("user_id"));
+    assert_oracle!(
+        &stmt,
+        r#"SELECT "SQ0"."USER_ID" FROM (SELECT "ORDERS0"."USER_ID" FROM "ORDERS" "ORDERS0") "SQ0""#
+    );
+}
+
+#[test]
+fn test_oracle__for_update_of_column() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("id").eq(1))
+        .for_update_of_columns(USERS.column("balance"))
+        .skip();
+    assert_oracle!(
+        &stmt,
+        r#"SELECT "USERS0"."ID" FROM "USERS" "USERS0" WHERE "USERS0"."ID" = :1 FOR UPDATE OF "USERS0"."BALANCE" SKIP LOCKED"#,
+        [1_i64]
+    );
+
+    // 按表加锁会退化为锁住所有行，严格模式下报错
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).for_update_of(USERS.clone());
+    assert_oracle!(&stmt, r#"SELECT "USERS0"."ID" FROM "USERS" "USERS0" FOR UPDATE"#);
+    let err = oracle().strict().visit_select_statement(&stmt).try_finish().unwrap_err();
+    assert_eq!(
+        err,
+        RenderError::unsupported("FOR UPDATE OF table", "the dialect locks by column, use for_update_of_columns")
+    );
+}
+
+#[test]
+fn test_oracle__cast() {
+    let stmt = SelectStatement::from(&*ORDERS).select(cast(
+        ORDERS.column("amount"),
+        ColumnType::Decimal { precision: Some(10), scale: Some(2) },
+    ));
+    assert_oracle!(
+        &stmt,
+        r#"SELECT CAST("ORDERS0"."AMOUNT" AS NUMBER(10, 2)) FROM "ORDERS" "ORDERS0""#
+    );
+}
+
+#[test]
+fn test_oracle__for_share() {
+    // Oracle 没有共享锁，退化为 FOR UPDATE，严格模式下报错
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).for_share().no_wait();
+    assert_oracle!(&stmt, r#"SELECT "USERS0"."ID" FROM "USERS" "USERS0" FOR UPDATE NOWAIT"#);
+    let err = oracle().strict().visit_select_statement(&stmt).try_finish().unwrap_err();
+    assert_eq!(err, RenderError::unsupported("FOR SHARE", "the dialect only has FOR UPDATE"));
+}
+
+#[test]
+fn test_oracle__long_in_list() {
+    // 单个 IN 列表最多 1000 个元素 (ORA-01795)
+    let ids: Vec<i64> = (1..=2001).collect();
+    let placeholders = |range: std::ops::RangeInclusive<usize>| {
+        range.map(|i| format!(":{}", i)).collect::<Vec<_>>().join(", ")
+    };
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).filter(USERS.column("id").in_(ids.clone()));
+    let (sql, params, _) = oracle().visit_select_statement(&stmt).try_finish().unwrap();
+    assert_eq!(
+        sql,
+        format!(
+            r#"SELECT "USERS0"."ID" FROM "USERS" "USERS0" WHERE ("USERS0"."ID" IN ({}) OR "USERS0"."ID" IN ({}) OR "USERS0"."ID" IN ({}))"#,
+            placeholders(1..=1000),
+            placeholders(1001..=2000),
+            placeholders(2001..=2001)
+        )
+    );
+    assert_eq!(params.len(), 2001);
+
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).filter(USERS.column("id").not_in(ids));
+    let (sql, _, _) = oracle().visit_select_statement(&stmt).try_finish().unwrap();
+    assert!(sql.contains(r#"WHERE ("USERS0"."ID" NOT IN (:1, "#));
+    assert!(sql.contains(r#":1000) AND "USERS0"."ID" NOT IN (:1001, "#));
+}
+
+#[test]
+fn test_duckdb__qualify() {
+    let rn = row_number().over(Window::new().partition_by(USERS.column("dept")).order_by_desc(USERS.column("salary")));
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("active").eq(true))
+        .qualify(rn.eq(1));
+    assert_duckdb!(
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."active" = $1 QUALIFY ROW_NUMBER() OVER (PARTITION BY "users0"."dept" ORDER BY "users0"."salary" DESC) = $2"#,
+        [true, 1_i64]
+    );
+}
+
+#[test]
+fn test_duckdb__group_by_all_and_sample() {
+    let stmt = SelectStatement::from(&*ORDERS)
+        .select(ORDERS.column("user_id"))
+        .select(sum(ORDERS.column("amount")))
+        .group_by_all()
+        .sample_percent(10.0);
+    assert_duckdb!(
+        &stmt,
+        r#"SELECT "orders0"."user_id", SUM("orders0"."amount") FROM "orders" AS "orders0" GROUP BY ALL USING SAMPLE 10%"#
+    );
+
+    let stmt = SelectStatement::from(&*ORDERS).select(ORDERS.column("id")).sample_rows(1000).limit(10);
+    assert_duckdb!(&stmt, r#"SELECT "orders0"."id" FROM "orders" AS "orders0" USING SAMPLE 1000 ROWS LIMIT 10"#);
+}
+
+#[test]
+fn test_clickhouse__final_sample_and_settings() {
+    let stmt = SelectStatement::from(&*ORDERS)
+        .select(ORDERS.column("id"))
+        .final_()
+        .sample_percent(10.0)
+        .filter(ORDERS.column("amount").gt(100))
+        .limit(10)
+        .setting("max_threads", 8)
+        .setting("optimize_read_in_order", 1);
+    assert_clickhouse!(
+        &stmt,
+        r#"SELECT "orders0"."id" FROM "orders" AS "orders0" FINAL SAMPLE 0.1 WHERE "orders0"."amount" > ? LIMIT 10 SETTINGS max_threads = 8, optimize_read_in_order = 1"#,
+        [100_i64]
+    );
+}
+
+#[test]
+fn test_clickhouse__limit_by_and_group_by_all() {
+    let stmt = SelectStatement::from(&*ORDERS)
+        .select(ORDERS.column("user_id"))
+        .select(ORDERS.column("amount"))
+        .order_by_desc(ORDERS.column("amount"))
+        .limit_by(3, ORDERS.column("user_id"))
+        .limit(100);
+    assert_clickhouse!(
+        &stmt,
+        r#"SELECT "orders0"."user_id", "orders0"."amount" FROM "orders" AS "orders0" ORDER BY "orders0"."amount" DESC LIMIT 3 BY "orders0"."user_id" LIMIT 100"#
+    );
+
+    let stmt = SelectStatement::from(&*ORDERS)
+        .select(ORDERS.column("user_id"))
+        .select(count_all())
+        .group_by_all()
+        .sample_rows(10000);
+    assert_clickhouse!(
+        &stmt,
+        r#"SELECT "orders0"."user_id", COUNT(*) FROM "orders" AS "orders0" SAMPLE 10000 GROUP BY ALL"#
+    );
+}
+
+#[test]
+fn test_strict__analytics_clauses() {
+    let stmt = SelectStatement::from(&*ORDERS)
+        .select(ORDERS.column("id"))
+        .final_()
+        .sample_rows(10)
+        .group_by_all()
+        .qualify(ORDERS.column("id").gt(1))
+        .limit_by(1, ORDERS.column("user_id"))
+        .setting("max_threads", 8);
+    // 宽松模式下丢弃不影响结果的子句，会改变结果的子句仍然报错
+    let mut visitor = postgre();
+    visitor.visit_select_statement(&stmt);
+    assert_eq!(
+        visitor.errors(),
+        &[
+            RenderError::unsupported("GROUP BY ALL", "the dialect has no GROUP BY ALL"),
+            RenderError::unsupported("QUALIFY", "the dialect has no QUALIFY clause"),
+            RenderError::unsupported("LIMIT BY", "the dialect has no LIMIT BY clause"),
+        ]
+    );
+    let stmt = SelectStatement::from(&*ORDERS)
+        .select(ORDERS.column("id"))
+        .final_()
+        .sample_rows(10)
+        .setting("max_threads", 8);
+    assert_pg!(&stmt, r#"SELECT "orders0"."id" FROM "orders" AS "orders0""#);
+
+    let stmt = stmt.group_by_all().qualify(ORDERS.column("id").gt(1)).limit_by(1, ORDERS.column("user_id"));
+    let mut visitor = postgre().strict();
+    visitor.visit_select_statement(&stmt);
+    assert_eq!(
+        visitor.errors(),
+        &[
+            RenderError::unsupported("FINAL", "the dialect has no FINAL modifier"),
+            RenderError::unsupported("GROUP BY ALL", "the dialect has no GROUP BY ALL"),
+            RenderError::unsupported("QUALIFY", "the dialect has no QUALIFY clause"),
+            RenderError::unsupported("SAMPLE", "the dialect has no SAMPLE clause"),
+            RenderError::unsupported("LIMIT BY", "the dialect has no LIMIT BY clause"),
+            RenderError::unsupported("SETTINGS", "the dialect has no SETTINGS clause"),
+        ]
+    );
+}
+
+#[test]
+fn test_dialect__binary_literal() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("avatar").eq(lit(vec![0x01_u8, 0xab])));
+    assert_mysql!(&stmt, "SELECT `users0`.`id` FROM `users` AS `users0` WHERE `users0`.`avatar` = X'01ab'");
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."avatar" = '\x01ab'::bytea"#
+    );
+    assert_mssql!(&stmt, "SELECT [users0].[id] FROM [users] AS [users0] WHERE [users0].[avatar] = 0x01ab");
+    assert_oracle!(
+        &stmt,
+        r#"SELECT "USERS0"."ID" FROM "USERS" "USERS0" WHERE "USERS0"."AVATAR" = HEXTORAW('01ab')"#
+    );
+}
+
+#[test]
+fn test_dialect__func_name_mapping() {
+    let stmt = SelectStatement::from(&*USERS).select(func("length", USERS.column("name")));
+    assert_mysql!(&stmt, "SELECT LENGTH(`users0`.`name`) FROM `users` AS `users0`");
+    assert_mssql!(&stmt, "SELECT LEN([users0].[name]) FROM [users] AS [users0]");
+}
+
+#[test]
+fn test_dialect__derived_from_existing() {
+    use crate::sequel::visitor::dialect::PlaceHolderStyle;
+    use crate::sequel::visitor::dialect::caps::{Capability, LimitCap};
+    use crate::sequel::visitor::dialect::derived::DialectBuilder;
+    use crate::sequel::visitor::dialect::mysql::MySQL;
+    use crate::sequel::visitor::visitor::Visitor;
+
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("active").eq(lit(true)))
+        .filter(USERS.column("age").gt(18))
+        .offset(20)
+        .limit(10);
+    let dialect = || {
+        DialectBuilder::new(MySQL {})
+            .caps(|c| Capability { limit: LimitCap::OffsetFetch, ..c })
+            .quote("\"", "\"")
+            .placeholder_style(PlaceHolderStyle::Numbered)
+            .bool_str("TRUE", "FALSE")
+            .build()
+    };
+    assert_dialect!(
+        Visitor::new(dialect()),
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."active" = TRUE AND "users0"."age" > $1 OFFSET 20 ROWS FETCH NEXT 10 ROWS ONLY"#,
+        [18_i64]
+    );
+
+    // 未覆盖的部分保持原方言的行为
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).offset(20);
+    let dialect = DialectBuilder::new(MySQL {}).build();
+    let mut visitor = Visitor::new(dialect).strict();
+    visitor.visit_select_statement(&stmt);
+    assert_eq!(
+        visitor.errors(),
+        &[RenderError::unsupported("OFFSET", "the dialect requires a LIMIT before OFFSET")]
+    );
+}
+
+#[test]
+fn test_dialect__custom_hooks() {
+    use crate::sequel::term::lock::{Lock, Wait};
+    use crate::sequel::visitor::dialect::caps::Capability;
+    use crate::sequel::visitor::dialect::mysql::MySQL;
+    use crate::sequel::visitor::dialect::{Dialect, PlaceHolderStyle, UnsupportedCast};
+    use crate::sequel::visitor::visitor::Visitor;
+    use std::borrow::Cow;
+
+    // MySQL 5.7 风格：LOCK IN SHARE MODE，IFNULL 代替 COALESCE
+    struct MySQL57 {}
+    impl Dialect for MySQL57 {
+        fn caps(&self) -> Capability {
+            MySQL {}.caps()
+        }
+        fn quote_char(&self) -> &'static str {
+            "`"
+        }
+        fn placeholder_style(&self) -> PlaceHolderStyle {
+            PlaceHolderStyle::QuestionMark
+        }
+        fn cast_type(&self, column_type: &ColumnType) -> Result<String, UnsupportedCast> {
+            MySQL {}.cast_type(column_type)
+        }
+        fn render_lock(&self, lock: &Lock, of: Option<&str>, wait: &Wait) -> String {
+            match lock {
+                Lock::Share => " LOCK IN SHARE MODE".to_string(),
+                _ => MySQL {}.render_lock(lock, of, wait),
+            }
+        }
+        fn render_func_name<'a>(&self, name: &'a str) -> Cow<'a, str> {
+            if name.eq_ignore_ascii_case("coalesce") { Cow::Borrowed("IFNULL") } else { Cow::Borrowed(name) }
+        }
+    }
+
+    let stmt = SelectStatement::from(&*USERS)
+        .select(coalesce([Expr::from(USERS.column("nickname")), Expr::from(USERS.column("name"))]))
+        .filter(USERS.column("active").eq(lit(true)))
+        .for_share();
+    assert_dialect!(
+        Visitor::new(MySQL57 {}),
+        &stmt,
+        "SELECT IFNULL(`users0`.`nickname`, `users0`.`name`) FROM `users` AS `users0` WHERE `users0`.`active` = TRUE LOCK IN SHARE MODE"
+    );
+
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).for_update().skip();
+    assert_dialect!(
+        Visitor::new(MySQL57 {}),
+        &stmt,
+        "SELECT `users0`.`id` FROM `users` AS `users0` FOR UPDATE SKIP LOCKED"
+    );
+}
+
+#[test]
+fn test_pretty__default_format() {
+    use crate::sequel::visitor::format::Format;
+
+    let paid = SelectStatement::from(&*ORDERS)
+        .select(ORDERS.column("user_id"))
+        .filter(ORDERS.column("status").eq("paid"));
+    let from = USERS.clone().join(&*COMPANIES, USERS.column("company_id").eq(COMPANIES.column("id")));
+    let stmt = SelectStatement::from(&from)
+        .select(vec![USERS.column("id"), USERS.column("name"), COMPANIES.column("name")])
+        .filter(USERS.column("id").in_(vec![paid]))
+        .filter(USERS.column("age").gt(18))
+        .order_by(USERS.column("id"))
+        .limit(10);
+    assert_dialect!(
+        crate::sequel::visitor::visitor::postgre().pretty(Format::default()),
+        &stmt,
+        r#"SELECT
+    "users0"."id",
+    "users0"."name",
+    "companies0"."name"
+FROM "users" AS "users0"
+    INNER JOIN "companies" AS "companies0" ON "users0"."company_id" = "companies0"."id"
+WHERE "users0"."id" IN ((
+    SELECT
+        "orders0"."user_id"
+    FROM "orders" AS "orders0"
+    WHERE "orders0"."status" = $1
+)) AND "users0"."age" > $2
+ORDER BY "users0"."id"
+LIMIT 10"#,
+        ["paid", 18]
+    );
+}
+
+#[test]
+fn test_pretty__options() {
+    use crate::sequel::visitor::dialect::clickhouse::ClickHouse;
+    use crate::sequel::visitor::format::{Format, KeywordCase};
+
+    let stmt = SelectStatement::from(&*USERS)
+        .distinct()
+        .select(vec![USERS.column("id"), USERS.column("name")])
+        .filter(exists(SelectStatement::from(&*ORDERS).filter(ORDERS.column("user_id").eq(USERS.column("id")))))
+        .filter(USERS.column("active").eq(lit(true)));
+    let format = Format {
+        indent: 2,
+        keyword_case: KeywordCase::Lower,
+        ..Format::default()
+    };
+    assert_dialect!(
+        crate::sequel::visitor::visitor::mysql().pretty(format),
+        &stmt,
+        "select distinct
+  `users0`.`id`,
+  `users0`.`name`
+from `users` as `users0`
+where exists((
+  select
+    *
+  from `orders` as `orders0`
+  where `orders0`.`user_id` = `users0`.`id`
+)) and `users0`.`active` = 1"
+    );
+
+    // 不展开子查询、列表不换行时，只有子句另起一行
+    let format = Format {
+        one_item_per_line: false,
+        indent_subqueries: false,
+        ..Format::default()
+    };
+    let (sql, _, _) = crate::sequel::visitor::visitor::mysql().pretty(format).visit_select_statement(&stmt).finish();
+    assert_eq!(
+        sql,
+        "SELECT DISTINCT `users0`.`id`, `users0`.`name`
+FROM `users` AS `users0`
+WHERE EXISTS((SELECT * FROM `orders` AS `orders0` WHERE `orders0`.`user_id` = `users0`.`id`)) AND `users0`.`active` = 1"
+    );
+
+    // 默认仍然是紧凑格式
+    let (sql, _, _) = crate::sequel::visitor::visitor::mysql().visit_select_statement(&stmt).finish();
+    assert!(!sql.contains('\n'));
+
+    // 只转换关键字，区分大小写的函数名保持原样
+    let format = Format {
+        keyword_case: KeywordCase::Lower,
+        one_item_per_line: false,
+        ..Format::default()
+    };
+    let sql = "SELECT toStartOfMonth(\"created_at\") AS \"month\" FROM \"orders\"";
+    assert_eq!(
+        crate::sequel::visitor::format::pretty(sql, &ClickHouse {}, &format),
+        "select toStartOfMonth(\"created_at\") as \"month\"\nfrom \"orders\""
+    );
+}
+
+#[test]
+fn test_debug_sql__inline_params() {
+    let uuid = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("name").eq("O'Brien \\ ?"))
+        .filter(USERS.column("note").eq(lit("is it?")))
+        .filter(USERS.column("birthday").eq(Date::new(2024, 1, 15).unwrap()))
+        .filter(USERS.column("token").eq(uuid))
+        .filter(USERS.column("profile").eq(serde_json::json!({"a": "b'c"})))
+        .filter(USERS.column("avatar").eq(vec![0x01_u8, 0xab]))
+        .filter(USERS.column("active").eq(true));
+
+    let mut visitor = crate::sequel::visitor::visitor::mysql();
+    visitor.visit_select_statement(&stmt);
+    assert_eq!(
+        visitor.to_debug_sql(),
+        r#"-- debug only, not for execution
+SELECT `users0`.`id` FROM `users` AS `users0` WHERE `users0`.`name` = 'O''Brien \\ ?' AND `users0`.`note` = 'is it?' AND `users0`.`birthday` = '2024-1-15' AND `users0`.`token` = '550e8400-e29b-41d4-a716-446655440000' AND `users0`.`profile` = '{"a":"b''c"}' AND `users0`.`avatar` = X'01ab' AND `users0`.`active` = 1"#
+    );
+    // finish 的结果不受影响
+    let (sql, params, _) = visitor.finish();
+    assert!(sql.ends_with("`users0`.`active` = ?"));
+    assert_eq!(params.len(), 6);
+
+    // 标准 SQL 的字符串中反斜杠不是转义符
+    let mut visitor = crate::sequel::visitor::visitor::postgre();
+    visitor.visit_select_statement(&stmt);
+    assert_eq!(
+        visitor.to_debug_sql(),
+        r#"-- debug only, not for execution
+SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."name" = 'O''Brien \ ?' AND "users0"."note" = 'is it?' AND "users0"."birthday" = '2024-1-15' AND "users0"."token" = '550e8400-e29b-41d4-a716-446655440000' AND "users0"."profile" = '{"a":"b''c"}' AND "users0"."avatar" = '\x01ab'::bytea AND "users0"."active" = true"#
+    );
+}
+
+#[test]
+fn test_fingerprint__normalize_literals_and_in_lists() {
+    let by_ids = |ids: Vec<i64>, name: &str, age: i64| {
+        SelectStatement::from(&*USERS)
+            .select(USERS.column("id"))
+            .filter(USERS.column("id").in_(ids))
+            .filter(USERS.column("name").eq(lit(name)))
+            .filter(USERS.column("age").gt(age))
+            .filter(USERS.column("score").lt(lit(-5)))
+            .filter(USERS.column("active").eq(lit(true)))
+            .limit(10)
+    };
+    let a = crate::sequel::visitor::visitor::mysql().fingerprint(&by_ids(vec![1, 2, 3], "bob", 18));
+    let b = crate::sequel::visitor::visitor::mysql().fingerprint(&by_ids(vec![7], "alice", 60));
+    assert_eq!(a, b);
+    assert_eq!(
+        a.sql,
+        "SELECT `users`.`id` FROM `users` AS `users` WHERE `users`.`id` IN (...) AND `users`.`name` = ? AND `users`.`age` > ? AND `users`.`score` < ? AND `users`.`active` = ? LIMIT ?"
+    );
+
+    // 每个方言各自的 SQL，占位符重新编号
+    let pg = crate::sequel::visitor::visitor::postgre().fingerprint(&by_ids(vec![1, 2, 3], "bob", 18));
+    assert_eq!(
+        pg.sql,
+        r#"SELECT "users"."id" FROM "users" AS "users" WHERE "users"."id" IN (...) AND "users"."name" = $1 AND "users"."age" > $2 AND "users"."score" < $3 AND "users"."active" = $4 LIMIT $5"#
+    );
+    assert_ne!(pg.hash, a.hash);
+
+    // 结构不同则指纹不同
+    let c = crate::sequel::visitor::visitor::mysql().fingerprint(&by_ids(vec![1], "bob", 18).offset(5));
+    assert_ne!(a.hash, c.hash);
+
+    // 超出 Oracle 的 IN 列表上限或者绑定为数组时，IN 列表同样折叠为一种形式
+    let long: Vec<i64> = (0..1500).collect();
+    let short = crate::sequel::visitor::visitor::oracle().fingerprint(&by_ids(vec![1, 2, 3, 4, 5], "bob", 18));
+    let long = crate::sequel::visitor::visitor::oracle().fingerprint(&by_ids(long, "bob", 18));
+    assert_eq!(short, long);
+    assert!(long.sql.contains(r#""USERS"."ID" IN (...) AND"#));
+    let array = crate::sequel::visitor::visitor::postgre().array_binding().fingerprint(&by_ids(vec![1, 2, 3], "bob", 18));
+    assert_eq!(array, pg);
+
+    // 哈希值跨版本稳定，可以持久化
+    use crate::sequel::visitor::fingerprint::Fingerprint;
+    assert_eq!(Fingerprint::new("SELECT 1".to_string()).hash, 0x199e7bca63ea84f2);
+}
+
+#[test]
+fn test_fingerprint__ignore_generated_alias() {
+    let sub = |t: &Table| SelectStatement::from(t).select(t.column("user_id")).filter(t.column("total").gt(100));
+    let orders = Table::new("orders");
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("id").in_(vec![sub(&orders)]))
+        .filter(USERS.column("company_id").in_(vec![1, 2]));
+    let fp = crate::sequel::visitor::visitor::mysql().fingerprint(&stmt);
+    assert_eq!(
+        fp.sql,
+        "SELECT `users`.`id` FROM `users` AS `users` WHERE `users`.`id` IN ((SELECT `orders`.`user_id` FROM `orders` AS `orders` WHERE `orders`.`total` > ?)) AND `users`.`company_id` IN (...)"
+    );
+
+    // 显式别名是查询的一部分，保持原样
+    let u = Table::new("users").alias("u");
+    let stmt = SelectStatement::from(&u).select(u.column("id")).filter(u.column("id").eq(1));
+    let fp = crate::sequel::visitor::visitor::mysql().fingerprint(&stmt);
+    assert_eq!(fp.sql, "SELECT `u`.`id` FROM `users` AS `u` WHERE `u`.`id` = ?");
+
+    // 计算指纹不影响 Visitor 中已经渲染的语句
+    let mut visitor = crate::sequel::visitor::visitor::mysql();
+    visitor.visit_select_statement(&stmt);
+    assert_eq!(visitor.fingerprint(&stmt), fp);
+    let (sql, params, _) = visitor.finish();
+    assert_eq!(sql, "SELECT `u`.`id` FROM `users` AS `u` WHERE `u`.`id` = ?");
+    assert_eq!(params.len(), 1);
+}
+
+#[test]
+fn test_render_cache__hit_binds_new_values() {
+    use crate::sequel::visitor::dialect::postgre::PostgreSQL;
+    use crate::sequel::visitor::render_cache::RenderCache;
+
+    let by_user = |id: i64, statuses: Vec<&str>, total: i64| {
+        SelectStatement::from(&*USERS)
+            .left_join(&*ORDERS, USERS.column("id").eq(ORDERS.column("user_id")))
+            .select(vec![USERS.column("id"), USERS.column("name")])
+            .filter(USERS.column("id").eq(id))
+            .filter(USERS.column("status").in_(statuses))
+            .filter(ORDERS.column("total").between(total, total * 10))
+            .filter(USERS.column("name").eq(lit("bob")))
+            .limit(20)
+    };
+    let cache = RenderCache::<PostgreSQL>::new();
+    for (id, statuses, total) in [(1, vec!["a", "b"], 10), (2, vec!["c", "d"], 20), (3, vec!["e", "f"], 30)] {
+        let stmt = by_user(id, statuses, total);
+        let expected = postgre().visit_select_statement(&stmt).finish();
+        assert_eq!(postgre().visit_select_statement_cached(&stmt, &cache).finish(), expected);
+    }
+    assert_eq!(cache.len(), 1);
+
+    // IN 列表长度、字面量取值属于结构，各自一条缓存
+    let stmt = by_user(4, vec!["a", "b", "c"], 40);
+    let expected = postgre().visit_select_statement(&stmt).finish();
+    assert_eq!(postgre().visit_select_statement_cached(&stmt, &cache).finish(), expected);
+    assert_eq!(cache.len(), 2);
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_render_cache__table_identity_in_shape() {
+    use crate::sequel::visitor::dialect::mysql::MySQL;
+    use crate::sequel::visitor::render_cache::RenderCache;
+
+    let cache = RenderCache::<MySQL>::new();
+    // 同一张表引用两次与两张同名表生成的别名不同，不能共用缓存
+    let same = SelectStatement::from(&*USERS)
+        .join(&*USERS, USERS.column("id").eq(USERS.column("parent_id")))
+        .select(USERS.column("id"))
+        .filter(USERS.column("id").eq(1));
+    let other = Table::new("users");
+    let distinct = SelectStatement::from(&*USERS)
+        .join(&other, USERS.column("id").eq(other.column("parent_id")))
+        .select(USERS.column("id"))
+        .filter(USERS.column("id").eq(2));
+    for stmt in [&same, &distinct, &same, &distinct] {
+        let expected = mysql().visit_select_statement(stmt).finish();
+        assert_eq!(mysql().visit_select_statement_cached(stmt, &cache).finish(), expected);
+    }
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn test_render_cache__rewritten_statement() {
+    use crate::sequel::visitor::dialect::sqlite::SQLite;
+    use crate::sequel::visitor::render_cache::RenderCache;
+
+    let cache = RenderCache::<SQLite>::new();
+    let stmt = SelectStatement::from(&*USERS)
+        .select(count([USERS.column("city"), USERS.column("username")]).distinct())
+        .filter(USERS.column("age").gt(18));
+    let expected = sqlite().visit_select_statement(&stmt).finish();
+    assert!(expected.0.starts_with(r#"SELECT COUNT(*) FROM (SELECT DISTINCT "users0"."city", "users0"."username""#));
+    assert_eq!(sqlite().visit_select_statement_cached(&stmt, &cache).finish(), expected);
+    // 按改写后的语句计算结构和提取计划，同样可以缓存
+    assert_eq!(cache.len(), 1);
+    assert_eq!(sqlite().visit_select_statement_cached(&stmt, &cache).finish(), expected);
+}
+
+#[test]
+fn test_render_cache__mode_and_capacity() {
+    use crate::sequel::visitor::dialect::postgre::PostgreSQL;
+    use crate::sequel::visitor::render_cache::RenderCache;
+
+    // 宽松模式写入的结果不能被严格模式命中
+    let cache = RenderCache::<PostgreSQL>::new();
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).filter(USERS.column("id").eq(1)).final_();
+    let (sql, _, _) = postgre().visit_select_statement_cached(&stmt, &cache).finish();
+    assert_eq!(sql, r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."id" = $1"#);
+    let err = postgre().strict().visit_select_statement_cached(&stmt, &cache).try_finish().unwrap_err();
+    assert_eq!(err, RenderError::unsupported("FINAL", "the dialect has no FINAL modifier"));
+    assert_eq!(cache.len(), 1);
+
+    // 超出容量时淘汰最早写入的结构
+    let cache = RenderCache::<PostgreSQL>::with_capacity(2);
+    let by_limit = |n: usize| SelectStatement::from(&*USERS).select(USERS.column("id")).limit(n);
+    for n in 1..=3 {
+        postgre().visit_select_statement_cached(&by_limit(n), &cache);
+    }
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.capacity(), 2);
+}
+
+#[test]
+fn test_identifier__escape_quote_chars() {
+    let t = Table::new("we`ird\"ta]ble");
+    let stmt = SelectStatement::from(&t).select(t.column("a`b\"c]d").alias("x`y\"z]"));
+    assert_mysql!(&stmt, "SELECT `we``ird\"ta]ble0`.`a``b\"c]d` AS `x``y\"z]` FROM `we``ird\"ta]ble` AS `we``ird\"ta]ble0`");
+    assert_pg!(
+        &stmt,
+        r#"SELECT "we`ird""ta]ble0"."a`b""c]d" AS "x`y""z]" FROM "we`ird""ta]ble" AS "we`ird""ta]ble0""#
+    );
+    assert_mssql!(&stmt, r#"SELECT [we`ird"ta]]ble0].[a`b"c]]d] AS [x`y"z]]] FROM [we`ird"ta]]ble] AS [we`ird"ta]]ble0]"#);
+}
+
+#[test]
+fn test_identifier__schema_qualified_table() {
+    let events = Table::new("events").schema("analytics");
+    let stmt = SelectStatement::from(&events).select(events.column("id")).filter(events.column("kind").eq("click"));
+    assert_mysql!(
+        &stmt,
+        "SELECT `events0`.`id` FROM `analytics`.`events` AS `events0` WHERE `events0`.`kind` = ?",
+        ["click"]
+    );
+    assert_pg!(
+        &stmt,
+        r#"SELECT "events0"."id" FROM "analytics"."events" AS "events0" WHERE "events0"."kind" = $1"#,
+        ["click"]
+    );
+    assert_mssql!(
+        &stmt,
+        "SELECT [events0].[id] FROM [analytics].[events] AS [events0] WHERE [events0].[kind] = @p1",
+        ["click"]
+    );
+    assert_oracle!(
+        &stmt,
+        r#"SELECT "EVENTS0"."ID" FROM "ANALYTICS"."EVENTS" "EVENTS0" WHERE "EVENTS0"."KIND" = :1"#,
+        ["click"]
+    );
+
+    let logs = Table::new("logs").schema("audit").catalog("warehouse").alias("l");
+    let stmt = SelectStatement::from(&logs).select(logs.column("id"));
+    assert_pg!(&stmt, r#"SELECT "l"."id" FROM "warehouse"."audit"."logs" AS "l""#);
+}
+
+#[test]
+fn test_identifier__qualified_column_outside_from() {
+    // 关联到不在 FROM 中的表时，列按完整表名限定
+    let events = Table::new("events").schema("analytics");
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("id").eq(events.column("user_id")));
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."id" = "analytics"."events"."user_id""#
+    );
+    assert_mysql!(
+        &stmt,
+        "SELECT `users0`.`id` FROM `users` AS `users0` WHERE `users0`.`id` = `analytics`.`events`.`user_id`"
+    );
+}
+
+#[test]
+fn test_param__named_placeholders() {
+    use crate::sequel::visitor::dialect::PlaceHolderStyle;
+    use crate::sequel::visitor::dialect::derived::DialectBuilder;
+    use crate::sequel::visitor::dialect::postgre::PostgreSQL;
+    use crate::sequel::visitor::visitor::Visitor;
+
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("id").eq(named("user_id", 7)).or(USERS.column("parent_id").eq(named("user_id", 7))))
+        .filter(USERS.column("age").gt(18));
+    let colon = || Visitor::new(DialectBuilder::new(PostgreSQL {}).placeholder_style(PlaceHolderStyle::ColonNamed).build());
+    assert_dialect!(
+        colon(),
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE ("users0"."id" = :user_id OR "users0"."parent_id" = :user_id) AND "users0"."age" > :_p2"#,
+        [7_i64, 18_i64]
+    );
+    let (_, _, names) = colon().visit_select_statement(&stmt).finish();
+    assert_eq!(names, HashMap::from([("user_id".to_string(), vec![0]), ("_p2".to_string(), vec![1])]));
+
+    let at = || Visitor::new(DialectBuilder::new(PostgreSQL {}).placeholder_style(PlaceHolderStyle::AtNamed).build());
+    assert_dialect!(
+        at(),
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE ("users0"."id" = @user_id OR "users0"."parent_id" = @user_id) AND "users0"."age" > @_p2"#,
+        [7_i64, 18_i64]
+    );
+
+    // $n 复用同一个位置；解析回来的参数不带名字，round trip 需要打开值复用
+    assert_dialect!(
+        postgre().reuse_params(),
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE ("users0"."id" = $1 OR "users0"."parent_id" = $1) AND "users0"."age" > $2"#,
+        [7_i64, 18_i64]
+    );
+    // ? 只能按出现顺序绑定，每次引用各占一个位置
+    assert_mysql!(
+        &stmt,
+        "SELECT `users0`.`id` FROM `users` AS `users0` WHERE (`users0`.`id` = ? OR `users0`.`parent_id` = ?) AND `users0`.`age` > ?",
+        [7_i64, 7_i64, 18_i64]
+    );
+    let (_, _, names) = mysql().visit_select_statement(&stmt).finish();
+    assert_eq!(names, HashMap::from([("user_id".to_string(), vec![0, 1])]));
+
+    let mut visitor = colon();
+    visitor.visit_select_statement(&stmt);
+    assert_eq!(
+        visitor.to_debug_sql(),
+        r#"-- debug only, not for execution
+SELECT "users0"."id" FROM "users" AS "users0" WHERE ("users0"."id" = 7 OR "users0"."parent_id" = 7) AND "users0"."age" > 18"#
+    );
+}
+
+#[test]
+fn test_param__dedupe_numbered() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("min_age").eq(18))
+        .filter(USERS.column("age").gt(18))
+        .filter(USERS.column("name").eq("18"));
+    // 默认不复用，同一个值可能绑定到不同类型的列
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."min_age" = $1 AND "users0"."age" > $2 AND "users0"."name" = $3"#,
+        [18_i64, 18_i64, "18"]
+    );
+    // 同值不同类型不合并
+    assert_dialect!(
+        postgre().reuse_params(),
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."min_age" = $1 AND "users0"."age" > $1 AND "users0"."name" = $2"#,
+        [18_i64, "18"]
+    );
+    assert_dialect!(
+        mssql().reuse_params(),
+        &stmt,
+        "SELECT [users0].[id] FROM [users] AS [users0] WHERE [users0].[min_age] = @p1 AND [users0].[age] > @p1 AND [users0].[name] = @p2",
+        [18_i64, "18"]
+    );
+    assert_oracle!(
+        &stmt,
+        r#"SELECT "USERS0"."ID" FROM "USERS" "USERS0" WHERE "USERS0"."MIN_AGE" = :1 AND "USERS0"."AGE" > :2 AND "USERS0"."NAME" = :3"#,
+        [18_i64, 18_i64, "18"]
+    );
+
+    // 具名参数按名字重新绑定，不与取值相同的未命名参数合并
+    let stmt = stmt.filter(USERS.column("score").eq(named("score", 18)));
+    let (sql, params, names) = postgre().reuse_params().visit_select_statement(&stmt).finish();
+    assert!(sql.ends_with(r#""users0"."name" = $2 AND "users0"."score" = $3"#));
+    assert_eq!(params.len(), 3);
+    assert_eq!(names, HashMap::from([("score".to_string(), vec![2])]));
+}
+
+#[test]
+fn test_param__named_conflict() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("id").eq(named("user_id", 1)))
+        .filter(USERS.column("parent_id").eq(named("user_id", 2)));
+    let err = postgre().visit_select_statement(&stmt).try_finish().unwrap_err();
+    assert_eq!(err, RenderError::ConflictingParam("user_id".to_string()));
+}
+
+#[test]
+fn test_render_cache__param_reuse() {
+    use crate::sequel::visitor::dialect::postgre::PostgreSQL;
+    use crate::sequel::visitor::render_cache::RenderCache;
+
+    let cache = RenderCache::<PostgreSQL>::new();
+    let stmt = |a: i64, b: i64, user_id: i64| {
+        SelectStatement::from(&*USERS)
+            .select(USERS.column("id"))
+            .filter(USERS.column("min_age").eq(a))
+            .filter(USERS.column("age").gt(b))
+            .filter(USERS.column("id").eq(named("user_id", user_id)))
+            .filter(USERS.column("parent_id").eq(named("user_id", user_id)))
+    };
+    // 取值是否相同决定 $n 能否合并，不能共用缓存
+    for stmt in [stmt(1, 1, 5), stmt(1, 2, 6), stmt(3, 3, 7), stmt(3, 4, 8)] {
+        let expected = postgre().reuse_params().visit_select_statement(&stmt).finish();
+        assert_eq!(postgre().reuse_params().visit_select_statement_cached(&stmt, &cache).finish(), expected);
+    }
+    assert_eq!(cache.len(), 2);
+
+    // 同名参数取值冲突时照常报错
+    let conflict = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("min_age").eq(1))
+        .filter(USERS.column("age").gt(2))
+        .filter(USERS.column("id").eq(named("user_id", 1)))
+        .filter(USERS.column("parent_id").eq(named("user_id", 2)));
+    assert!(postgre().visit_select_statement_cached(&conflict, &cache).try_finish().is_err());
+}
+
+#[test]
+fn test_param__reserved_name() {
+    // _p1 等名字留给具名风格下的未命名参数
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).filter(USERS.column("age").gt(named("_p1", 18)));
+    let err = postgre().visit_select_statement(&stmt).try_finish().unwrap_err();
+    assert_eq!(
+        err,
+        RenderError::invalid("named parameter", "names like _p1 are reserved for unnamed parameters")
+    );
+    // 名字原样写入 SQL，不是标识符的名字直接报错
+    for name in ["x; drop table t", "", "1st", "a-b"] {
+        let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).filter(USERS.column("age").gt(named(name, 18)));
+        let err = postgre().visit_select_statement(&stmt).try_finish().unwrap_err();
+        assert_eq!(err, RenderError::invalid("named parameter", "a name must match [A-Za-z_][A-Za-z0-9_]*"));
+    }
+    let stmt = SelectStatement::from(&*USERS).select(USERS.column("id")).filter(USERS.column("age").gt(named("p1", 18)));
+    assert_pg!(&stmt, r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."age" > $1"#, [18_i64]);
+}
+
+#[test]
+fn test_param__array_binding() {
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("id").in_(vec![1, 2, 3]))
+        .filter(USERS.column("status").not_in(vec!["banned", "deleted"]))
+        .filter(USERS.column("role").in_(vec![value("admin"), value(1)]));
+    let mut visitor = postgre().array_binding();
+    visitor.visit_select_statement(&stmt);
+    let (sql, params, _) = visitor.finish();
+    // 类型不一致的列表保持原样
+    assert_eq!(
+        sql,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."id" = ANY($1) AND "users0"."status" <> ALL($2) AND "users0"."role" IN ($3, $4)"#
+    );
+    assert_eq!(
+        params,
+        vec![
+            ParamData::Array(vec![ParamData::Int(1), ParamData::Int(2), ParamData::Int(3)]),
+            ParamData::Array(vec![
+                ParamData::String("banned".into()),
+                ParamData::String("deleted".into())
+            ]),
+            ParamData::String("admin".into()),
+            ParamData::Int(1),
+        ]
+    );
+    assert!(
+        visitor
+            .to_debug_sql()
+            .contains(r#""users0"."id" = ANY(ARRAY[1, 2, 3])"#)
+    );
+
+    // 方言不支持数组参数时不生效
+    let (sql, params, _) = mysql().array_binding().visit_select_statement(&stmt).finish();
+    assert!(sql.contains("`users0`.`id` IN (?, ?, ?)"));
+    assert_eq!(params.len(), 7);
+}
+
+#[test]
+fn test_table__values() {
+    use crate::sequel::term::values::Values;
+
+    let rows = vec![
+        vec![lit(1).into(), lit("a").into()],
+        vec![lit(2).into(), lit("b").into()],
+    ];
+    let v = Table::from(Values::new(["id", "name"], rows));
+    let stmt = SelectStatement::from(&*USERS)
+        .join(&v, USERS.column("id").eq(v.column("id")))
+        .select(vec![USERS.column("id"), v.column("name")]);
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."id", "v0"."name" FROM "users" AS "users0" INNER JOIN (VALUES (1, 'a'), (2, 'b')) AS "v0" ("id", "name") ON "users0"."id" = "v0"."id""#
+    );
+    assert_mssql!(
+        &stmt,
+        "SELECT [users0].[id], [v0].[name] FROM [users] AS [users0] INNER JOIN (VALUES (1, 'a'), (2, 'b')) AS [v0] ([id], [name]) ON [users0].[id] = [v0].[id]"
+    );
+    let (sql, _, _) = sqlite().visit_select_statement(&stmt).finish();
+    assert_eq!(
+        sql,
+        r#"SELECT "users0"."id", "v0"."name" FROM "users" AS "users0" INNER JOIN (SELECT "column1" AS "id", "column2" AS "name" FROM (VALUES (1, 'a'), (2, 'b'))) AS "v0" ON "users0"."id" = "v0"."id""#
+    );
+    let err = mysql().strict().visit_select_statement(&stmt).try_finish().unwrap_err();
+    assert_eq!(
+        err,
+        RenderError::unsupported("VALUES table", "the dialect has no VALUES table constructor")
+    );
+}
+
+#[test]
+fn test_in_list__split() {
+    use crate::sequel::visitor::dialect::caps::Capability;
+    use crate::sequel::visitor::dialect::derived::DialectBuilder;
+    use crate::sequel::visitor::dialect::mysql::MySQL;
+    use crate::sequel::visitor::dialect::postgre::PostgreSQL;
+    use crate::sequel::visitor::dialect::sqlite::SQLite;
+    use crate::sequel::visitor::visitor::Visitor;
+    use crate::sequel::visitor::{InListStrategy, split_in_list};
+
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("age").gt(18))
+        .filter(USERS.column("id").in_(vec![1, 2, 3, 4, 5, 6, 7]));
+    let sqlite4 = || {
+        DialectBuilder::new(SQLite {})
+            .caps(|c| Capability {
+                max_params: Some(4),
+                ..c
+            })
+            .build()
+    };
+
+    // 没有超出限制
+    let split = split_in_list(&stmt, &SQLite {});
+    assert_eq!(split.strategy, InListStrategy::Unchanged);
+    assert_eq!(split.statements.len(), 1);
+
+    // 逐行独立的查询拆成多条语句，每条最多 4 个参数
+    let split = split_in_list(&stmt, &sqlite4());
+    assert_eq!(split.strategy, InListStrategy::Chunks);
+    let rendered: Vec<_> = split
+        .statements
+        .iter()
+        .map(|s| Visitor::new(sqlite4()).visit_select_statement(s).finish())
+        .map(|(sql, params, _)| (sql, params.len()))
+        .collect();
+    assert_eq!(
+        rendered,
+        vec![
+            (r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."age" > ? AND "users0"."id" IN (?, ?, ?)"#.to_string(), 4),
+            (r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."age" > ? AND "users0"."id" IN (?, ?, ?)"#.to_string(), 4),
+            (r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."age" > ? AND "users0"."id" IN (?)"#.to_string(), 2),
+        ]
+    );
+
+    // 先去重再拆分
+    let dup = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("id").in_(vec![1, 2, 1, 2, 3, 3, 4]));
+    let split = split_in_list(&dup, &sqlite4());
+    assert_eq!(split.strategy, InListStrategy::Chunks);
+    let (sql, params, _) = Visitor::new(sqlite4()).visit_select_statement(&split.statements[0]).finish();
+    assert_eq!(split.statements.len(), 1);
+    assert_eq!(sql, r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."id" IN (?, ?, ?, ?)"#);
+    assert_eq!(params.len(), 4);
+
+    // 排序、分页、聚合的结果不能拼接，改为与 VALUES 临时表 JOIN，重复的值只保留一个
+    let stmt = stmt
+        .filter(USERS.column("id").in_(vec![9, 8, 9]))
+        .order_by(USERS.column("name"))
+        .limit(10);
+    let split = split_in_list(&stmt, &sqlite4());
+    assert_eq!(split.strategy, InListStrategy::ValuesJoin);
+    let (sql, params, _) = Visitor::new(sqlite4())
+        .visit_select_statement(&split.statements[0])
+        .finish();
+    assert_eq!(
+        sql,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" INNER JOIN (SELECT "column1" AS "v" FROM (VALUES (1), (2), (3), (4), (5), (6), (7))) AS "v0" ON "users0"."id" = "v0"."v" WHERE "users0"."age" > ? AND "users0"."id" IN (?, ?, ?) ORDER BY "users0"."name" LIMIT 10"#
+    );
+    assert_eq!(params.len(), 4);
+
+    let pg4 = DialectBuilder::new(PostgreSQL {})
+        .caps(|c| Capability {
+            max_params: Some(4),
+            ..c
+        })
+        .build();
+    let split = split_in_list(&stmt.clone().filter(USERS.column("id").in_(vec![9, 8, 9, 8, 9])), &pg4);
+    assert_eq!(split.strategy, InListStrategy::ValuesJoin);
+    let (sql, _, _) = Visitor::new(pg4.clone()).visit_select_statement(&split.statements[0]).finish();
+    assert!(sql.contains(
+        r#"INNER JOIN (VALUES (1), (2), (3), (4), (5), (6), (7)) AS "v0" ("v") ON "users0"."id" = "v0"."v""#
+    ));
+
+    // 内联的字面量按方言转义，PostgreSQL 中反斜杠不是转义符
+    let names = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("name").in_(vec![r"C:\dir", "it's", r"C:\dir", "a", "b"]))
+        .order_by(USERS.column("name"));
+    let split = split_in_list(&names, &pg4);
+    assert_eq!(split.strategy, InListStrategy::ValuesJoin);
+    let (sql, _, _) = Visitor::new(pg4.clone()).visit_select_statement(&split.statements[0]).finish();
+    assert!(sql.contains(r#"INNER JOIN (VALUES ('C:\dir'), ('it''s'), ('a'), ('b')) AS "v0" ("v")"#));
+
+    // UUID 内联为字符串后与列的类型不匹配，保持原样
+    let uids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+    let uids = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("uid").in_(uids))
+        .order_by(USERS.column("name"));
+    let split = split_in_list(&uids, &pg4);
+    assert_eq!(split.strategy, InListStrategy::Unchanged);
+
+    // 不支持 VALUES 临时表时原样返回
+    let mysql4 = DialectBuilder::new(MySQL {})
+        .caps(|c| Capability {
+            max_params: Some(4),
+            ..c
+        })
+        .build();
+    let split = split_in_list(&stmt, &mysql4);
+    assert_eq!(split.strategy, InListStrategy::Unchanged);
+}
+
+#[test]
+fn test_traverse__between_subquery() {
+    let low = Table::new("orders");
+    let high = Table::new("orders");
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("age").between(
+            SelectStatement::from(&low).select(min(low.column("total"))),
+            SelectStatement::from(&high).select(max(high.column("total"))),
+        ))
+        .group_by(USERS.column("id"))
+        .having(
+            USERS
+                .column("id")
+                .gt(SelectStatement::from(&*ORDERS).select(min(ORDERS.column("user_id")))),
+        );
+    // BETWEEN 中的子查询按出现顺序编号
+    assert_pg!(
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."age" BETWEEN (SELECT MIN("orders0"."total") FROM "orders" AS "orders0") AND (SELECT MAX("orders1"."total") FROM "orders" AS "orders1") GROUP BY "users0"."id" HAVING "users0"."id" > (SELECT MIN("orders2"."user_id") FROM "orders" AS "orders2")"#
+    );
+}
+
+#[test]
+fn test_traverse__visit() {
+    use crate::sequel::term::column::Column;
+    use crate::sequel::traverse::visit::Visit;
+
+    struct Collect<'a> {
+        columns: Vec<&'a str>,
+        params: Vec<&'a Param>,
+        subqueries: usize,
+    }
+    impl<'a> Visit<'a> for Collect<'a> {
+        fn visit_select_statement(&mut self, stmt: &'a SelectStatement) {
+            self.subqueries += 1;
+            crate::sequel::traverse::visit::visit_select_statement(self, stmt);
+        }
+
+        fn visit_column(&mut self, column: &'a Column) {
+            self.columns.push(&column.name);
+        }
+
+        fn visit_param(&mut self, param: &'a Param) {
+            self.params.push(param);
+        }
+    }
+
+    let stmt = SelectStatement::from(&*USERS)
+        .left_join(&*ORDERS, USERS.column("id").eq(ORDERS.column("user_id")))
+        .select(
+            case()
+                .when(USERS.column("vip").eq(true), "gold")
+                .otherwise("normal")
+                .alias("level"),
+        )
+        .select(
+            rank().over(
+                Window::new()
+                    .partition_by(USERS.column("city"))
+                    .order_by(ORDERS.column("total")),
+            ),
+        )
+        .filter(ORDERS.column("total").between(
+            10,
+            SelectStatement::from(&*PRODUCTS).select(max(PRODUCTS.column("price"))),
+        ))
+        .filter(cast(USERS.column("age"), ColumnType::Int).in_(vec![18, 30]));
+    let mut collect = Collect {
+        columns: Vec::new(),
+        params: Vec::new(),
+        subqueries: 0,
+    };
+    collect.visit_select_statement(&stmt);
+    assert_eq!(
+        collect.columns,
+        vec!["vip", "city", "total", "id", "user_id", "total", "price", "age"]
+    );
+    assert_eq!(collect.params.len(), 6);
+    assert_eq!(collect.subqueries, 2);
+}
+
+#[test]
+fn test_traverse__visit_mut() {
+    use crate::sequel::term::param::ParamData;
+    use crate::sequel::traverse::visit_mut::VisitMut;
+
+    // 所有整数参数加倍
+    struct Double;
+    impl VisitMut for Double {
+        fn visit_param_mut(&mut self, param: &mut Param) {
+            if let Param::Value(ParamData::Int(n)) = param {
+                *n *= 2;
+            }
+        }
+    }
+
+    let sq = Table::from(
+        SelectStatement::from(&*ORDERS)
+            .select(ORDERS.column("user_id"))
+            .filter(ORDERS.column("total").gt(100)),
+    );
+    let stmt = SelectStatement::from(&*USERS)
+        .join(&sq, USERS.column("id").eq(sq.column("user_id")))
+        .select(vec![USERS.column("id"), sq.column("user_id")])
+        .filter(USERS.column("age").between(18, 30))
+        .for_update_of(&sq);
+    let mut doubled = stmt.clone();
+    Double.visit_select_statement_mut(&mut doubled);
+
+    // 子查询被复制后，引用它的列跟随改为新的子查询，原语句不受影响
+    let (sql, params, _) = postgre().visit_select_statement(&doubled).finish();
+    assert_eq!(
+        sql,
+        r#"SELECT "users0"."id", "sq0"."user_id" FROM "users" AS "users0" INNER JOIN (SELECT "orders0"."user_id" FROM "orders" AS "orders0" WHERE "orders0"."total" > $1) AS "sq0" ON "users0"."id" = "sq0"."user_id" WHERE "users0"."age" BETWEEN $2 AND $3 FOR UPDATE OF "sq0""#
+    );
+    assert_eq!(
+        params,
+        vec![ParamData::Int(200), ParamData::Int(36), ParamData::Int(60)]
+    );
+    let (_, params, _) = postgre().visit_select_statement(&stmt).finish();
+    assert_eq!(
+        params,
+        vec![ParamData::Int(100), ParamData::Int(18), ParamData::Int(30)]
+    );
+}
+
+#[test]
+fn test_traverse__fold() {
+    use crate::sequel::term::ops::BinaryOp;
+    use crate::sequel::traverse::fold::{self, Fold};
+
+    // col IN (a, b) 改写为 col = a OR col = b
+    struct InToOr;
+    impl Fold for InToOr {
+        fn fold_expr(&mut self, expr: Expr) -> Expr {
+            match fold::fold_expr(self, expr) {
+                Expr::In {
+                    expr,
+                    list,
+                    negated: false,
+                } => list
+                    .into_iter()
+                    .map(|item| Expr::Binary {
+                        left: expr.clone(),
+                        op: BinaryOp::Eq,
+                        right: Box::new(item),
+                    })
+                    .reduce(|left, right| Expr::Binary {
+                        left: Box::new(left),
+                        op: BinaryOp::Or,
+                        right: Box::new(right),
+                    })
+                    .unwrap_or(Expr::Param(lit(false))),
+                expr => expr,
+            }
+        }
+    }
+
+    let sq = Table::from(
+        SelectStatement::from(&*ORDERS)
+            .select(ORDERS.column("user_id"))
+            .filter(ORDERS.column("status").in_(vec!["paid", "shipped"])),
+    );
+    let stmt = SelectStatement::from(&sq)
+        .select(sq.column("user_id"))
+        .filter(sq.column("user_id").in_(vec![1, 2]));
+    let folded = InToOr.fold_select_statement(stmt.clone());
+    assert_pg!(
+        &folded,
+        r#"SELECT "sq0"."user_id" FROM (SELECT "orders0"."user_id" FROM "orders" AS "orders0" WHERE "orders0"."status" = $1 OR "orders0"."status" = $2) AS "sq0" WHERE "sq0"."user_id" = $3 OR "sq0"."user_id" = $4"#,
+        [value("paid"), value("shipped"), value(1), value(2)]
+    );
+}
+
+#[test]
+fn test_pass__simplify() {
+    use crate::sequel::term::ops::BinaryOp;
+
+    let arith = |l: i64, op, r: i64| Expr::Binary {
+        left: Box::new(Expr::Param(lit(l))),
+        op,
+        right: Box::new(Expr::Param(lit(r))),
+    };
+    let nested = USERS
+        .column("city")
+        .eq("Paris")
+        .and(USERS.column("age").lt(arith(7, BinaryOp::Mul, 9)));
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("age").gt(arith(10, BinaryOp::Add, 8)))
+        .filter(!!USERS.column("active").eq(true))
+        .filter(Expr::Param(lit(true)).and(nested))
+        .filter(USERS.column("age").gt(lit(18)))
+        .filter(USERS.column("id").not_in(Vec::<i64>::new()));
+    assert_dialect!(
+        postgre().simplify(),
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."age" > 18 AND "users0"."active" = $1 AND "users0"."city" = $2 AND "users0"."age" < 63"#,
+        [value(true), value("Paris")]
+    );
+    // 浮点数运算不折叠，避免 0.1 + 0.2 变成 0.30000000000000004
+    let price = SelectStatement::from(&*USERS).select(USERS.column("id")).filter(USERS.column("price").eq(Expr::Binary {
+        left: Box::new(Expr::Param(lit(0.1))),
+        op: BinaryOp::Add,
+        right: Box::new(Expr::Param(lit(0.2))),
+    }));
+    assert_dialect!(
+        postgre().simplify(),
+        &price,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."price" = 0.1 + 0.2"#
+    );
+    // 默认只有 COUNT(DISTINCT a, b) 的改写
+    let (sql, _, _) = postgre().visit_select_statement(&stmt).finish();
+    let unchanged = r#"WHERE "users0"."age" > 10 + 8 AND NOT NOT "users0"."active" = $1"#;
+    assert!(sql.contains(unchanged), "{}", sql);
+}
+
+#[test]
+fn test_pass__simplify_nested() {
+    let empty = ORDERS.column("status").in_(Vec::<&str>::new());
+    let sq = Table::from(
+        SelectStatement::from(&*ORDERS)
+            .select(ORDERS.column("user_id"))
+            .filter(empty.or(ORDERS.column("total").gt(100))),
+    );
+    let stmt = SelectStatement::from(&*USERS)
+        .join(
+            &sq,
+            USERS
+                .column("id")
+                .eq(sq.column("user_id"))
+                .and(!!USERS.column("active").eq(true)),
+        )
+        .select(USERS.column("id"))
+        .filter(USERS.column("id").in_(Vec::<i64>::new()));
+    assert_dialect!(
+        postgre().simplify(),
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" INNER JOIN (SELECT "orders0"."user_id" FROM "orders" AS "orders0" WHERE "orders0"."total" > $1) AS "sq0" ON "users0"."id" = "sq0"."user_id" AND "users0"."active" = $2 WHERE 1 = 0"#,
+        [value(100), value(true)]
+    );
+}
+
+#[test]
+fn test_pass__dedupe_filters() {
+    use crate::sequel::visitor::pass::{DedupeFilters, FlattenLogic};
+
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(USERS.column("age").gt(18))
+        .filter(USERS.column("age").gt(20))
+        .filter(USERS.column("age").gt(18).and(USERS.column("city").eq("Paris")))
+        .having(count_all().gt(1))
+        .having(count_all().gt(1));
+    assert_dialect!(
+        postgre().pass(DedupeFilters).pass(FlattenLogic).pass(DedupeFilters),
+        &stmt,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE "users0"."age" > $1 AND "users0"."age" > $2 AND "users0"."city" = $3 HAVING COUNT(*) > $4"#,
+        [value(18), value(20), value("Paris"), value(1)]
+    );
+
+    // 每次求值结果不同的函数，重复的条件不能合并
+    let sample = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .filter(func("random", Vec::<Expr>::new()).lt(0.5))
+        .filter(func("random", Vec::<Expr>::new()).lt(0.5));
+    assert_dialect!(
+        postgre().pass(DedupeFilters),
+        &sample,
+        r#"SELECT "users0"."id" FROM "users" AS "users0" WHERE RANDOM() < $1 AND RANDOM() < $2"#,
+        [value(0.5), value(0.5)]
+    );
+}
+
+#[test]
+fn test_pass__custom() {
+    use crate::sequel::term::table::TableInner;
+    use crate::sequel::traverse::visit_mut::{VisitMut, visit_select_statement_mut};
+    use crate::sequel::visitor::dialect::Dialect;
+    use crate::sequel::visitor::pass::Pass;
+
+    // 查询 users 的语句都加上租户条件，包括子查询
+    struct Tenant(i64);
+    impl Pass for Tenant {
+        fn run(&self, stmt: &mut SelectStatement, _: &dyn Dialect) {
+            struct Inject(i64);
+            impl VisitMut for Inject {
+                fn visit_select_statement_mut(&mut self, stmt: &mut SelectStatement) {
+                    visit_select_statement_mut(self, stmt);
+                    if let TableInner::Named(name) = stmt.from_clause.inner.as_ref()
+                        && name.name == "users"
+                    {
+                        stmt.where_clause.push(stmt.from_clause.column("tenant_id").eq(self.0));
+                    }
+                }
+            }
+            Inject(self.0).visit_select_statement_mut(stmt);
+        }
+    }
+
+    let max_id = SelectStatement::from(&*USERS).select(max(USERS.column("id")));
+    let stmt = SelectStatement::from(&*ORDERS)
+        .select(ORDERS.column("id"))
+        .filter(ORDERS.column("user_id").eq(max_id));
+    let (sql, params, _) = postgre()
+        .pass(Tenant(42))
+        .pass(|stmt: &mut SelectStatement, _: &dyn Dialect| stmt.limit = Some(10))
+        .visit_select_statement(&stmt)
+        .finish();
+    assert_eq!(
+        sql,
+        r#"SELECT "orders0"."id" FROM "orders" AS "orders0" WHERE "orders0"."user_id" = (SELECT MAX("users0"."id") FROM "users" AS "users0" WHERE "users0"."tenant_id" = $1) LIMIT 10"#
+    );
+    assert_eq!(params, vec![ParamData::Int(42)]);
+
+    // 去掉默认的改写后，SQLite 不支持的 COUNT(DISTINCT a, b) 会报错
+    let count_distinct = count([USERS.column("city"), USERS.column("username")]).distinct();
+    let stmt = SelectStatement::from(&*USERS).select(count_distinct);
+    assert!(sqlite().visit_select_statement(&stmt).try_finish().is_ok());
+    let mut visitor = sqlite().without_passes();
+    assert!(visitor.visit_select_statement(&stmt).try_finish().is_err());
+}
+
+#[test]
+fn test_serde__round_trip() {
+    let round_trip = |stmt: &SelectStatement| -> SelectStatement {
+        let json = serde_json::to_string(stmt).unwrap();
+        serde_json::from_str(&json).unwrap()
+    };
+
+    let since = DateTime::new(2024, 1, 15, 10, 30, 0, 0).unwrap();
+    let sq = Table::from(
+        SelectStatement::from(&*ORDERS)
+            .select(ORDERS.column("user_id"))
+            .select(sum(ORDERS.column("total")).alias("spent"))
+            .filter(ORDERS.column("created_at").gt(value(since)))
+            .group_by(ORDERS.column("user_id")),
+    );
+    let rank = row_number().over(
+        Window::new()
+            .partition_by(USERS.column("city"))
+            .order_by_desc(sq.column("spent")),
+    );
+    let owned = SelectStatement::from(&*PRODUCTS)
+        .select(lit(1))
+        .filter(PRODUCTS.column("owner_id").eq(USERS.column("id")));
+    let stmt = SelectStatement::from(&*USERS)
+        .join(&sq, USERS.column("id").eq(sq.column("user_id")))
+        .select(vec![USERS.column("id"), sq.column("spent")])
+        .select(cast(USERS.column("age"), ColumnType::Text).alias("age_text"))
+        .select(rank.alias("rank"))
+        .select(
+            case()
+                .when(USERS.column("age").lt(18), lit("minor"))
+                .otherwise(lit("adult")),
+        )
+        .filter(USERS.column("score").gt(value("12.50".parse::<Decimal>().unwrap())))
+        .filter(USERS.column("id").in_(vec![1, 2, 3]))
+        .filter(USERS.column("profile").eq(serde_json::json!({"vip": true})))
+        .filter(exists(owned))
+        .order_by_desc(sq.column("spent"))
+        .limit(10);
+    let back = round_trip(&stmt);
+    assert_eq!(
+        postgre().visit_select_statement(&back).finish(),
+        postgre().visit_select_statement(&stmt).finish()
+    );
+    assert_eq!(
+        mysql().visit_select_statement(&back).finish(),
+        mysql().visit_select_statement(&stmt).finish()
+    );
+
+    let stmt = SelectStatement::from(&*USERS)
+        .select(USERS.column("id"))
+        .force_index(["idx_users_email"])
+        .for_update_of_columns(USERS.column("id"))
+        .skip();
+    let back = round_trip(&stmt);
+    assert_eq!(
+        oracle().visit_select_statement(&back).finish(),
+        oracle().visit_select_statement(&stmt).finish()
+    );
+    assert_eq!(
+        mysql().visit_select_statement(&back).finish(),
+        mysql().visit_select_statement(&stmt).finish()
+    );
+}
+
+#[test]
+fn test_serde__table_identity() {
+    use crate::sequel::term::table::TableInner;
+    use std::sync::Arc;
+
+    // 同名的两张表是不同的 Arc，反序列化后仍然是两张表
+    let managers = Table::new("users");
+    let stmt = SelectStatement::from(&*USERS)
+        .join(&managers, USERS.column("manager_id").eq(managers.column("id")))
+        .select(vec![USERS.column("name"), managers.column("name")]);
+    let json = serde_json::to_value(&stmt).unwrap();
+    assert_eq!(json["version"], 1);
+    assert_eq!(
+        json["root"]["select_clause"][1]["expr"]["column"],
+        serde_json::json!({"name": "name", "table": 1})
+    );
+    assert_eq!(json["tables"].as_array().unwrap().len(), 3);
+
+    let back: SelectStatement = serde_json::from_value(json).unwrap();
+    assert_pg!(
+        &back,
+        r#"SELECT "users0"."name", "users1"."name" FROM "users" AS "users0" INNER JOIN "users" AS "users1" ON "users0"."manager_id" = "users1"."id""#
+    );
+    let TableInner::Join(join) = back.from_clause.inner.as_ref() else {
+        panic!("expected join");
+    };
+    let Expr::Column(column) = &back.select_clause[1].expr else {
+        panic!("expected column");
+    };
+    assert!(Arc::ptr_eq(&column.table_inner, &join.right.inner));
+    assert!(!Arc::ptr_eq(&join.left.inner, &join.right.inner));
+}
+
+#[test]
+fn test_serde__document() {
+    use crate::sequel::term::column::Column;
+    use std::sync::Arc;
+
+    let expr = USERS.column("age").gt(18).and(USERS.column("city").eq("Paris"));
+    let json = serde_json::to_value(&expr).unwrap();
+    assert_eq!(
+        json["tables"],
+        serde_json::json!([{"named": {"catalog": null, "schema": null, "name": "users"}}])
+    );
+    assert_eq!(
+        json["root"]["binary"]["left"]["binary"]["left"],
+        serde_json::json!({"column": {"name": "age", "table": 0}})
+    );
+
+    // 键的顺序不影响反序列化，e.g. 存入 PostgreSQL 的 jsonb 后取出
+    let reordered = format!(
+        r#"{{"tables": {}, "root": {}, "version": 1}}"#,
+        json["tables"], json["root"]
+    );
+    let back: Expr = serde_json::from_str(&reordered).unwrap();
+    let Expr::Binary { left, right, .. } = &back else {
+        panic!("expected binary");
+    };
+    let (Expr::Binary { left: age, .. }, Expr::Binary { left: city, .. }) = (left.as_ref(), right.as_ref()) else {
+        panic!("expected comparisons");
+    };
+    let (Expr::Column(age), Expr::Column(city)) = (age.as_ref(), city.as_ref()) else {
+        panic!("expected columns");
+    };
+    assert!(Arc::ptr_eq(&age.table_inner, &city.table_inner));
+
+    let err = serde_json::from_str::<Expr>(r#"{"version": 2, "root": null, "tables": []}"#).unwrap_err();
+    assert!(err.to_string().contains("unsupported document version 2"), "{}", err);
+    let err = serde_json::from_str::<Column>(r#"{"version": 1, "root": {"name": "id", "table": 3}, "tables": []}"#)
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid table reference 3"), "{}", err);
+    // 循环引用
+    let cyclic = r#"{"version": 1, "root": {"table": 0, "alias": null}, "tables": [
+        {"join": {"left": {"table": 0, "alias": null}, "join_type": "cross", "right": {"table": 0, "alias": null}, "on": null}}
+    ]}"#;
+    let err = serde_json::from_str::<Table>(cyclic).unwrap_err();
+    assert!(err.to_string().contains("invalid table reference 0"), "{}", err);
+}
+
+#[test]
+fn test_serde__param_data() {
+    use crate::sequel::term::param::ParamData;
+
+    let params = vec![
+        ParamData::Int(42),
+        ParamData::Float(3.5),
+        ParamData::Bool(true),
+        ParamData::String("text".to_string()),
+        ParamData::Date(Date::new(2024, 2, 29).unwrap()),
+        ParamData::DateTime(DateTime::new(2024, 1, 15, 10, 30, 0, 0).unwrap()),
+        ParamData::Time(Time::new(23, 59, 59, 999_999).unwrap()),
+        ParamData::Decimal("123.45".parse().unwrap()),
+        ParamData::Json(serde_json::json!({"key": [1, 2]})),
+        ParamData::Uuid(Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap()),
+        ParamData::Binary(vec![0x01, 0x02]),
+        ParamData::Array(vec![ParamData::Int(1), ParamData::Int(2)]),
+    ];
+    let json = serde_json::to_value(&params).unwrap();
+    assert_eq!(json[0], serde_json::json!({"int": 42}));
+    assert_eq!(
+        json[4],
+        serde_json::json!({"date": {"year": 2024, "month": 2, "day": 29}})
+    );
+    assert_eq!(json[7], serde_json::json!({"decimal": "123.45"}));
+    let back: Vec<ParamData> = serde_json::from_value(json).unwrap();
+    assert_eq!(back, params);
+
+    // 反序列化时检查日期和时间的取值范围
+    let invalid = serde_json::json!({"date": {"year": 2023, "month": 2, "day": 29}});
+    assert!(serde_json::from_value::<ParamData>(invalid).is_err());
+    let invalid = serde_json::json!({"time": {"hour": 24, "minute": 0, "second": 0, "microsecond": 0}});
+    assert!(serde_json::from_value::<ParamData>(invalid).is_err());
+}
@@ -0,0 +1,68 @@
+// 各语句测试共用的断言宏
+//
+// 在测试文件开头传入要调用的 visit_* 方法，生成 assert_dialect! 以及按方言断言的 assert_mysql!、assert_pg! 等：
+//   test_macros!($ visit_update_statement);
+//   assert_pg!(&stmt, r#"UPDATE ..."#, [1_i64]);
+// 第一个参数固定为 $，用来在生成的宏中书写 $stmt 等元变量
+macro_rules! test_macros {
+    ($d:tt $visit:ident) => {
+        #[allow(unused_macros)]
+        macro_rules! assert_dialect {
+            ($d visitor:expr, $d stmt:expr, $d expected_sql:expr $d(, [$d($d params:expr),*])?) => {
+                assert_render!($visit, $d visitor, $d stmt, $d expected_sql $d(, [$d($d params),*])?)
+            };
+        }
+        test_macros!(@dialect $d assert_mysql, mysql);
+        test_macros!(@dialect $d assert_pg, postgre);
+        test_macros!(@dialect $d assert_sqlite, sqlite);
+        test_macros!(@dialect $d assert_oracle, oracle);
+        test_macros!(@dialect $d assert_mssql, mssql);
+        test_macros!(@dialect $d assert_duckdb, duckdb);
+        test_macros!(@dialect $d assert_clickhouse, clickhouse);
+    };
+    (@dialect $d:tt $name:ident, $visitor:ident) => {
+        #[allow(unused_macros)]
+        macro_rules! $name {
+            ($d stmt:expr, $d expected_sql:expr $d(, [$d($d params:expr),*])?) => {
+                assert_dialect!(
+                    crate::sequel::visitor::visitor::$visitor(),
+                    $d stmt,
+                    $d expected_sql
+                    $d(, [$d($d params),*])?
+                )
+            };
+        }
+    };
+}
+
+macro_rules! assert_render {
+    (@check $visit:ident, $visitor:expr, $stmt:expr, $expected_sql:expr $(, [$($params:expr),*])?) => {{
+        let (sql, params_relt, _) = $visitor.$visit($stmt).try_finish().unwrap_or_else(|e| panic!("{}", e));
+        assert_eq!(sql, $expected_sql.to_string());
+        let expected: Vec<crate::sequel::term::param::ParamData> = vec![$($($params.into()),*)?]
+            .into_iter()
+            .map(|p: crate::sequel::term::param::Param| p.data().unwrap().clone())
+            .collect();
+        assert_eq!(params_relt, expected);
+        (sql, params_relt)
+    }};
+    // SELECT 额外校验 render(parse(render(stmt))) == render(stmt)
+    (visit_select_statement, $visitor:expr, $($rest:tt)*) => {{
+        let (sql, params) = assert_render!(@check visit_select_statement, $visitor, $($rest)*);
+        assert_round_trip!($visitor, sql, params);
+    }};
+    ($visit:ident, $visitor:expr, $($rest:tt)*) => {{
+        assert_render!(@check $visit, $visitor, $($rest)*);
+    }};
+}
+
+macro_rules! assert_round_trip {
+    ($visitor:expr, $sql:expr, $params:expr) => {{
+        let mut visitor = $visitor;
+        let parsed = crate::sequel::parser::parser::parse_select(visitor.dialect(), &$sql, $params.clone())
+            .unwrap_or_else(|e| panic!("{}\n{}", e, $sql));
+        let (sql, params, _) = visitor.visit_select_statement(&parsed).finish();
+        assert_eq!(sql, $sql, "round trip");
+        assert_eq!(params, $params, "round trip");
+    }};
+}
@@ -0,0 +1,97 @@
+use crate::sequel::term::column::Column;
+use crate::sequel::term::expr::Expr;
+use crate::sequel::term::order::Order;
+use crate::sequel::term::select_item::SelectItem;
+use crate::sequel::term::table::Table;
+use utils::into_vec::IntoVec;
+
+#[derive(Clone, Debug)]
+pub struct Assignment {
+    pub column: Column,
+    pub value: Expr,
+}
+
+#[derive(Clone, Debug)]
+pub struct UpdateStatement {
+    pub table: Table,
+    pub assignments: Vec<Assignment>,
+    pub joins: Vec<(Table, Expr)>,
+    pub where_clause: Vec<Expr>,
+    pub orders: Vec<Order>,
+    pub limit: Option<usize>,
+    pub returning: Vec<SelectItem>,
+}
+
+impl UpdateStatement {
+    pub fn table<T>(t: &T) -> Self
+    where
+        T: Clone + Into<Table>,
+    {
+        Self {
+            table: t.clone().into(),
+            assignments: Vec::new(),
+            joins: Vec::new(),
+            where_clause: Vec::new(),
+            orders: Vec::new(),
+            limit: None,
+            returning: Vec::new(),
+        }
+    }
+
+    // e.g. .set(stock, stock.clone() - lit(1)) => SET stock = stock - 1
+    pub fn set(mut self, column: Column, value: impl Into<Expr>) -> Self {
+        self.assignments.push(Assignment {
+            column,
+            value: value.into(),
+        });
+        self
+    }
+
+    // 多表更新：PostgreSQL/SQLite 渲染为 UPDATE ... FROM，MySQL 渲染为 UPDATE a JOIN b
+    pub fn join<T>(mut self, other: &T, on: Expr) -> Self
+    where
+        T: Clone + Into<Table>,
+    {
+        self.joins.push((other.clone().into(), on));
+        self
+    }
+
+    pub fn filter<T>(mut self, c: T) -> Self
+    where
+        T: Into<Expr> + Clone,
+    {
+        self.where_clause.push(c.into());
+        self
+    }
+
+    pub fn order_by(mut self, cs: impl IntoVec<Expr>) -> Self {
+        for item in cs.into_vec() {
+            self.orders.push(Order::asc(item));
+        }
+        self
+    }
+    pub fn order_by_desc(mut self, cs: impl IntoVec<Expr>) -> Self {
+        for item in cs.into_vec() {
+            self.orders.push(Order::desc(item));
+        }
+        self
+    }
+
+    pub fn limit(mut self, n: usize) -> Self {
+        if n > 0 {
+            self.limit = Some(n);
+        }
+        self
+    }
+
+    pub fn returning(mut self, items: impl IntoVec<SelectItem>) -> Self {
+        for item in items.into_vec() {
+            self.returning.push(item);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+#[path = "./update_test.rs"]
+mod tests;
@@ -0,0 +1,270 @@
+use crate::prelude::*;
+use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::statement::update::UpdateStatement;
+use crate::sequel::term::func::max;
+use crate::sequel::term::param::lit;
+use crate::sequel::term::table::Table;
+use crate::sequel::visitor::error::RenderError;
+use crate::sequel::visitor::visitor::{mysql, oracle, postgre, sqlite};
+use std::sync::LazyLock;
+
+test_macros!($ visit_update_statement);
+
+pub static PRODUCTS: LazyLock<Table> = LazyLock::new(|| Table::new("products"));
+pub static ORDERS: LazyLock<Table> = LazyLock::new(|| Table::new("orders"));
+pub static USERS: LazyLock<Table> = LazyLock::new(|| Table::new("users"));
+
+#[test]
+fn test_update__set_values() {
+    let stmt = UpdateStatement::table(&*USERS)
+        .set(USERS.column("name"), "Lucy")
+        .set(USERS.column("age"), 18)
+        .filter(USERS.column("id").eq(1));
+    assert_mysql!(
+        &stmt,
+        "UPDATE `users` AS `users0` SET `name` = ?, `age` = ? WHERE `users0`.`id` = ?",
+        ["Lucy", 18_i64, 1_i64]
+    );
+    assert_pg!(
+        &stmt,
+        r#"UPDATE "users" AS "users0" SET "name" = $1, "age" = $2 WHERE "users0"."id" = $3"#,
+        ["Lucy", 18_i64, 1_i64]
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"UPDATE "users" AS "users0" SET "name" = ?, "age" = ? WHERE "users0"."id" = ?"#,
+        ["Lucy", 18_i64, 1_i64]
+    );
+}
+
+#[test]
+fn test_update__arithmetic() {
+    let stock = PRODUCTS.column("stock");
+    let stmt = UpdateStatement::table(&*PRODUCTS)
+        .set(stock.clone(), stock - lit(1))
+        .filter(PRODUCTS.column("id").eq(7));
+    assert_mysql!(
+        &stmt,
+        "UPDATE `products` AS `products0` SET `stock` = `products0`.`stock` - 1 WHERE `products0`.`id` = ?",
+        [7_i64]
+    );
+    assert_pg!(
+        &stmt,
+        r#"UPDATE "products" AS "products0" SET "stock" = "products0"."stock" - 1 WHERE "products0"."id" = $1"#,
+        [7_i64]
+    );
+}
+
+#[test]
+fn test_update__set_null_and_column() {
+    let stmt = UpdateStatement::table(&*USERS)
+        .set(USERS.column("deleted_at"), None::<i32>)
+        .set(USERS.column("nickname"), USERS.column("name"));
+    assert_sqlite!(
+        &stmt,
+        r#"UPDATE "users" AS "users0" SET "deleted_at" = NULL, "nickname" = "users0"."name""#
+    );
+}
+
+#[test]
+fn test_update__set_subquery() {
+    let total = SelectStatement::from(&*ORDERS)
+        .select(max(ORDERS.column("total")))
+        .filter(ORDERS.column("user_id").eq(USERS.column("id")));
+    let stmt = UpdateStatement::table(&*USERS).set(USERS.column("max_total"), total);
+    assert_pg!(
+        &stmt,
+        r#"UPDATE "users" AS "users0" SET "max_total" = (SELECT MAX("orders0"."total") FROM "orders" AS "orders0" WHERE "orders0"."user_id" = "users0"."id")"#
+    );
+}
+
+#[test]
+fn test_update__subquery_passes() {
+    use crate::sequel::term::func::count;
+    use crate::sequel::visitor::dialect::Dialect;
+
+    // 改写同样作用于 UPDATE 中的子查询
+    let total = SelectStatement::from(&*ORDERS)
+        .select(max(ORDERS.column("total")))
+        .filter(ORDERS.column("user_id").eq(USERS.column("id")));
+    let stmt = UpdateStatement::table(&*USERS).set(USERS.column("max_total"), total);
+    let (sql, params, _) = postgre()
+        .pass(|stmt: &mut SelectStatement, _: &dyn Dialect| {
+            stmt.where_clause.push(stmt.from_clause.column("tenant_id").eq(42))
+        })
+        .visit_update_statement(&stmt)
+        .finish();
+    assert_eq!(
+        sql,
+        r#"UPDATE "users" AS "users0" SET "max_total" = (SELECT MAX("orders0"."total") FROM "orders" AS "orders0" WHERE "orders0"."user_id" = "users0"."id" AND "orders0"."tenant_id" = $1)"#
+    );
+    assert_eq!(params.len(), 1);
+
+    // SQLite 不支持的 COUNT(DISTINCT a, b) 由默认的改写处理
+    let buyers = SelectStatement::from(&*ORDERS)
+        .select(count([ORDERS.column("user_id"), ORDERS.column("product_id")]).distinct())
+        .filter(ORDERS.column("product_id").eq(PRODUCTS.column("id")));
+    let stmt = UpdateStatement::table(&*PRODUCTS).set(PRODUCTS.column("buyers"), buyers);
+    assert!(sqlite().strict().visit_update_statement(&stmt).try_finish().is_ok());
+    assert!(sqlite().strict().without_passes().visit_update_statement(&stmt).try_finish().is_err());
+}
+
+#[test]
+fn test_update__multi_table() {
+    let stock = PRODUCTS.column("stock");
+    let stmt = UpdateStatement::table(&*PRODUCTS)
+        .set(stock.clone(), stock - ORDERS.column("quantity"))
+        .join(&*ORDERS, ORDERS.column("product_id").eq(PRODUCTS.column("id")))
+        .filter(ORDERS.column("status").eq("paid"));
+    assert_mysql!(
+        &stmt,
+        "UPDATE `products` AS `products0` INNER JOIN `orders` AS `orders0` ON `orders0`.`product_id` = `products0`.`id` SET `products0`.`stock` = `products0`.`stock` - `orders0`.`quantity` WHERE `orders0`.`status` = ?",
+        ["paid"]
+    );
+    assert_pg!(
+        &stmt,
+        r#"UPDATE "products" AS "products0" SET "stock" = "products0"."stock" - "orders0"."quantity" FROM "orders" AS "orders0" WHERE "orders0"."product_id" = "products0"."id" AND "orders0"."status" = $1"#,
+        ["paid"]
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"UPDATE "products" AS "products0" SET "stock" = "products0"."stock" - "orders0"."quantity" FROM "orders" AS "orders0" WHERE "orders0"."product_id" = "products0"."id" AND "orders0"."status" = ?"#,
+        ["paid"]
+    );
+}
+
+#[test]
+fn test_update__multi_table_multiple_joins() {
+    let stmt = UpdateStatement::table(&*ORDERS)
+        .set(ORDERS.column("vip"), true)
+        .join(&*USERS, USERS.column("id").eq(ORDERS.column("user_id")))
+        .join(&*PRODUCTS, PRODUCTS.column("id").eq(ORDERS.column("product_id")))
+        .filter(USERS.column("level").gt(3));
+    assert_mysql!(
+        &stmt,
+        "UPDATE `orders` AS `orders0` INNER JOIN `users` AS `users0` ON `users0`.`id` = `orders0`.`user_id` INNER JOIN `products` AS `products0` ON `products0`.`id` = `orders0`.`product_id` SET `orders0`.`vip` = ? WHERE `users0`.`level` > ?",
+        [true, 3_i64]
+    );
+    assert_pg!(
+        &stmt,
+        r#"UPDATE "orders" AS "orders0" SET "vip" = $1 FROM "users" AS "users0", "products" AS "products0" WHERE "users0"."id" = "orders0"."user_id" AND "products0"."id" = "orders0"."product_id" AND "users0"."level" > $2"#,
+        [true, 3_i64]
+    );
+}
+
+#[test]
+fn test_update__order_by_and_limit() {
+    let stmt = UpdateStatement::table(&*USERS)
+        .set(USERS.column("active"), false)
+        .filter(USERS.column("age").gt(60))
+        .order_by_desc(USERS.column("age"))
+        .limit(10);
+    assert_mysql!(
+        &stmt,
+        "UPDATE `users` AS `users0` SET `active` = ? WHERE `users0`.`age` > ? ORDER BY `users0`.`age` DESC LIMIT 10",
+        [false, 60_i64]
+    );
+    // PostgreSQL 和 SQLite（默认编译）不支持 UPDATE ... LIMIT，丢掉后会更新所有匹配的行
+    let err = postgre().try_visit_update_statement(&stmt).err().unwrap();
+    assert_eq!(
+        err,
+        RenderError::unsupported("ORDER BY / LIMIT", "the dialect does not allow them in this UPDATE / DELETE")
+    );
+    assert!(sqlite().try_visit_update_statement(&stmt).is_err());
+}
+
+#[test]
+fn test_update__multi_table_rejects_limit_on_mysql() {
+    let stmt = UpdateStatement::table(&*PRODUCTS)
+        .set(PRODUCTS.column("hot"), true)
+        .join(&*ORDERS, ORDERS.column("product_id").eq(PRODUCTS.column("id")))
+        .limit(10);
+    let err = mysql().try_visit_update_statement(&stmt).err().unwrap();
+    assert_eq!(
+        err,
+        RenderError::unsupported("ORDER BY / LIMIT", "the dialect does not allow them in this UPDATE / DELETE")
+    );
+}
+
+#[test]
+fn test_update__returning() {
+    let stock = PRODUCTS.column("stock");
+    let stmt = UpdateStatement::table(&*PRODUCTS)
+        .set(stock.clone(), stock.clone() - lit(1))
+        .filter(PRODUCTS.column("id").eq(7))
+        .returning(stock);
+    assert_mysql!(
+        &stmt,
+        "UPDATE `products` AS `products0` SET `stock` = `products0`.`stock` - 1 WHERE `products0`.`id` = ?",
+        [7_i64]
+    );
+    assert_pg!(
+        &stmt,
+        r#"UPDATE "products" AS "products0" SET "stock" = "products0"."stock" - 1 WHERE "products0"."id" = $1 RETURNING "products0"."stock""#,
+        [7_i64]
+    );
+    assert_sqlite!(
+        &stmt,
+        r#"UPDATE "products" AS "products0" SET "stock" = "products0"."stock" - 1 WHERE "products0"."id" = ? RETURNING "products0"."stock""#,
+        [7_i64]
+    );
+}
+
+#[test]
+fn test_update__strict_reports_dropped_clauses() {
+    let stmt = UpdateStatement::table(&*USERS)
+        .set(USERS.column("active"), false)
+        .limit(10)
+        .returning(USERS.column("id"));
+    let err = postgre().strict().try_visit_update_statement(&stmt).err().unwrap();
+    assert_eq!(
+        err,
+        RenderError::unsupported("ORDER BY / LIMIT", "the dialect does not allow them in this UPDATE / DELETE")
+    );
+
+    let err = mysql().strict().try_visit_update_statement(&stmt).err().unwrap();
+    assert_eq!(
+        err,
+        RenderError::unsupported("RETURNING", "the dialect has no RETURNING clause")
+    );
+}
+
+#[test]
+fn test_update__mssql_alias_in_from() {
+    // T-SQL 不能直接给 UPDATE 的目标表起别名，通过 FROM 引用
+    let stmt = UpdateStatement::table(&*USERS)
+        .set(USERS.column("name"), "Lucy")
+        .filter(USERS.column("id").eq(1));
+    assert_mssql!(
+        &stmt,
+        "UPDATE [users0] SET [name] = @p1 FROM [users] AS [users0] WHERE [users0].[id] = @p2",
+        ["Lucy", 1_i64]
+    );
+
+    let stock = PRODUCTS.column("stock");
+    let stmt = UpdateStatement::table(&*PRODUCTS)
+        .set(stock.clone(), stock - ORDERS.column("quantity"))
+        .join(&*ORDERS, ORDERS.column("product_id").eq(PRODUCTS.column("id")))
+        .filter(ORDERS.column("status").eq("paid"));
+    assert_mssql!(
+        &stmt,
+        "UPDATE [products0] SET [stock] = [products0].[stock] - [orders0].[quantity] FROM [products] AS [products0] INNER JOIN [orders] AS [orders0] ON [orders0].[product_id] = [products0].[id] WHERE [orders0].[status] = @p1",
+        ["paid"]
+    );
+}
+
+#[test]
+fn test_update__oracle_rejects_join() {
+    let stmt = UpdateStatement::table(&*USERS)
+        .set(USERS.column("name"), "Lucy")
+        .filter(USERS.column("id").eq(1));
+    assert_oracle!(&stmt, r#"UPDATE "USERS" "USERS0" SET "NAME" = :1 WHERE "USERS0"."ID" = :2"#, ["Lucy", 1_i64]);
+
+    // Oracle 没有 UPDATE ... FROM / JOIN
+    let stock = PRODUCTS.column("stock");
+    let stmt = UpdateStatement::table(&*PRODUCTS)
+        .set(stock.clone(), stock - ORDERS.column("quantity"))
+        .join(&*ORDERS, ORDERS.column("product_id").eq(PRODUCTS.column("id")));
+    let err = oracle().try_visit_update_statement(&stmt).err().unwrap();
+    assert_eq!(err, RenderError::unsupported("UPDATE ... JOIN", "the dialect has no multi-table UPDATE"));
+}
@@ -1,3 +1,5 @@
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::fmt;
 use std::fmt::Formatter;
 use thiserror::Error;
@@ -17,7 +19,7 @@ impl ValueError {
         }
     }
 }
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize)]
 pub struct Time {
     hour: u8,
     minute: u8,
@@ -36,6 +38,21 @@ impl Time {
     }
 }
 
+// 反序列化时同样检查取值范围
+impl<'de> Deserialize<'de> for Time {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Fields {
+            hour: u8,
+            minute: u8,
+            second: u8,
+            microsecond: u32,
+        }
+        let f = Fields::deserialize(deserializer)?;
+        Self::new(f.hour, f.minute, f.second, f.microsecond).map_err(D::Error::custom)
+    }
+}
+
 impl fmt::Display for Time {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
@@ -46,7 +63,7 @@ impl fmt::Display for Time {
     }
 }
 
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize)]
 pub struct Date {
     year: i32,
     month: u8,
@@ -58,13 +75,26 @@ impl Date {
         Ok(Self { year, month, day })
     }
 }
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Fields {
+            year: i32,
+            month: u8,
+            day: u8,
+        }
+        let f = Fields::deserialize(deserializer)?;
+        Self::new(f.year, f.month, f.day).map_err(D::Error::custom)
+    }
+}
+
 impl fmt::Display for Date {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}-{}-{}", self.year, self.month, self.day)
     }
 }
 
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct DateTime {
     date: Date,
     time: Time,
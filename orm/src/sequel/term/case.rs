@@ -0,0 +1,63 @@
+use crate::prelude::Comparable;
+use crate::sequel::term::expr::Expr;
+use crate::sequel::term::select_item::SelectItem;
+use serde::{Deserialize, Serialize};
+
+// e.g. 搜索式: CASE WHEN price > 100 THEN 'expensive' ELSE 'cheap' END
+//      简单式: CASE status WHEN 1 THEN 'active' WHEN 2 THEN 'banned' END
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Case {
+    // 简单式 CASE 的比较对象，搜索式为 None
+    pub operand: Option<Box<Expr>>,
+    pub conditions: Vec<(Expr, Expr)>,
+    pub else_expr: Option<Box<Expr>>,
+}
+
+impl Case {
+    pub fn when<C, T>(mut self, condition: C, then: T) -> Self
+    where
+        C: Into<Expr>,
+        T: Into<Expr>,
+    {
+        self.conditions.push((condition.into(), then.into()));
+        self
+    }
+
+    pub fn otherwise<T>(mut self, else_expr: T) -> Self
+    where
+        T: Into<Expr>,
+    {
+        self.else_expr = Some(Box::new(else_expr.into()));
+        self
+    }
+
+    pub fn alias(self, alias: impl Into<String>) -> SelectItem {
+        Expr::from(self).alias(alias)
+    }
+}
+
+// 搜索式 CASE
+pub fn case() -> Case {
+    Case {
+        operand: None,
+        conditions: Vec::new(),
+        else_expr: None,
+    }
+}
+
+// 简单式 CASE
+pub fn case_of<T>(operand: T) -> Case
+where
+    T: Into<Expr>,
+{
+    Case {
+        operand: Some(Box::new(operand.into())),
+        ..case()
+    }
+}
+
+impl Comparable for Case {
+    fn into_expr(&self) -> Expr {
+        Expr::Case(self.clone())
+    }
+}
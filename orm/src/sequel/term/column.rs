@@ -2,13 +2,18 @@ use crate::prelude::*;
 use crate::sequel::term::expr::Expr;
 use crate::sequel::term::select_item::SelectItem;
 use crate::sequel::term::table::TableInner;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use utils::impl_into_vec_for;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct Column {
     pub name: String,
+    #[serde(rename = "table", with = "crate::sequel::document::table_ref")]
     pub table_inner: Arc<TableInner>,
 }
+impl_into_vec_for!(Column => [Column]);
 
 impl Column {
     pub fn new(name: impl Into<String>, table: Arc<TableInner>) -> Self {
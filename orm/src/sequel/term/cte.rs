@@ -0,0 +1,48 @@
+use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::term::table::Table;
+use serde::{Deserialize, Serialize};
+
+// e.g. WITH recent (user_id, total) AS MATERIALIZED (SELECT ...) SELECT ... FROM recent;
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Cte {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub query: Box<SelectStatement>,
+    pub materialized: Option<bool>,
+}
+
+impl Cte {
+    pub fn new(name: impl Into<String>, query: SelectStatement) -> Self {
+        Self {
+            name: name.into(),
+            columns: Vec::new(),
+            query: Box::new(query),
+            materialized: None,
+        }
+    }
+
+    pub fn columns<T, I>(mut self, columns: I) -> Self
+    where
+        T: Into<String>,
+        I: IntoIterator<Item = T>,
+    {
+        self.columns = columns.into_iter().map(|c| c.into()).collect();
+        self
+    }
+
+    // 仅 PostgreSQL/SQLite 会输出 MATERIALIZED 提示
+    pub fn materialized(mut self) -> Self {
+        self.materialized = Some(true);
+        self
+    }
+
+    pub fn not_materialized(mut self) -> Self {
+        self.materialized = Some(false);
+        self
+    }
+
+    // 引用 CTE 的表，与普通表一样使用 column()；同一个 Table 需要复用，别名依赖其身份
+    pub fn table(&self) -> Table {
+        Table::new(self.name.clone())
+    }
+}
@@ -1,6 +1,8 @@
 use crate::sequel::term::expr::Expr;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Distinct {
     None,
     All,
@@ -1,12 +1,17 @@
+use crate::model::columns::ColumnType;
+use crate::prelude::Comparable;
 use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::term::case::Case;
 use crate::sequel::term::column::Column;
 use crate::sequel::term::func::Func;
 use crate::sequel::term::ops::{BinaryOp, UnaryOp};
 use crate::sequel::term::param::Param;
 use crate::sequel::term::select_item::SelectItem;
+use serde::{Deserialize, Serialize};
 use utils::impl_into_vec_for;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self", rename_all = "snake_case")]
 pub enum Expr {
     // e.g. SELECT id FROM users;
     //      SELECT u.id FROM users u;
@@ -64,10 +69,22 @@ pub enum Expr {
     //     END
     // FROM
     //      products;
-    // Case { conditions: Vec<(Expr, Expr)>, else_expr: Option<Box<Expr>> },
+    Case(Case),
+
+    // e.g. SELECT CAST(price AS DECIMAL(10, 2)) FROM products;
+    //      SELECT price::NUMERIC(10, 2) FROM products;
+    Cast {
+        expr: Box<Expr>,
+        column_type: ColumnType,
+    },
 
     // e.g. SELECT (SELECT MAX(id) FROM users);
     Subquery(Box<SelectStatement>),
+
+    // 引用 upsert 中待插入行的值
+    // e.g. INSERT ... ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name;
+    //      INSERT ... ON DUPLICATE KEY UPDATE name = VALUES(name);
+    Excluded(Column),
 }
 
 impl Expr {
@@ -108,7 +125,18 @@ impl Expr {
         }
     }
 }
-impl_into_vec_for!(Expr => [Expr, Column, Func, SelectStatement]);
+impl_into_vec_for!(Expr => [Expr, Column, Func, Case, SelectStatement]);
+
+// 目标类型由各方言映射成自己的类型名
+pub fn cast<T>(expr: T, column_type: ColumnType) -> Expr
+where
+    T: Into<Expr>,
+{
+    Expr::Cast {
+        expr: Box::new(expr.into()),
+        column_type,
+    }
+}
 
 impl<T> From<Option<T>> for Expr
 where
@@ -136,6 +164,11 @@ impl From<Func> for Expr {
         Expr::Func(value)
     }
 }
+impl From<Case> for Expr {
+    fn from(value: Case) -> Self {
+        Expr::Case(value)
+    }
+}
 impl<T> From<T> for Expr
 where
     T: Into<Param>,
@@ -144,6 +177,13 @@ where
         Expr::Param(value.into())
     }
 }
+// e.g. cast(paid_at, ColumnType::Date).eq("2024-01-01")
+impl Comparable for Expr {
+    fn into_expr(&self) -> Expr {
+        self.clone()
+    }
+}
+
 impl std::ops::Not for Expr {
     type Output = Self;
 
@@ -1,18 +1,22 @@
 use crate::prelude::Comparable;
 use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::term::case::Case;
 use crate::sequel::term::column::Column;
 use crate::sequel::term::expr::Expr;
 use crate::sequel::term::param::Param;
 use crate::sequel::term::select_item::SelectItem;
+use crate::sequel::term::window::Window;
+use serde::{Deserialize, Serialize};
 use utils::impl_into_vec_for;
 use utils::into_vec::IntoVec;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FuncArg {
     Wildcard,
     Expr(Expr),
 }
-impl_into_vec_for!(FuncArg => [Column, Expr, SelectStatement, Param, FuncArg]);
+impl_into_vec_for!(FuncArg => [Column, Expr, Case, SelectStatement, Param, FuncArg]);
 
 impl From<Column> for FuncArg {
     fn from(col: Column) -> Self {
@@ -25,6 +29,11 @@ impl From<Expr> for FuncArg {
         FuncArg::Expr(expr)
     }
 }
+impl From<Case> for FuncArg {
+    fn from(case: Case) -> Self {
+        Self::Expr(Expr::Case(case))
+    }
+}
 impl From<SelectStatement> for FuncArg {
     fn from(stmt: SelectStatement) -> Self {
         Self::Expr(Expr::Subquery(Box::new(stmt)))
@@ -37,11 +46,13 @@ impl From<Param> for FuncArg {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct Func {
     pub name: String,
     pub args: Vec<FuncArg>,
     pub distinct: bool,
+    pub over: Option<Window>,
 }
 
 impl Func {
@@ -50,6 +61,17 @@ impl Func {
         self
     }
 
+    // e.g. ROW_NUMBER() OVER (PARTITION BY dept ORDER BY salary DESC)
+    pub fn over(mut self, window: Window) -> Self {
+        self.over = Some(window);
+        self
+    }
+
+    // e.g. SUM(amount) OVER w
+    pub fn over_window(self, name: impl Into<String>) -> Self {
+        self.over(Window::named(name))
+    }
+
     pub fn alias(self, alias: impl Into<String>) -> SelectItem {
         Expr::from(self).alias(alias)
     }
@@ -60,6 +82,7 @@ pub fn func(name: impl Into<String>, args: impl IntoVec<FuncArg>) -> Func {
         name: name.into().to_uppercase(),
         args: args.into_vec(),
         distinct: false,
+        over: None,
     }
 }
 
@@ -72,9 +95,21 @@ macro_rules! define_functions {
     };
 }
 define_functions!(
-    sum, avg, sqrt, abs, upper, lower, max, min, ceil, floor, exists, count, coalesce
+    sum, avg, sqrt, abs, upper, lower, max, min, ceil, floor, exists, count, coalesce,
+    lag, lead, first_value, last_value, nth_value, ntile
 );
 
+// 无参数的窗口函数
+macro_rules! define_nullary_functions {
+    ($($name:ident),*) => {
+        $(
+            #[inline]
+            pub fn $name() -> Func { func(stringify!($name), Vec::<FuncArg>::new()) }
+        )*
+    };
+}
+define_nullary_functions!(row_number, rank, dense_rank, percent_rank, cume_dist);
+
 // 处理 count(*)
 #[inline]
 pub fn count_all() -> Func {
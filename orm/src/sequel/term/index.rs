@@ -1,8 +1,9 @@
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fmt::Formatter;
 use utils::impl_into_vec_for;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Index(String);
 impl_into_vec_for!(Index => [Index, &'static str]);
 
@@ -21,7 +22,7 @@ impl fmt::Display for Index {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Indexes {
     pub force: Vec<Index>,
     pub use_: Vec<Index>,
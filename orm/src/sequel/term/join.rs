@@ -1,8 +1,10 @@
 use crate::sequel::term::expr::Expr;
 use crate::sequel::term::table::{Table, TableInner};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum JoinType {
     Inner,
     Left,
@@ -11,7 +13,7 @@ pub enum JoinType {
     Cross,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Join {
     pub left: Box<Table>,
     pub join_type: JoinType,
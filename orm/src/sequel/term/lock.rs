@@ -1,20 +1,27 @@
+use crate::sequel::term::column::Column;
 use crate::sequel::term::table::Table;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Lock {
     Update,
     UpdateOf(Vec<Table>),
+    // Oracle 按列加锁：FOR UPDATE OF t.col
+    UpdateOfColumns(Vec<Column>),
     Share,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Wait {
     Default, // default: wait
     NoWait,
     SkipLocked,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct Locking {
     pub lock: Option<Lock>,
     pub wait: Option<Wait>,
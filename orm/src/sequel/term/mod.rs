@@ -1,7 +1,9 @@
 pub mod arithmetic;
 pub mod calendar;
+pub mod case;
 pub mod column;
 pub mod comparable;
+pub mod cte;
 pub mod distinct;
 pub mod expr;
 pub mod func;
@@ -11,5 +13,9 @@ pub mod lock;
 pub mod ops;
 pub mod order;
 pub mod param;
+pub mod sample;
 pub mod select_item;
+pub mod set_op;
 pub mod table;
+pub mod values;
+pub mod window;
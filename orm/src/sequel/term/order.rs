@@ -1,11 +1,14 @@
 use crate::sequel::term::expr::Expr;
-#[derive(Clone, Debug)]
+use serde::{Deserialize, Serialize};
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Ordering {
     Asc,
     Desc,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct Order {
     pub expr: Expr,
     ordering: Ordering,
@@ -3,12 +3,14 @@ use crate::sequel::term::calendar::{Date, DateTime, Time};
 use crate::sequel::term::expr::Expr;
 use crate::sequel::term::select_item::SelectItem;
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use uuid::Uuid;
 
 
 pub type Json = Value;
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ParamData {
     Int(i64),
     Float(f64),
@@ -21,12 +23,17 @@ pub enum ParamData {
     Json(Json),
     Uuid(Uuid),
     Binary(Vec<u8>),
+    // 数组参数，e.g. PostgreSQL 的 = ANY($1)
+    Array(Vec<ParamData>),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Param {
     Literal(ParamData),
     Value(ParamData),
+    // 具名参数，同名的多次引用是同一个参数
+    Named(String, ParamData),
     Null,
 }
 
@@ -41,7 +48,7 @@ impl Param {
 
     pub fn data(&self) -> Option<&ParamData> {
         match self {
-            Self::Literal(data) | Self::Value(data) => Some(data),
+            Self::Literal(data) | Self::Value(data) | Self::Named(_, data) => Some(data),
             Self::Null => None,
         }
     }
@@ -121,3 +128,11 @@ pub fn value(v: impl Into<Param>) -> Param {
         p => p,
     }
 }
+
+// e.g. WHERE id = :user_id OR parent_id = :user_id
+pub fn named(name: impl Into<String>, v: impl Into<Param>) -> Param {
+    match v.into() {
+        Param::Literal(d) | Param::Value(d) | Param::Named(_, d) => Param::Named(name.into(), d),
+        Param::Null => Param::Null,
+    }
+}
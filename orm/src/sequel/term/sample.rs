@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+// e.g. DuckDB:     SELECT ... FROM t USING SAMPLE 10%;
+//                  SELECT ... FROM t USING SAMPLE 1000 ROWS;
+//      ClickHouse: SELECT ... FROM t SAMPLE 0.1;
+//                  SELECT ... FROM t SAMPLE 1000;
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sample {
+    Percent(f64),
+    Rows(usize),
+}
@@ -1,16 +1,18 @@
 use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::term::case::Case;
 use crate::sequel::term::column::Column;
 use crate::sequel::term::expr::Expr;
 use crate::sequel::term::func::Func;
 use crate::sequel::term::param::{Param, lit};
+use serde::{Deserialize, Serialize};
 use utils::impl_into_vec_for;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SelectItem {
     pub expr: Expr,
     pub alias: Option<String>,
 }
-impl_into_vec_for!(SelectItem => [SelectStatement, Column, Func, Expr, Param, SelectItem]);
+impl_into_vec_for!(SelectItem => [SelectStatement, Column, Func, Case, Expr, Param, SelectItem]);
 
 impl From<SelectStatement> for SelectItem {
     fn from(value: SelectStatement) -> Self {
@@ -39,6 +41,15 @@ impl From<Func> for SelectItem {
     }
 }
 
+impl From<Case> for SelectItem {
+    fn from(value: Case) -> Self {
+        Self {
+            expr: Expr::Case(value),
+            alias: None,
+        }
+    }
+}
+
 impl From<Expr> for SelectItem {
     fn from(expr: Expr) -> Self {
         Self { expr, alias: None }
@@ -0,0 +1,29 @@
+use crate::sequel::statement::select::SelectStatement;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SetOp {
+    Union,
+    UnionAll,
+    Intersect,
+    Except,
+}
+
+impl SetOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Union => "UNION",
+            Self::UnionAll => "UNION ALL",
+            Self::Intersect => "INTERSECT",
+            Self::Except => "EXCEPT",
+        }
+    }
+}
+
+// e.g. SELECT id FROM a UNION ALL SELECT id FROM b;
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetOperation {
+    pub op: SetOp,
+    pub stmt: SelectStatement,
+}
@@ -2,19 +2,52 @@ use crate::sequel::statement::select::SelectStatement;
 use crate::sequel::term::column::Column;
 use crate::sequel::term::expr::Expr;
 use crate::sequel::term::join::{Join, JoinType};
+use crate::sequel::term::values::Values;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use utils::impl_into_vec_for;
 use utils::into_vec::IntoVec;
 
-#[derive(Debug, Clone)]
+// 表名及其所属的 schema、catalog，e.g. warehouse.analytics.events
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableName {
+    pub catalog: Option<String>,
+    pub schema: Option<String>,
+    pub name: String,
+}
+
+impl TableName {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            catalog: None,
+            schema: None,
+            name: name.into(),
+        }
+    }
+
+    pub fn is_qualified(&self) -> bool {
+        self.schema.is_some() || self.catalog.is_some()
+    }
+
+    // 依次为 catalog、schema、表名
+    pub fn parts(&self) -> impl Iterator<Item = &str> {
+        [self.catalog.as_deref(), self.schema.as_deref(), Some(self.name.as_str())].into_iter().flatten()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TableInner {
-    Named(String),
+    Named(TableName),
     Subquery(Box<SelectStatement>),
     Join(Join),
+    Values(Values),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct Table {
+    #[serde(rename = "table", with = "crate::sequel::document::table_ref")]
     pub inner: Arc<TableInner>,
     pub alias: Option<String>,
 }
@@ -30,6 +63,15 @@ impl From<&str> for Table {
     }
 }
 
+impl From<TableName> for Table {
+    fn from(value: TableName) -> Self {
+        Self {
+            inner: Arc::new(TableInner::Named(value)),
+            alias: None,
+        }
+    }
+}
+
 impl From<SelectStatement> for Table {
     fn from(value: SelectStatement) -> Self {
         let inner = TableInner::Subquery(Box::new(value));
@@ -42,7 +84,7 @@ impl From<SelectStatement> for Table {
 
 impl Table {
     pub fn new(value: impl Into<String>) -> Self {
-        let inner = TableInner::Named(value.into());
+        let inner = TableInner::Named(TableName::new(value));
         Self {
             inner: Arc::new(inner),
             alias: None,
@@ -57,6 +99,22 @@ impl Table {
         }
     }
 
+    // 修改的是表本身，之前取得的列不会跟随变化，应在取列之前设置
+    //   let events = Table::new("events").schema("analytics");
+    pub fn schema(mut self, schema: impl Into<String>) -> Self {
+        if let TableInner::Named(name) = Arc::make_mut(&mut self.inner) {
+            name.schema = Some(schema.into());
+        }
+        self
+    }
+
+    pub fn catalog(mut self, catalog: impl Into<String>) -> Self {
+        if let TableInner::Named(name) = Arc::make_mut(&mut self.inner) {
+            name.catalog = Some(catalog.into());
+        }
+        self
+    }
+
     pub fn column(&self, name: impl Into<String>) -> Column {
         Column {
             name: name.into(),
@@ -82,7 +140,7 @@ impl Table {
             return alias.clone();
         }
         match &*self.inner {
-            TableInner::Named(inner) => inner.clone(),
+            TableInner::Named(inner) => inner.name.clone(),
             _ => String::from(""),
         }
     }
@@ -148,4 +206,14 @@ mod tests {
         assert!(Arc::ptr_eq(&u.inner, &u_clone.inner));
         assert!(!Arc::ptr_eq(&u.inner, &u_fork.inner));
     }
+
+    #[test]
+    pub fn test_schema() {
+        let t = Table::new("events").schema("analytics").catalog("warehouse");
+        let TableInner::Named(name) = t.inner.as_ref() else {
+            panic!("expected a named table");
+        };
+        assert_eq!(name.parts().collect::<Vec<_>>(), vec!["warehouse", "analytics", "events"]);
+        assert_eq!(t.visible_name(), "events");
+    }
 }
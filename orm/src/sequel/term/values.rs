@@ -0,0 +1,33 @@
+use crate::sequel::term::expr::Expr;
+use crate::sequel::term::table::{Table, TableInner};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+// 由 VALUES 构造的临时表，e.g. (VALUES (1, 'a'), (2, 'b')) AS v (id, name)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Values {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Expr>>,
+}
+
+impl Values {
+    pub fn new(columns: impl IntoIterator<Item = impl Into<String>>, rows: Vec<Vec<Expr>>) -> Self {
+        Self {
+            columns: columns.into_iter().map(|c| c.into()).collect(),
+            rows,
+        }
+    }
+
+    pub fn alias(self, alias: impl Into<String>) -> Table {
+        Table::from(self).alias(alias)
+    }
+}
+
+impl From<Values> for Table {
+    fn from(value: Values) -> Self {
+        Self {
+            inner: Arc::new(TableInner::Values(value)),
+            alias: None,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::sequel::term::expr::Expr;
+use crate::sequel::term::order::Order;
+use serde::{Deserialize, Serialize};
+use utils::into_vec::IntoVec;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameUnit {
+    Rows,
+    Range,
+    Groups,
+}
+
+impl FrameUnit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rows => "ROWS",
+            Self::Range => "RANGE",
+            Self::Groups => "GROUPS",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameBound {
+    UnboundedPreceding,
+    Preceding(usize),
+    CurrentRow,
+    Following(usize),
+    UnboundedFollowing,
+}
+
+// e.g. ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Frame {
+    pub unit: FrameUnit,
+    pub start: FrameBound,
+    pub end: Option<FrameBound>,
+}
+
+// e.g. OVER (PARTITION BY dept ORDER BY salary DESC ROWS BETWEEN 1 PRECEDING AND CURRENT ROW)
+//      OVER w
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Window {
+    // 引用 WINDOW 子句中定义的命名窗口
+    pub name: Option<String>,
+    pub partition_by: Vec<Expr>,
+    pub orders: Vec<Order>,
+    pub frame: Option<Frame>,
+}
+
+impl Window {
+    pub fn new() -> Self {
+        Self {
+            name: None,
+            partition_by: Vec::new(),
+            orders: Vec::new(),
+            frame: None,
+        }
+    }
+
+    pub fn named(name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Self::new()
+        }
+    }
+
+    pub fn partition_by(mut self, cs: impl IntoVec<Expr>) -> Self {
+        for item in cs.into_vec() {
+            self.partition_by.push(item);
+        }
+        self
+    }
+
+    pub fn order_by(mut self, cs: impl IntoVec<Expr>) -> Self {
+        for item in cs.into_vec() {
+            self.orders.push(Order::asc(item));
+        }
+        self
+    }
+    pub fn order_by_desc(mut self, cs: impl IntoVec<Expr>) -> Self {
+        for item in cs.into_vec() {
+            self.orders.push(Order::desc(item));
+        }
+        self
+    }
+
+    pub fn rows(self, start: FrameBound) -> Self {
+        self.frame(FrameUnit::Rows, start, None)
+    }
+    pub fn rows_between(self, start: FrameBound, end: FrameBound) -> Self {
+        self.frame(FrameUnit::Rows, start, Some(end))
+    }
+    pub fn range(self, start: FrameBound) -> Self {
+        self.frame(FrameUnit::Range, start, None)
+    }
+    pub fn range_between(self, start: FrameBound, end: FrameBound) -> Self {
+        self.frame(FrameUnit::Range, start, Some(end))
+    }
+    pub fn groups(self, start: FrameBound) -> Self {
+        self.frame(FrameUnit::Groups, start, None)
+    }
+    pub fn groups_between(self, start: FrameBound, end: FrameBound) -> Self {
+        self.frame(FrameUnit::Groups, start, Some(end))
+    }
+
+    fn frame(mut self, unit: FrameUnit, start: FrameBound, end: Option<FrameBound>) -> Self {
+        self.frame = Some(Frame { unit, start, end });
+        self
+    }
+
+    // 只引用命名窗口时渲染为 OVER w，不需要括号
+    pub fn is_name_only(&self) -> bool {
+        self.name.is_some() && self.partition_by.is_empty() && self.orders.is_empty() && self.frame.is_none()
+    }
+}
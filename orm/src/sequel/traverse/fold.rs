@@ -0,0 +1,215 @@
+use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::term::column::Column;
+use crate::sequel::term::distinct::Distinct;
+use crate::sequel::term::expr::Expr;
+use crate::sequel::term::func::{Func, FuncArg};
+use crate::sequel::term::order::Order;
+use crate::sequel::term::param::Param;
+use crate::sequel::term::select_item::SelectItem;
+use crate::sequel::term::table::{Table, TableInner};
+use crate::sequel::term::window::Window;
+use crate::sequel::traverse::visit_mut::{from_tables, relink};
+use std::sync::Arc;
+
+// 按值转换，适合把节点替换成另一种节点，e.g. 把 IN 列表改写为 OR
+// 与 VisitMut 一样，所在语句转换结束后指向旧表的列会改为指向新表
+//   struct InToOr;
+//   impl Fold for InToOr {
+//       fn fold_expr(&mut self, expr: Expr) -> Expr {
+//           match fold::fold_expr(self, expr) {
+//               Expr::In { expr, list, negated: false } => ...,
+//               expr => expr,
+//           }
+//       }
+//   }
+pub trait Fold {
+    fn fold_select_statement(&mut self, stmt: SelectStatement) -> SelectStatement {
+        fold_select_statement(self, stmt)
+    }
+
+    fn fold_select_item(&mut self, item: SelectItem) -> SelectItem {
+        fold_select_item(self, item)
+    }
+
+    fn fold_table(&mut self, table: Table) -> Table {
+        fold_table(self, table)
+    }
+
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        fold_expr(self, expr)
+    }
+
+    fn fold_func(&mut self, func: Func) -> Func {
+        fold_func(self, func)
+    }
+
+    fn fold_window(&mut self, window: Window) -> Window {
+        fold_window(self, window)
+    }
+
+    fn fold_column(&mut self, column: Column) -> Column {
+        column
+    }
+
+    fn fold_param(&mut self, param: Param) -> Param {
+        param
+    }
+}
+
+fn fold_exprs<F: Fold + ?Sized>(f: &mut F, exprs: Vec<Expr>) -> Vec<Expr> {
+    exprs.into_iter().map(|e| f.fold_expr(e)).collect()
+}
+
+fn fold_order<F: Fold + ?Sized>(f: &mut F, mut order: Order) -> Order {
+    order.expr = f.fold_expr(order.expr);
+    order
+}
+
+pub fn fold_select_statement<F: Fold + ?Sized>(f: &mut F, mut stmt: SelectStatement) -> SelectStatement {
+    // 原来的表持有到 relink 结束，避免释放后地址被新建的表复用
+    let before = from_tables(&stmt.from_clause);
+    stmt.with_clause = stmt
+        .with_clause
+        .into_iter()
+        .map(|mut cte| {
+            *cte.query = f.fold_select_statement(*cte.query);
+            cte
+        })
+        .collect();
+    if let Distinct::On(exprs) = stmt.distinct {
+        stmt.distinct = Distinct::On(fold_exprs(f, exprs));
+    }
+    stmt.select_clause = stmt
+        .select_clause
+        .into_iter()
+        .map(|item| f.fold_select_item(item))
+        .collect();
+    stmt.from_clause = f.fold_table(stmt.from_clause);
+    stmt.where_clause = fold_exprs(f, stmt.where_clause);
+    stmt.groups = fold_exprs(f, stmt.groups);
+    stmt.having_clause = fold_exprs(f, stmt.having_clause);
+    stmt.windows = stmt
+        .windows
+        .into_iter()
+        .map(|(name, window)| (name, f.fold_window(window)))
+        .collect();
+    stmt.qualify_clause = fold_exprs(f, stmt.qualify_clause);
+    stmt.orders = stmt.orders.into_iter().map(|order| fold_order(f, order)).collect();
+    stmt.limit_by = stmt.limit_by.map(|(n, exprs)| (n, fold_exprs(f, exprs)));
+    stmt.set_ops = stmt
+        .set_ops
+        .into_iter()
+        .map(|mut set_op| {
+            set_op.stmt = f.fold_select_statement(set_op.stmt);
+            set_op
+        })
+        .collect();
+    stmt.settings = stmt
+        .settings
+        .into_iter()
+        .map(|(name, value)| (name, f.fold_param(value)))
+        .collect();
+    let before: Vec<usize> = before.iter().map(|t| Arc::as_ptr(t) as usize).collect();
+    relink(&mut stmt, &before);
+    stmt
+}
+
+pub fn fold_select_item<F: Fold + ?Sized>(f: &mut F, item: SelectItem) -> SelectItem {
+    SelectItem {
+        expr: f.fold_expr(item.expr),
+        alias: item.alias,
+    }
+}
+
+// 具名表原样返回，保持与列的引用关系
+pub fn fold_table<F: Fold + ?Sized>(f: &mut F, table: Table) -> Table {
+    if let TableInner::Named(_) = table.inner.as_ref() {
+        return table;
+    }
+    let inner = match Arc::unwrap_or_clone(table.inner) {
+        TableInner::Named(name) => TableInner::Named(name),
+        TableInner::Subquery(sq) => TableInner::Subquery(Box::new(f.fold_select_statement(*sq))),
+        TableInner::Join(mut join) => {
+            *join.left = f.fold_table(*join.left);
+            *join.right = f.fold_table(*join.right);
+            join.on = join.on.map(|on| f.fold_expr(on));
+            TableInner::Join(join)
+        },
+        TableInner::Values(mut values) => {
+            values.rows = values.rows.into_iter().map(|row| fold_exprs(f, row)).collect();
+            TableInner::Values(values)
+        },
+    };
+    Table {
+        inner: Arc::new(inner),
+        alias: table.alias,
+    }
+}
+
+pub fn fold_expr<F: Fold + ?Sized>(f: &mut F, expr: Expr) -> Expr {
+    match expr {
+        Expr::Column(column) => Expr::Column(f.fold_column(column)),
+        Expr::Excluded(column) => Expr::Excluded(f.fold_column(column)),
+        Expr::Param(param) => Expr::Param(f.fold_param(param)),
+        Expr::Between {
+            expr,
+            low,
+            high,
+            negated,
+        } => Expr::Between {
+            expr: Box::new(f.fold_expr(*expr)),
+            low: Box::new(f.fold_expr(*low)),
+            high: Box::new(f.fold_expr(*high)),
+            negated,
+        },
+        Expr::In { expr, list, negated } => Expr::In {
+            expr: Box::new(f.fold_expr(*expr)),
+            list: fold_exprs(f, list),
+            negated,
+        },
+        Expr::Unary { op, expr } => Expr::Unary {
+            op,
+            expr: Box::new(f.fold_expr(*expr)),
+        },
+        Expr::Binary { left, op, right } => Expr::Binary {
+            left: Box::new(f.fold_expr(*left)),
+            op,
+            right: Box::new(f.fold_expr(*right)),
+        },
+        Expr::Func(func) => Expr::Func(f.fold_func(func)),
+        Expr::Case(mut case) => {
+            case.operand = case.operand.map(|operand| Box::new(f.fold_expr(*operand)));
+            case.conditions = case
+                .conditions
+                .into_iter()
+                .map(|(when, then)| (f.fold_expr(when), f.fold_expr(then)))
+                .collect();
+            case.else_expr = case.else_expr.map(|else_expr| Box::new(f.fold_expr(*else_expr)));
+            Expr::Case(case)
+        },
+        Expr::Cast { expr, column_type } => Expr::Cast {
+            expr: Box::new(f.fold_expr(*expr)),
+            column_type,
+        },
+        Expr::Subquery(sq) => Expr::Subquery(Box::new(f.fold_select_statement(*sq))),
+    }
+}
+
+pub fn fold_func<F: Fold + ?Sized>(f: &mut F, mut func: Func) -> Func {
+    func.args = func
+        .args
+        .into_iter()
+        .map(|arg| match arg {
+            FuncArg::Expr(expr) => FuncArg::Expr(f.fold_expr(expr)),
+            FuncArg::Wildcard => FuncArg::Wildcard,
+        })
+        .collect();
+    func.over = func.over.map(|window| f.fold_window(window));
+    func
+}
+
+pub fn fold_window<F: Fold + ?Sized>(f: &mut F, mut window: Window) -> Window {
+    window.partition_by = fold_exprs(f, window.partition_by);
+    window.orders = window.orders.into_iter().map(|order| fold_order(f, order)).collect();
+    window
+}
@@ -0,0 +1,3 @@
+pub mod fold;
+pub mod visit;
+pub mod visit_mut;
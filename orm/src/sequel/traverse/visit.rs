@@ -0,0 +1,152 @@
+use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::term::column::Column;
+use crate::sequel::term::distinct::Distinct;
+use crate::sequel::term::expr::Expr;
+use crate::sequel::term::func::{Func, FuncArg};
+use crate::sequel::term::param::Param;
+use crate::sequel::term::select_item::SelectItem;
+use crate::sequel::term::table::{Table, TableInner};
+use crate::sequel::term::window::Window;
+
+// 只读遍历，默认实现按渲染顺序访问所有子节点
+// 覆盖某个方法后，需要继续向下时调用本模块中的同名函数
+//   struct Columns<'a>(Vec<&'a Column>);
+//   impl<'a> Visit<'a> for Columns<'a> {
+//       fn visit_column(&mut self, column: &'a Column) {
+//           self.0.push(column);
+//       }
+//   }
+pub trait Visit<'a> {
+    fn visit_select_statement(&mut self, stmt: &'a SelectStatement) {
+        visit_select_statement(self, stmt);
+    }
+
+    fn visit_select_item(&mut self, item: &'a SelectItem) {
+        visit_select_item(self, item);
+    }
+
+    fn visit_table(&mut self, table: &'a Table) {
+        visit_table(self, table);
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        visit_expr(self, expr);
+    }
+
+    fn visit_func(&mut self, func: &'a Func) {
+        visit_func(self, func);
+    }
+
+    fn visit_window(&mut self, window: &'a Window) {
+        visit_window(self, window);
+    }
+
+    fn visit_column(&mut self, _column: &'a Column) {}
+
+    fn visit_param(&mut self, _param: &'a Param) {}
+}
+
+// FOR UPDATE OF 引用的是 FROM 中已有的表和列，不再访问
+pub fn visit_select_statement<'a, V: Visit<'a> + ?Sized>(v: &mut V, stmt: &'a SelectStatement) {
+    for cte in &stmt.with_clause {
+        v.visit_select_statement(&cte.query);
+    }
+    if let Distinct::On(exprs) = &stmt.distinct {
+        exprs.iter().for_each(|e| v.visit_expr(e));
+    }
+    for item in &stmt.select_clause {
+        v.visit_select_item(item);
+    }
+    v.visit_table(&stmt.from_clause);
+    stmt.where_clause.iter().for_each(|e| v.visit_expr(e));
+    stmt.groups.iter().for_each(|e| v.visit_expr(e));
+    stmt.having_clause.iter().for_each(|e| v.visit_expr(e));
+    for (_, window) in &stmt.windows {
+        v.visit_window(window);
+    }
+    stmt.qualify_clause.iter().for_each(|e| v.visit_expr(e));
+    for order in &stmt.orders {
+        v.visit_expr(&order.expr);
+    }
+    if let Some((_, exprs)) = &stmt.limit_by {
+        exprs.iter().for_each(|e| v.visit_expr(e));
+    }
+    for set_op in &stmt.set_ops {
+        v.visit_select_statement(&set_op.stmt);
+    }
+    for (_, value) in &stmt.settings {
+        v.visit_param(value);
+    }
+}
+
+pub fn visit_select_item<'a, V: Visit<'a> + ?Sized>(v: &mut V, item: &'a SelectItem) {
+    v.visit_expr(&item.expr);
+}
+
+pub fn visit_table<'a, V: Visit<'a> + ?Sized>(v: &mut V, table: &'a Table) {
+    match table.inner.as_ref() {
+        TableInner::Named(_) => {},
+        TableInner::Subquery(sq) => v.visit_select_statement(sq),
+        TableInner::Join(join) => {
+            v.visit_table(&join.left);
+            v.visit_table(&join.right);
+            if let Some(on) = &join.on {
+                v.visit_expr(on);
+            }
+        },
+        TableInner::Values(values) => values.rows.iter().flatten().for_each(|e| v.visit_expr(e)),
+    }
+}
+
+pub fn visit_expr<'a, V: Visit<'a> + ?Sized>(v: &mut V, expr: &'a Expr) {
+    match expr {
+        Expr::Column(column) | Expr::Excluded(column) => v.visit_column(column),
+        Expr::Param(param) => v.visit_param(param),
+        Expr::Between { expr, low, high, .. } => {
+            v.visit_expr(expr);
+            v.visit_expr(low);
+            v.visit_expr(high);
+        },
+        Expr::In { expr, list, .. } => {
+            v.visit_expr(expr);
+            list.iter().for_each(|e| v.visit_expr(e));
+        },
+        Expr::Unary { expr, .. } | Expr::Cast { expr, .. } => v.visit_expr(expr),
+        Expr::Binary { left, right, .. } => {
+            v.visit_expr(left);
+            v.visit_expr(right);
+        },
+        Expr::Func(func) => v.visit_func(func),
+        Expr::Case(case) => {
+            if let Some(operand) = &case.operand {
+                v.visit_expr(operand);
+            }
+            for (when, then) in &case.conditions {
+                v.visit_expr(when);
+                v.visit_expr(then);
+            }
+            if let Some(else_expr) = &case.else_expr {
+                v.visit_expr(else_expr);
+            }
+        },
+        Expr::Subquery(sq) => v.visit_select_statement(sq),
+    }
+}
+
+pub fn visit_func<'a, V: Visit<'a> + ?Sized>(v: &mut V, func: &'a Func) {
+    for arg in &func.args {
+        if let FuncArg::Expr(expr) = arg {
+            v.visit_expr(expr);
+        }
+    }
+    if let Some(window) = &func.over {
+        v.visit_window(window);
+    }
+}
+
+pub fn visit_window<'a, V: Visit<'a> + ?Sized>(v: &mut V, window: &'a Window) {
+    window.partition_by.iter().for_each(|e| v.visit_expr(e));
+    for order in &window.orders {
+        v.visit_expr(&order.expr);
+    }
+}
@@ -0,0 +1,253 @@
+use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::term::column::Column;
+use crate::sequel::term::distinct::Distinct;
+use crate::sequel::term::expr::Expr;
+use crate::sequel::term::func::{Func, FuncArg};
+use crate::sequel::term::lock::Lock;
+use crate::sequel::term::param::Param;
+use crate::sequel::term::select_item::SelectItem;
+use crate::sequel::term::table::{Table, TableInner};
+use crate::sequel::term::window::Window;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// 原地修改的遍历，访问顺序与 Visit 相同
+// 列通过 Arc 引用所属的表，修改子查询等被列引用的表时会复制一份，
+// 所在语句遍历结束后，指向旧表的列和 FOR UPDATE OF 会改为指向新表
+//   struct Rename;
+//   impl VisitMut for Rename {
+//       fn visit_column_mut(&mut self, column: &mut Column) {
+//           column.name = column.name.to_lowercase();
+//       }
+//   }
+pub trait VisitMut {
+    fn visit_select_statement_mut(&mut self, stmt: &mut SelectStatement) {
+        visit_select_statement_mut(self, stmt);
+    }
+
+    fn visit_select_item_mut(&mut self, item: &mut SelectItem) {
+        visit_select_item_mut(self, item);
+    }
+
+    fn visit_table_mut(&mut self, table: &mut Table) {
+        visit_table_mut(self, table);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        visit_expr_mut(self, expr);
+    }
+
+    fn visit_func_mut(&mut self, func: &mut Func) {
+        visit_func_mut(self, func);
+    }
+
+    fn visit_window_mut(&mut self, window: &mut Window) {
+        visit_window_mut(self, window);
+    }
+
+    fn visit_column_mut(&mut self, _column: &mut Column) {}
+
+    fn visit_param_mut(&mut self, _param: &mut Param) {}
+}
+
+pub fn visit_select_statement_mut<V: VisitMut + ?Sized>(v: &mut V, stmt: &mut SelectStatement) {
+    let before = from_table_ptrs(&stmt.from_clause);
+    for cte in &mut stmt.with_clause {
+        v.visit_select_statement_mut(&mut cte.query);
+    }
+    if let Distinct::On(exprs) = &mut stmt.distinct {
+        exprs.iter_mut().for_each(|e| v.visit_expr_mut(e));
+    }
+    for item in &mut stmt.select_clause {
+        v.visit_select_item_mut(item);
+    }
+    v.visit_table_mut(&mut stmt.from_clause);
+    stmt.where_clause.iter_mut().for_each(|e| v.visit_expr_mut(e));
+    stmt.groups.iter_mut().for_each(|e| v.visit_expr_mut(e));
+    stmt.having_clause.iter_mut().for_each(|e| v.visit_expr_mut(e));
+    for (_, window) in &mut stmt.windows {
+        v.visit_window_mut(window);
+    }
+    stmt.qualify_clause.iter_mut().for_each(|e| v.visit_expr_mut(e));
+    for order in &mut stmt.orders {
+        v.visit_expr_mut(&mut order.expr);
+    }
+    if let Some((_, exprs)) = &mut stmt.limit_by {
+        exprs.iter_mut().for_each(|e| v.visit_expr_mut(e));
+    }
+    for set_op in &mut stmt.set_ops {
+        v.visit_select_statement_mut(&mut set_op.stmt);
+    }
+    for (_, value) in &mut stmt.settings {
+        v.visit_param_mut(value);
+    }
+    relink(stmt, &before);
+}
+
+pub fn visit_select_item_mut<V: VisitMut + ?Sized>(v: &mut V, item: &mut SelectItem) {
+    v.visit_expr_mut(&mut item.expr);
+}
+
+// 具名表没有子节点，不会被复制
+pub fn visit_table_mut<V: VisitMut + ?Sized>(v: &mut V, table: &mut Table) {
+    if !matches!(table.inner.as_ref(), TableInner::Named(_)) {
+        visit_table_inner_mut(v, Arc::make_mut(&mut table.inner));
+    }
+}
+
+fn visit_table_inner_mut<V: VisitMut + ?Sized>(v: &mut V, inner: &mut TableInner) {
+    match inner {
+        TableInner::Named(_) => {},
+        TableInner::Subquery(sq) => v.visit_select_statement_mut(sq),
+        TableInner::Join(join) => {
+            v.visit_table_mut(&mut join.left);
+            v.visit_table_mut(&mut join.right);
+            if let Some(on) = &mut join.on {
+                v.visit_expr_mut(on);
+            }
+        },
+        TableInner::Values(values) => values.rows.iter_mut().flatten().for_each(|e| v.visit_expr_mut(e)),
+    }
+}
+
+pub fn visit_expr_mut<V: VisitMut + ?Sized>(v: &mut V, expr: &mut Expr) {
+    match expr {
+        Expr::Column(column) | Expr::Excluded(column) => v.visit_column_mut(column),
+        Expr::Param(param) => v.visit_param_mut(param),
+        Expr::Between { expr, low, high, .. } => {
+            v.visit_expr_mut(expr);
+            v.visit_expr_mut(low);
+            v.visit_expr_mut(high);
+        },
+        Expr::In { expr, list, .. } => {
+            v.visit_expr_mut(expr);
+            list.iter_mut().for_each(|e| v.visit_expr_mut(e));
+        },
+        Expr::Unary { expr, .. } | Expr::Cast { expr, .. } => v.visit_expr_mut(expr),
+        Expr::Binary { left, right, .. } => {
+            v.visit_expr_mut(left);
+            v.visit_expr_mut(right);
+        },
+        Expr::Func(func) => v.visit_func_mut(func),
+        Expr::Case(case) => {
+            if let Some(operand) = &mut case.operand {
+                v.visit_expr_mut(operand);
+            }
+            for (when, then) in &mut case.conditions {
+                v.visit_expr_mut(when);
+                v.visit_expr_mut(then);
+            }
+            if let Some(else_expr) = &mut case.else_expr {
+                v.visit_expr_mut(else_expr);
+            }
+        },
+        Expr::Subquery(sq) => v.visit_select_statement_mut(sq),
+    }
+}
+
+pub fn visit_func_mut<V: VisitMut + ?Sized>(v: &mut V, func: &mut Func) {
+    for arg in &mut func.args {
+        if let FuncArg::Expr(expr) = arg {
+            v.visit_expr_mut(expr);
+        }
+    }
+    if let Some(window) = &mut func.over {
+        v.visit_window_mut(window);
+    }
+}
+
+pub fn visit_window_mut<V: VisitMut + ?Sized>(v: &mut V, window: &mut Window) {
+    window.partition_by.iter_mut().for_each(|e| v.visit_expr_mut(e));
+    for order in &mut window.orders {
+        v.visit_expr_mut(&mut order.expr);
+    }
+}
+
+// FROM 中的表按先序排列
+pub(super) fn from_tables(table: &Table) -> Vec<Arc<TableInner>> {
+    fn collect(table: &Table, tables: &mut Vec<Arc<TableInner>>) {
+        tables.push(table.inner.clone());
+        if let TableInner::Join(join) = table.inner.as_ref() {
+            collect(&join.left, tables);
+            collect(&join.right, tables);
+        }
+    }
+    let mut tables = Vec::new();
+    collect(table, &mut tables);
+    tables
+}
+
+// 遍历前只记录地址，持有 Arc 会导致修改时总是复制
+pub(super) fn from_table_ptrs(table: &Table) -> Vec<usize> {
+    from_tables(table).iter().map(|t| Arc::as_ptr(t) as usize).collect()
+}
+
+// 遍历前后 FROM 中的表一一对应时，把引用旧表的列改为引用新表
+// 遍历中增删了 FROM 中的表时无法对应，需要调用方自行维护
+pub(super) fn relink(stmt: &mut SelectStatement, before: &[usize]) {
+    let after = from_tables(&stmt.from_clause);
+    if after.len() != before.len() {
+        return;
+    }
+    let moved: HashMap<usize, Arc<TableInner>> = before
+        .iter()
+        .zip(after)
+        .filter(|(old, new)| **old != Arc::as_ptr(new) as usize)
+        .map(|(old, new)| (*old, new))
+        .collect();
+    if moved.is_empty() {
+        return;
+    }
+    let mut relink = Relink(moved);
+    relink.visit_select_statement_mut(stmt);
+    if let Some(lock) = stmt.locking.as_mut().and_then(|l| l.lock.as_mut()) {
+        match lock {
+            Lock::UpdateOf(tables) => {
+                for table in tables {
+                    if let Some(new) = relink.0.get(&(Arc::as_ptr(&table.inner) as usize)) {
+                        table.inner = new.clone();
+                    }
+                }
+            },
+            Lock::UpdateOfColumns(columns) => columns.iter_mut().for_each(|c| relink.visit_column_mut(c)),
+            Lock::Update | Lock::Share => {},
+        }
+    }
+}
+
+struct Relink(HashMap<usize, Arc<TableInner>>);
+
+impl VisitMut for Relink {
+    // 先处理 FROM，这时还没有列引用新的连接，连接的 ON 可以原地修改
+    fn visit_select_statement_mut(&mut self, stmt: &mut SelectStatement) {
+        self.visit_table_mut(&mut stmt.from_clause);
+        let from = std::mem::replace(&mut stmt.from_clause, Table::new(""));
+        visit_select_statement_mut(self, stmt);
+        stmt.from_clause = from;
+    }
+
+    // 只修改列，不能复制被引用的表；映射中持有的新表暂时移出，以便原地修改
+    fn visit_table_mut(&mut self, table: &mut Table) {
+        let held: Vec<usize> = self
+            .0
+            .iter()
+            .filter(|(_, new)| Arc::ptr_eq(new, &table.inner))
+            .map(|(&old, _)| old)
+            .collect();
+        held.iter().for_each(|old| {
+            self.0.remove(old);
+        });
+        if let Some(inner) = Arc::get_mut(&mut table.inner) {
+            visit_table_inner_mut(self, inner);
+        }
+        for old in held {
+            self.0.insert(old, table.inner.clone());
+        }
+    }
+
+    fn visit_column_mut(&mut self, column: &mut Column) {
+        if let Some(new) = self.0.get(&(Arc::as_ptr(&column.table_inner) as usize)) {
+            column.table_inner = new.clone();
+        }
+    }
+}
@@ -1,23 +1,41 @@
 use crate::sequel::term::table::TableInner;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 pub struct AliasCache {
     all_alias: HashMap<String, usize>,
     mapping: HashMap<usize, (String, Option<String>)>,
+    ctes: HashSet<String>,
+    // 自动生成的别名及其来源，e.g. ("users0", "users")
+    generated: Vec<(String, String)>,
 }
 impl AliasCache {
     pub fn new() -> Self {
         Self {
             all_alias: HashMap::new(),
             mapping: HashMap::new(),
+            ctes: HashSet::new(),
+            generated: Vec::new(),
         }
     }
+
+    pub fn add_cte(&mut self, name: &str) {
+        self.ctes.insert(name.to_string());
+    }
     pub fn add(&mut self, table_inner: &Arc<TableInner>, name: String, default_alias: Option<String>) {
         let addr = Arc::as_ptr(table_inner) as usize;
         if !self.mapping.contains_key(&addr) {
             let n = *self.all_alias.get(&name).unwrap_or(&0);
-            self.mapping.insert(addr, (format!("{}{}", name, n), default_alias));
+            // CTE 的第一次引用直接使用 CTE 名，不再生成 xxx0 别名
+            let generated = if n == 0 && self.ctes.contains(&name) {
+                name.clone()
+            } else {
+                format!("{}{}", name, n)
+            };
+            if default_alias.is_none() && generated != name {
+                self.generated.push((generated.clone(), name.clone()));
+            }
+            self.mapping.insert(addr, (generated, default_alias));
             self.all_alias.insert(name, n + 1);
         }
     }
@@ -34,4 +52,8 @@ impl AliasCache {
             None
         }
     }
+
+    pub fn generated(&self) -> &[(String, String)] {
+        &self.generated
+    }
 }
@@ -1,9 +1,21 @@
-use crate::sequel::term::param::{Param, ParamData};
-use crate::sequel::visitor::dialect::{Dialect, PlaceHolderStyle};
+use crate::sequel::term::param::ParamData;
+use crate::sequel::visitor::dialect::Dialect;
+use crate::sequel::visitor::error::RenderError;
+use std::collections::HashMap;
+
+// 参数名到 binder 中位置的映射，? 风格下同名参数每次引用各占一个位置
+pub type ParamMap = HashMap<String, Vec<usize>>;
 
 pub struct Builder {
     pub buff: String,
     pub binder: Vec<ParamData>,
+    pub names: ParamMap,
+    // 每个位置的参数名，未命名的参数为 None
+    pub(crate) slot_names: Vec<Option<String>>,
+    // 取值相同的未命名参数共用一个位置，见 Visitor::reuse_params
+    pub(crate) reuse: bool,
+    // 未命名参数的取值到位置的索引，只在 reuse 时使用
+    unnamed_slots: HashMap<String, usize>,
 }
 
 impl Builder {
@@ -14,6 +26,10 @@ impl Builder {
         Self {
             buff: String::with_capacity(size),
             binder: Vec::new(),
+            names: HashMap::new(),
+            slot_names: Vec::new(),
+            reuse: false,
+            unnamed_slots: HashMap::new(),
         }
     }
     #[inline]
@@ -22,15 +38,64 @@ impl Builder {
         self
     }
 
-    pub fn bind(&mut self, value: ParamData, dialect: &impl Dialect) -> &mut Self {
-        self.binder.push(value);
-        match dialect.placeholder_style() {
-            PlaceHolderStyle::QuestionMark => self.buff.push_str("?"),
-            PlaceHolderStyle::Numbered => {
-                self.buff.push_str("$");
-                self.buff.push_str(&self.binder.len().to_string());
-            },
+    // 返回是否占用了新的位置；开启复用且占位符可以复用时，取值相同的未命名参数共用一个位置
+    pub fn bind(&mut self, value: ParamData, dialect: &impl Dialect) -> bool {
+        let style = dialect.placeholder_style();
+        // 按 Debug 文本区分取值，同值不同类型不合并，e.g. 18 与 "18"
+        let key = (self.reuse && style.reusable()).then(|| format!("{:?}", value));
+        let (slot, new) = match key.as_ref().and_then(|key| self.unnamed_slots.get(key)) {
+            Some(&slot) => (slot, false),
+            None => (self.new_slot(value, None), true),
+        };
+        if new && let Some(key) = key {
+            self.unnamed_slots.insert(key, slot);
         }
-        self
+        if new && style.is_named() {
+            self.names.insert(unnamed_param(slot + 1), vec![slot]);
+        }
+        self.buff.push_str(&style.render(slot + 1));
+        new
+    }
+
+    pub fn bind_named(&mut self, name: &str, value: ParamData, dialect: &impl Dialect) -> Result<bool, RenderError> {
+        if !is_identifier(name) {
+            return Err(RenderError::invalid("named parameter", "a name must match [A-Za-z_][A-Za-z0-9_]*"));
+        }
+        if is_unnamed_param(name) {
+            return Err(RenderError::invalid("named parameter", "names like _p1 are reserved for unnamed parameters"));
+        }
+        let style = dialect.placeholder_style();
+        let (slot, new) = match self.names.get(name).and_then(|slots| slots.first()) {
+            Some(&slot) if self.binder[slot] != value => return Err(RenderError::ConflictingParam(name.to_string())),
+            Some(&slot) if style.reusable() => (slot, false),
+            _ => (self.new_slot(value, Some(name)), true),
+        };
+        if new {
+            self.names.entry(name.to_string()).or_default().push(slot);
+        }
+        self.buff.push_str(&style.render_named(name, slot + 1));
+        Ok(new)
     }
+
+    fn new_slot(&mut self, value: ParamData, name: Option<&str>) -> usize {
+        self.binder.push(value);
+        self.slot_names.push(name.map(str::to_string));
+        self.binder.len() - 1
+    }
+}
+
+// 具名风格下未命名参数的名字，e.g. _p1；用户的参数名不能使用这种形式
+pub fn unnamed_param(n: usize) -> String {
+    format!("_p{}", n)
+}
+
+// 名字原样写入 SQL，只接受 [A-Za-z_][A-Za-z0-9_]*
+fn is_identifier(name: &str) -> bool {
+    let mut bytes = name.bytes();
+    bytes.next().is_some_and(|b| b.is_ascii_alphabetic() || b == b'_')
+        && bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+pub fn is_unnamed_param(name: &str) -> bool {
+    name.strip_prefix("_p").is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
 }
@@ -5,6 +5,70 @@ pub enum CountDistinctCap {
     Merge,
     Rewrite,
 }
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub enum UpdateJoinCap {
+    #[default]
+    From, // UPDATE a SET ... FROM b WHERE ...
+    Join, // UPDATE a INNER JOIN b ON ... SET ...
+    AliasFrom, // UPDATE a SET ... FROM t AS a INNER JOIN b ON ...，目标表只能在 FROM 中起别名
+    None, // 只能单表 UPDATE
+}
+
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub enum DeleteJoinCap {
+    #[default]
+    Using, // DELETE FROM a USING b WHERE ...
+    Join, // DELETE a FROM a INNER JOIN b ON ...
+    Subquery, // DELETE FROM a WHERE EXISTS (SELECT 1 FROM b WHERE ...)
+    AliasFrom, // DELETE a FROM t AS a INNER JOIN b ON ...，单表删除同样按别名引用目标表
+}
+
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub enum UpsertCap {
+    #[default]
+    None,
+    OnConflict, // INSERT ... ON CONFLICT (...) DO UPDATE SET x = EXCLUDED.x
+    OnDuplicateKey, // INSERT ... ON DUPLICATE KEY UPDATE x = VALUES(x)
+}
+
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub enum CastCap {
+    #[default]
+    Function, // CAST(x AS INTEGER)
+    DoubleColon, // x::INTEGER
+}
+
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub enum LimitCap {
+    #[default]
+    LimitOffset, // LIMIT n OFFSET m
+    Top, // SELECT TOP n ...，带 OFFSET 时改用 ORDER BY ... OFFSET m ROWS FETCH NEXT n ROWS ONLY
+    OffsetFetch, // OFFSET m ROWS FETCH NEXT n ROWS ONLY，只有 LIMIT 时为 FETCH FIRST n ROWS ONLY
+}
+
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub enum SampleCap {
+    #[default]
+    None,
+    UsingSample, // SELECT ... USING SAMPLE 10%
+    Sample, // SELECT ... FROM t SAMPLE 0.1
+}
+
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub enum LockOfCap {
+    #[default]
+    Table, // FOR UPDATE OF t
+    Column, // FOR UPDATE OF t.col
+}
+
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub enum ValuesTableCap {
+    #[default]
+    None,
+    ColumnList,    // (VALUES (1), (2)) AS v (id)
+    SelectColumns, // (SELECT column1 AS id FROM (VALUES (1), (2))) AS v
+}
+
 #[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
 pub struct IndexFormat {
     pub before: &'static str,
@@ -27,6 +91,46 @@ pub struct Capability {
     pub select_with_locking: bool,
     pub count_distinct: CountDistinctCap,
     pub index_cap: IndexCap,
+    pub update_join: UpdateJoinCap,
+    pub delete_join: DeleteJoinCap,
+    pub write_with_limit: bool,
+    pub upsert: UpsertCap,
+    // ON CONFLICT ON CONSTRAINT name
+    pub conflict_constraint: bool,
+    pub cte_materialized: bool,
+    // WITH RECURSIVE，SQL Server 和 Oracle 的递归 CTE 只写 WITH
+    pub recursive_keyword: bool,
+    // 递归 CTE 必须写出列名，e.g. Oracle 的 WITH t (id, parent_id) AS (...)
+    pub recursive_cte_columns: bool,
+    // 窗口帧的 GROUPS 单位
+    pub groups_frame: bool,
+    pub parenthesized_set_operand: bool,
+    pub cast: CastCap,
+    pub limit: LimitCap,
+    // 锁和索引提示写在表名之后，e.g. FROM t WITH (INDEX(idx), UPDLOCK, ROWLOCK)
+    pub table_hints: bool,
+    // 表别名前不写 AS，e.g. FROM users u
+    pub bare_table_alias: bool,
+    // 标识符统一转成大写后再加引号
+    pub upper_case_identifiers: bool,
+    // 字符串字面量中的反斜杠是转义符，e.g. 'C:\\dir'
+    pub backslash_escape: bool,
+    pub lock_of: LockOfCap,
+    // FOR SHARE 共享锁
+    pub share_lock: bool,
+    pub qualify: bool,
+    pub sample: SampleCap,
+    pub final_modifier: bool,
+    pub settings: bool,
+    pub limit_by: bool,
+    pub group_by_all: bool,
+    // 一条语句最多绑定的参数个数，None 为不限制
+    pub max_params: Option<usize>,
+    // IN 列表最多的元素个数，超出时拆成多个 IN 用 OR 连接，None 为不限制
+    pub max_in_list: Option<usize>,
+    // IN 列表可以改写为 = ANY($1)，整个列表绑定为一个数组参数
+    pub array_binding: bool,
+    pub values_table: ValuesTableCap,
 }
 
 impl Capability {
@@ -42,6 +146,35 @@ impl Capability {
                 use_: None,
                 ignore: None,
             },
+            update_join: UpdateJoinCap::default(),
+            delete_join: DeleteJoinCap::default(),
+            write_with_limit: true,
+            upsert: UpsertCap::OnConflict,
+            conflict_constraint: true,
+            cte_materialized: true,
+            recursive_keyword: true,
+            recursive_cte_columns: false,
+            groups_frame: true,
+            parenthesized_set_operand: true,
+            cast: CastCap::default(),
+            limit: LimitCap::default(),
+            table_hints: false,
+            bare_table_alias: false,
+            upper_case_identifiers: false,
+            backslash_escape: false,
+            lock_of: LockOfCap::default(),
+            share_lock: true,
+            // 以下为分析型数据库的扩展语法
+            qualify: false,
+            sample: SampleCap::default(),
+            final_modifier: false,
+            settings: false,
+            limit_by: false,
+            group_by_all: false,
+            max_params: None,
+            max_in_list: None,
+            array_binding: false,
+            values_table: ValuesTableCap::default(),
         }
     }
 }
@@ -0,0 +1,63 @@
+use crate::model::columns::ColumnType;
+use crate::sequel::visitor::dialect::caps::{Capability, CountDistinctCap, SampleCap};
+use crate::sequel::visitor::dialect::{Dialect, PlaceHolderStyle, UnsupportedCast};
+
+#[derive(Clone)]
+pub struct ClickHouse {}
+impl Dialect for ClickHouse {
+    #[inline]
+    fn caps(&self) -> Capability {
+        Capability {
+            count_distinct: CountDistinctCap::Extend,
+            parenthesized_set_operand: true,
+            recursive_keyword: true,
+            qualify: true,
+            sample: SampleCap::Sample,
+            final_modifier: true,
+            settings: true,
+            limit_by: true,
+            group_by_all: true,
+            backslash_escape: true,
+            ..Capability::default()
+        }
+    }
+    #[inline]
+    fn quote_char(&self) -> &'static str {
+        "\""
+    }
+    #[inline]
+    fn placeholder_style(&self) -> PlaceHolderStyle {
+        PlaceHolderStyle::QuestionMark
+    }
+
+    #[inline]
+    fn bool_str(&self, v: bool) -> &'static str {
+        if v { "true" } else { "false" }
+    }
+
+    // ClickHouse 的类型名区分大小写
+    fn cast_type(&self, column_type: &ColumnType) -> Result<String, UnsupportedCast> {
+        let name = match column_type {
+            ColumnType::Char { .. } | ColumnType::Text | ColumnType::Json | ColumnType::Blob => "String".to_string(),
+            ColumnType::Int => "Int32".to_string(),
+            ColumnType::BigInt => "Int64".to_string(),
+            ColumnType::SmallInt => "Int16".to_string(),
+            ColumnType::UnsignedInt => "UInt32".to_string(),
+            ColumnType::UnsignedSmallInt => "UInt16".to_string(),
+            ColumnType::Float => "Float32".to_string(),
+            ColumnType::Double => "Float64".to_string(),
+            // Decimal 必须带精度
+            ColumnType::Decimal { precision, scale } => {
+                format!("Decimal({}, {})", precision.unwrap_or(10), scale.unwrap_or(0))
+            },
+            ColumnType::Bool => "Bool".to_string(),
+            ColumnType::Date => "Date".to_string(),
+            ColumnType::DateTime => "DateTime".to_string(),
+            ColumnType::Uuid => "UUID".to_string(),
+            ColumnType::Time | ColumnType::Auto | ColumnType::ForeignKey => {
+                return Err(UnsupportedCast::new("ClickHouse", column_type));
+            },
+        };
+        Ok(name)
+    }
+}
@@ -0,0 +1,106 @@
+use crate::model::columns::ColumnType;
+use crate::sequel::term::lock::{Lock, Wait};
+use crate::sequel::visitor::dialect::caps::Capability;
+use crate::sequel::visitor::dialect::{Dialect, PlaceHolderStyle, UnsupportedCast};
+use std::borrow::Cow;
+
+// 在已有方言的基础上派生新方言，e.g. MariaDB = MySQL + RETURNING
+//   let mariadb = DialectBuilder::new(MySQL {}).caps(|c| Capability { returning: true, ..c }).build();
+//   let visitor = Visitor::new(mariadb);
+pub struct DialectBuilder<D: Dialect> {
+    dialect: DerivedDialect<D>,
+}
+
+impl<D: Dialect> DialectBuilder<D> {
+    pub fn new(base: D) -> Self {
+        Self {
+            dialect: DerivedDialect {
+                caps: base.caps(),
+                quote: None,
+                placeholder_style: None,
+                bool_str: None,
+                base,
+            },
+        }
+    }
+
+    pub fn caps(mut self, f: impl FnOnce(Capability) -> Capability) -> Self {
+        self.dialect.caps = f(self.dialect.caps);
+        self
+    }
+
+    pub fn quote(mut self, open: &'static str, close: &'static str) -> Self {
+        self.dialect.quote = Some((open, close));
+        self
+    }
+
+    pub fn placeholder_style(mut self, style: PlaceHolderStyle) -> Self {
+        self.dialect.placeholder_style = Some(style);
+        self
+    }
+
+    pub fn bool_str(mut self, t: &'static str, f: &'static str) -> Self {
+        self.dialect.bool_str = Some((t, f));
+        self
+    }
+
+    pub fn build(self) -> DerivedDialect<D> {
+        self.dialect
+    }
+}
+
+// 未覆盖的部分委托给原方言；render_limit 与 render_identifier 依赖 caps()，由默认实现按派生后的能力渲染
+#[derive(Clone)]
+pub struct DerivedDialect<D: Dialect> {
+    base: D,
+    caps: Capability,
+    quote: Option<(&'static str, &'static str)>,
+    placeholder_style: Option<PlaceHolderStyle>,
+    bool_str: Option<(&'static str, &'static str)>,
+}
+
+impl<D: Dialect> Dialect for DerivedDialect<D> {
+    #[inline]
+    fn caps(&self) -> Capability {
+        self.caps
+    }
+    #[inline]
+    fn quote_char(&self) -> &'static str {
+        self.quote.map_or_else(|| self.base.quote_char(), |(open, _)| open)
+    }
+    #[inline]
+    fn quote_close_char(&self) -> &'static str {
+        self.quote.map_or_else(|| self.base.quote_close_char(), |(_, close)| close)
+    }
+    #[inline]
+    fn placeholder_style(&self) -> PlaceHolderStyle {
+        self.placeholder_style.unwrap_or_else(|| self.base.placeholder_style())
+    }
+    #[inline]
+    fn bool_str(&self, v: bool) -> &'static str {
+        match self.bool_str {
+            Some((t, f)) => if v { t } else { f },
+            None => self.base.bool_str(v),
+        }
+    }
+
+    fn cast_type(&self, column_type: &ColumnType) -> Result<String, UnsupportedCast> {
+        self.base.cast_type(column_type)
+    }
+
+    fn render_lock(&self, lock: &Lock, of: Option<&str>, wait: &Wait) -> String {
+        self.base.render_lock(lock, of, wait)
+    }
+
+    fn render_string(&self, v: &str) -> String {
+        self.base.render_string(v)
+    }
+
+    fn render_binary(&self, v: &[u8]) -> String {
+        self.base.render_binary(v)
+    }
+
+    fn render_func_name<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        self.base.render_func_name(name)
+    }
+}
@@ -0,0 +1,63 @@
+use crate::model::columns::ColumnType;
+use crate::sequel::visitor::dialect::caps::{Capability, CountDistinctCap, SampleCap, ValuesTableCap};
+use crate::sequel::visitor::dialect::{Dialect, PlaceHolderStyle, UnsupportedCast, decimal};
+
+#[derive(Clone)]
+pub struct DuckDB {}
+impl Dialect for DuckDB {
+    #[inline]
+    fn caps(&self) -> Capability {
+        Capability {
+            count_distinct: CountDistinctCap::Merge,
+            select_with_locking: false,
+            write_with_limit: false,
+            conflict_constraint: false,
+            qualify: true,
+            sample: SampleCap::UsingSample,
+            group_by_all: true,
+            values_table: ValuesTableCap::ColumnList,
+            ..Capability::all()
+        }
+    }
+    #[inline]
+    fn quote_char(&self) -> &'static str {
+        "\""
+    }
+    #[inline]
+    fn placeholder_style(&self) -> PlaceHolderStyle {
+        PlaceHolderStyle::Numbered
+    }
+
+    #[inline]
+    fn bool_str(&self, v: bool) -> &'static str {
+        if v { "true" } else { "false" }
+    }
+
+    fn cast_type(&self, column_type: &ColumnType) -> Result<String, UnsupportedCast> {
+        let name = match column_type {
+            ColumnType::Char { .. } | ColumnType::Text => "VARCHAR".to_string(),
+            ColumnType::Int => "INTEGER".to_string(),
+            ColumnType::BigInt => "BIGINT".to_string(),
+            ColumnType::SmallInt => "SMALLINT".to_string(),
+            ColumnType::UnsignedInt => "UINTEGER".to_string(),
+            ColumnType::UnsignedSmallInt => "USMALLINT".to_string(),
+            ColumnType::Float => "FLOAT".to_string(),
+            ColumnType::Double => "DOUBLE".to_string(),
+            ColumnType::Decimal { precision, scale } => decimal("DECIMAL", *precision, *scale),
+            ColumnType::Bool => "BOOLEAN".to_string(),
+            ColumnType::Date => "DATE".to_string(),
+            ColumnType::DateTime => "TIMESTAMP".to_string(),
+            ColumnType::Time => "TIME".to_string(),
+            ColumnType::Json => "JSON".to_string(),
+            ColumnType::Uuid => "UUID".to_string(),
+            ColumnType::Blob => "BLOB".to_string(),
+            ColumnType::Auto | ColumnType::ForeignKey => return Err(UnsupportedCast::new("DuckDB", column_type)),
+        };
+        Ok(name)
+    }
+
+    fn render_binary(&self, v: &[u8]) -> String {
+        let escaped: String = v.iter().map(|byte| format!("\\x{:02x}", byte)).collect();
+        format!("'{}'::BLOB", escaped)
+    }
+}
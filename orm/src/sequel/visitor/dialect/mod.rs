@@ -1,5 +1,18 @@
+use crate::model::columns::ColumnType;
+use crate::sequel::term::lock::{Lock, Wait};
+use crate::sequel::visitor::builder::unnamed_param;
+use crate::sequel::visitor::dialect::caps::LimitCap;
+use crate::sequel::visitor::error::RenderError;
+use std::borrow::Cow;
+use thiserror::Error;
+
 pub mod caps;
+pub mod clickhouse;
+pub mod derived;
+pub mod duckdb;
+pub mod mssql;
 pub mod mysql;
+pub mod oracle;
 pub mod postgre;
 pub mod sqlite;
 
@@ -7,10 +20,168 @@ pub mod sqlite;
 pub enum PlaceHolderStyle {
     QuestionMark,
     Numbered,
+    AtNumbered, // @p1, @p2
+    ColonNumbered, // :1, :2
+    ColonNamed, // :user_id
+    AtNamed, // @user_id
+}
+
+impl PlaceHolderStyle {
+    // 第 n 个占位符，n 从 1 开始；具名风格下未命名的参数命名为 _pn
+    pub fn render(&self, n: usize) -> String {
+        match self {
+            Self::QuestionMark => "?".to_string(),
+            Self::Numbered => format!("${}", n),
+            Self::AtNumbered => format!("@p{}", n),
+            Self::ColonNumbered => format!(":{}", n),
+            Self::ColonNamed => format!(":{}", unnamed_param(n)),
+            Self::AtNamed => format!("@{}", unnamed_param(n)),
+        }
+    }
+
+    pub fn render_named(&self, name: &str, n: usize) -> String {
+        match self {
+            Self::ColonNamed => format!(":{}", name),
+            Self::AtNamed => format!("@{}", name),
+            _ => self.render(n),
+        }
+    }
+
+    pub fn is_named(&self) -> bool {
+        matches!(self, Self::ColonNamed | Self::AtNamed)
+    }
+
+    // 同一个占位符能否在语句中引用多次；? 与 Oracle 的 :n 按出现顺序绑定，不能复用
+    pub fn reusable(&self) -> bool {
+        !matches!(self, Self::QuestionMark | Self::ColonNumbered)
+    }
 }
+
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("UnsupportedCast: {dialect} can not CAST to {column_type:?}")]
+pub struct UnsupportedCast {
+    pub dialect: &'static str,
+    pub column_type: ColumnType,
+}
+
+impl UnsupportedCast {
+    pub fn new(dialect: &'static str, column_type: &ColumnType) -> Self {
+        Self {
+            dialect,
+            column_type: column_type.clone(),
+        }
+    }
+}
+
 pub trait Dialect {
     fn caps(&self) -> caps::Capability;
     fn quote_char(&self) -> &'static str;
+    // 左右不对称的引号，e.g. [name]
+    fn quote_close_char(&self) -> &'static str {
+        self.quote_char()
+    }
     fn placeholder_style(&self) -> PlaceHolderStyle;
-    fn bool_str(&self, v: bool) -> &'static str;
+    fn bool_str(&self, v: bool) -> &'static str {
+        if v { "TRUE" } else { "FALSE" }
+    }
+    // CAST 的目标类型名
+    fn cast_type(&self, column_type: &ColumnType) -> Result<String, UnsupportedCast>;
+
+    // 以下为渲染钩子，默认实现按 caps() 渲染，方言变体（MariaDB、TiDB 等）可按需覆盖
+
+    // 分页子句，包含前导空格，e.g. " LIMIT 10 OFFSET 20"
+    fn render_limit(&self, limit: Option<usize>, offset: Option<usize>) -> Result<String, RenderError> {
+        let mut sql = String::new();
+        match self.caps().limit {
+            LimitCap::LimitOffset => {
+                if let Some(n) = limit {
+                    sql.push_str(&format!(" LIMIT {}", n));
+                }
+                if let Some(n) = offset {
+                    if limit.is_none() && !self.caps().standalone_offset {
+                        return Err(RenderError::unsupported("OFFSET", "the dialect requires a LIMIT before OFFSET"));
+                    }
+                    sql.push_str(&format!(" OFFSET {}", n));
+                }
+            },
+            LimitCap::Top => {
+                // SQL Server 的 FETCH 必须跟在 OFFSET 之后
+                if limit.is_some() || offset.is_some() {
+                    sql.push_str(&format!(" OFFSET {} ROWS", offset.unwrap_or(0)));
+                }
+                if let Some(n) = limit {
+                    sql.push_str(&format!(" FETCH NEXT {} ROWS ONLY", n));
+                }
+            },
+            LimitCap::OffsetFetch => {
+                if let Some(n) = offset {
+                    sql.push_str(&format!(" OFFSET {} ROWS", n));
+                }
+                if let Some(n) = limit {
+                    let next = if offset.is_some() { "NEXT" } else { "FIRST" };
+                    sql.push_str(&format!(" FETCH {} {} ROWS ONLY", next, n));
+                }
+            },
+        }
+        Ok(sql)
+    }
+
+    // 锁子句，包含前导空格；of 为已渲染好的 OF 目标列表
+    fn render_lock(&self, lock: &Lock, of: Option<&str>, wait: &Wait) -> String {
+        let mut sql = match (lock, of) {
+            (Lock::Share, _) => " FOR SHARE".to_string(),
+            (_, Some(of)) => format!(" FOR UPDATE OF {}", of),
+            _ => " FOR UPDATE".to_string(),
+        };
+        match wait {
+            Wait::Default => {},
+            Wait::NoWait => sql.push_str(" NOWAIT"),
+            Wait::SkipLocked => sql.push_str(" SKIP LOCKED"),
+        }
+        sql
+    }
+
+    // 字符串字面量，e.g. 'O''Brien'
+    fn render_string(&self, v: &str) -> String {
+        let v = v.replace('\'', "''");
+        if self.caps().backslash_escape {
+            format!("'{}'", v.replace('\\', "\\\\"))
+        } else {
+            format!("'{}'", v)
+        }
+    }
+
+    // 二进制字面量，e.g. X'0a1b'
+    fn render_binary(&self, v: &[u8]) -> String {
+        format!("X'{}'", hex(v))
+    }
+
+    // 函数名映射，e.g. LENGTH -> LEN
+    fn render_func_name<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        Cow::Borrowed(name)
+    }
+
+    // 加引号后的标识符，名字中的结束引号连写两次转义，e.g. [a]]b]
+    fn render_identifier(&self, name: &str) -> String {
+        let close = self.quote_close_char();
+        let name = if self.caps().upper_case_identifiers {
+            name.to_uppercase()
+        } else {
+            name.to_string()
+        };
+        format!("{}{}{}", self.quote_char(), name.replace(close, &close.repeat(2)), close)
+    }
+}
+
+// e.g. DECIMAL(10, 2)
+pub(crate) fn decimal(name: &str, precision: Option<usize>, scale: Option<usize>) -> String {
+    match (precision, scale) {
+        (Some(p), Some(s)) => format!("{}({}, {})", name, p, s),
+        (Some(p), None) => format!("{}({})", name, p),
+        _ => name.to_string(),
+    }
+}
+
+pub(crate) fn hex(v: &[u8]) -> String {
+    v.iter().map(|byte| format!("{:02x}", byte)).collect()
 }
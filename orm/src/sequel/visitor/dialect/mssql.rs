@@ -0,0 +1,89 @@
+use crate::model::columns::ColumnType;
+use crate::sequel::visitor::dialect::caps::{
+    Capability, CountDistinctCap, DeleteJoinCap, IndexCap, IndexFormat, LimitCap, UpdateJoinCap, ValuesTableCap,
+};
+use crate::sequel::visitor::dialect::{Dialect, PlaceHolderStyle, UnsupportedCast, decimal, hex};
+use std::borrow::Cow;
+
+#[derive(Clone)]
+pub struct MsSql {}
+impl Dialect for MsSql {
+    #[inline]
+    fn caps(&self) -> Capability {
+        Capability {
+            standalone_offset: true,
+            count_distinct: CountDistinctCap::Rewrite,
+            // T-SQL 的 UPDATE / DELETE 不能直接给目标表起别名
+            update_join: UpdateJoinCap::AliasFrom,
+            delete_join: DeleteJoinCap::AliasFrom,
+            parenthesized_set_operand: true,
+            limit: LimitCap::Top,
+            table_hints: true,
+            index_cap: IndexCap {
+                force: Some(IndexFormat {
+                    before: "INDEX(",
+                    after: ")",
+                    support_multiple: true,
+                }),
+                use_: None,
+                ignore: None,
+            },
+            max_params: Some(2100),
+            values_table: ValuesTableCap::ColumnList,
+            ..Capability::default()
+        }
+    }
+    #[inline]
+    fn quote_char(&self) -> &'static str {
+        "["
+    }
+    #[inline]
+    fn quote_close_char(&self) -> &'static str {
+        "]"
+    }
+    #[inline]
+    fn placeholder_style(&self) -> PlaceHolderStyle {
+        PlaceHolderStyle::AtNumbered
+    }
+
+    #[inline]
+    fn bool_str(&self, v: bool) -> &'static str {
+        if v { "1" } else { "0" }
+    }
+
+    fn cast_type(&self, column_type: &ColumnType) -> Result<String, UnsupportedCast> {
+        let name = match column_type {
+            ColumnType::Char { max_length: Some(n) } => format!("NVARCHAR({})", n),
+            ColumnType::Char { max_length: None } | ColumnType::Text | ColumnType::Json => "NVARCHAR(MAX)".to_string(),
+            ColumnType::Int => "INT".to_string(),
+            ColumnType::BigInt => "BIGINT".to_string(),
+            ColumnType::SmallInt => "SMALLINT".to_string(),
+            ColumnType::Float => "REAL".to_string(),
+            ColumnType::Double => "FLOAT".to_string(),
+            ColumnType::Decimal { precision, scale } => decimal("DECIMAL", *precision, *scale),
+            ColumnType::Bool => "BIT".to_string(),
+            ColumnType::Date => "DATE".to_string(),
+            ColumnType::DateTime => "DATETIME2".to_string(),
+            ColumnType::Time => "TIME".to_string(),
+            ColumnType::Uuid => "UNIQUEIDENTIFIER".to_string(),
+            ColumnType::Blob => "VARBINARY(MAX)".to_string(),
+            // SQL Server 没有无符号整数
+            ColumnType::UnsignedInt | ColumnType::UnsignedSmallInt | ColumnType::Auto | ColumnType::ForeignKey => {
+                return Err(UnsupportedCast::new("MsSql", column_type));
+            },
+        };
+        Ok(name)
+    }
+
+    fn render_binary(&self, v: &[u8]) -> String {
+        format!("0x{}", hex(v))
+    }
+
+    fn render_func_name<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        if name.eq_ignore_ascii_case("length") || name.eq_ignore_ascii_case("char_length") {
+            Cow::Borrowed("LEN")
+        } else {
+            Cow::Borrowed(name)
+        }
+    }
+}
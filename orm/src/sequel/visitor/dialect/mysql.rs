@@ -1,6 +1,10 @@
-use crate::sequel::visitor::dialect::caps::{Capability, CountDistinctCap, IndexCap, IndexFormat};
-use crate::sequel::visitor::dialect::{Dialect, PlaceHolderStyle};
+use crate::model::columns::ColumnType;
+use crate::sequel::visitor::dialect::caps::{
+    Capability, CountDistinctCap, DeleteJoinCap, IndexCap, IndexFormat, UpdateJoinCap, UpsertCap,
+};
+use crate::sequel::visitor::dialect::{Dialect, PlaceHolderStyle, UnsupportedCast, decimal};
 
+#[derive(Clone)]
 pub struct MySQL {}
 impl Dialect for MySQL {
     #[inline]
@@ -8,6 +12,13 @@ impl Dialect for MySQL {
         Capability {
             count_distinct: CountDistinctCap::Extend,
             select_with_locking: true,
+            share_lock: true,
+            update_join: UpdateJoinCap::Join,
+            delete_join: DeleteJoinCap::Join,
+            write_with_limit: true,
+            upsert: UpsertCap::OnDuplicateKey,
+            parenthesized_set_operand: true,
+            recursive_keyword: true,
             index_cap: IndexCap {
                 force: Some(IndexFormat {
                     before: "FORCE INDEX (",
@@ -25,6 +36,8 @@ impl Dialect for MySQL {
                     support_multiple: true,
                 }),
             },
+            max_params: Some(65535),
+            backslash_escape: true,
             ..Capability::default()
         }
     }
@@ -41,4 +54,25 @@ impl Dialect for MySQL {
     fn bool_str(&self, v: bool) -> &'static str {
         if v { "1" } else { "0" }
     }
+
+    // MySQL 的 CAST 只接受有限的几种目标类型
+    fn cast_type(&self, column_type: &ColumnType) -> Result<String, UnsupportedCast> {
+        let name = match column_type {
+            ColumnType::Char { max_length: Some(n) } => format!("CHAR({})", n),
+            ColumnType::Char { max_length: None } | ColumnType::Text => "CHAR".to_string(),
+            ColumnType::Int | ColumnType::BigInt | ColumnType::SmallInt | ColumnType::Bool => "SIGNED".to_string(),
+            ColumnType::UnsignedInt | ColumnType::UnsignedSmallInt => "UNSIGNED".to_string(),
+            ColumnType::Float => "FLOAT".to_string(),
+            ColumnType::Double => "DOUBLE".to_string(),
+            ColumnType::Decimal { precision, scale } => decimal("DECIMAL", *precision, *scale),
+            ColumnType::Date => "DATE".to_string(),
+            ColumnType::DateTime => "DATETIME".to_string(),
+            ColumnType::Time => "TIME".to_string(),
+            ColumnType::Json => "JSON".to_string(),
+            ColumnType::Uuid => "CHAR(36)".to_string(),
+            ColumnType::Blob => "BINARY".to_string(),
+            ColumnType::Auto | ColumnType::ForeignKey => return Err(UnsupportedCast::new("MySQL", column_type)),
+        };
+        Ok(name)
+    }
 }
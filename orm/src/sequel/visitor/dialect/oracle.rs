@@ -0,0 +1,72 @@
+use crate::model::columns::ColumnType;
+use crate::sequel::visitor::dialect::caps::{
+    Capability, CountDistinctCap, DeleteJoinCap, LimitCap, LockOfCap, UpdateJoinCap,
+};
+use crate::sequel::visitor::dialect::{Dialect, PlaceHolderStyle, UnsupportedCast, decimal, hex};
+
+#[derive(Clone)]
+pub struct Oracle {}
+impl Dialect for Oracle {
+    #[inline]
+    fn caps(&self) -> Capability {
+        Capability {
+            standalone_offset: true,
+            select_with_locking: true,
+            count_distinct: CountDistinctCap::Rewrite,
+            update_join: UpdateJoinCap::None,
+            delete_join: DeleteJoinCap::Subquery,
+            parenthesized_set_operand: true,
+            limit: LimitCap::OffsetFetch,
+            bare_table_alias: true,
+            upper_case_identifiers: true,
+            recursive_cte_columns: true,
+            lock_of: LockOfCap::Column,
+            max_params: Some(65535),
+            // ORA-01795
+            max_in_list: Some(1000),
+            ..Capability::default()
+        }
+    }
+    #[inline]
+    fn quote_char(&self) -> &'static str {
+        "\""
+    }
+    #[inline]
+    fn placeholder_style(&self) -> PlaceHolderStyle {
+        PlaceHolderStyle::ColonNumbered
+    }
+
+    // Oracle 没有布尔字面量
+    #[inline]
+    fn bool_str(&self, v: bool) -> &'static str {
+        if v { "1" } else { "0" }
+    }
+
+    fn cast_type(&self, column_type: &ColumnType) -> Result<String, UnsupportedCast> {
+        let name = match column_type {
+            ColumnType::Char { max_length: Some(n) } => format!("VARCHAR2({})", n),
+            ColumnType::Char { max_length: None } => "VARCHAR2(4000)".to_string(),
+            ColumnType::Text | ColumnType::Json => "CLOB".to_string(),
+            ColumnType::Int | ColumnType::UnsignedInt => "NUMBER(10)".to_string(),
+            ColumnType::BigInt => "NUMBER(19)".to_string(),
+            ColumnType::SmallInt | ColumnType::UnsignedSmallInt => "NUMBER(5)".to_string(),
+            ColumnType::Float => "BINARY_FLOAT".to_string(),
+            ColumnType::Double => "BINARY_DOUBLE".to_string(),
+            ColumnType::Decimal { precision, scale } => decimal("NUMBER", *precision, *scale),
+            ColumnType::Bool => "NUMBER(1)".to_string(),
+            ColumnType::Date => "DATE".to_string(),
+            ColumnType::DateTime => "TIMESTAMP".to_string(),
+            ColumnType::Uuid => "VARCHAR2(36)".to_string(),
+            ColumnType::Blob => "RAW(2000)".to_string(),
+            // Oracle 没有单独的 TIME 类型
+            ColumnType::Time | ColumnType::Auto | ColumnType::ForeignKey => {
+                return Err(UnsupportedCast::new("Oracle", column_type));
+            },
+        };
+        Ok(name)
+    }
+
+    fn render_binary(&self, v: &[u8]) -> String {
+        format!("HEXTORAW('{}')", hex(v))
+    }
+}
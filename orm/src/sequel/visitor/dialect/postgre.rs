@@ -1,12 +1,19 @@
-use crate::sequel::visitor::dialect::caps::{Capability, CountDistinctCap};
-use crate::sequel::visitor::dialect::{Dialect, PlaceHolderStyle};
+use crate::model::columns::ColumnType;
+use crate::sequel::visitor::dialect::caps::{CastCap, Capability, CountDistinctCap, ValuesTableCap};
+use crate::sequel::visitor::dialect::{Dialect, PlaceHolderStyle, UnsupportedCast, decimal, hex};
 
+#[derive(Clone)]
 pub struct PostgreSQL {}
 impl Dialect for PostgreSQL {
     #[inline]
     fn caps(&self) -> Capability {
         Capability {
             count_distinct: CountDistinctCap::Merge,
+            write_with_limit: false,
+            cast: CastCap::DoubleColon,
+            max_params: Some(65535),
+            array_binding: true,
+            values_table: ValuesTableCap::ColumnList,
             ..Capability::all()
         }
     }
@@ -23,4 +30,35 @@ impl Dialect for PostgreSQL {
     fn bool_str(&self, v: bool) -> &'static str {
         if v { "true" } else { "false" }
     }
+
+    fn cast_type(&self, column_type: &ColumnType) -> Result<String, UnsupportedCast> {
+        let name = match column_type {
+            ColumnType::Char { max_length: Some(n) } => format!("VARCHAR({})", n),
+            ColumnType::Char { max_length: None } => "VARCHAR".to_string(),
+            ColumnType::Text => "TEXT".to_string(),
+            ColumnType::Int => "INTEGER".to_string(),
+            ColumnType::BigInt => "BIGINT".to_string(),
+            ColumnType::SmallInt => "SMALLINT".to_string(),
+            ColumnType::Float => "REAL".to_string(),
+            ColumnType::Double => "DOUBLE PRECISION".to_string(),
+            ColumnType::Decimal { precision, scale } => decimal("NUMERIC", *precision, *scale),
+            ColumnType::Bool => "BOOLEAN".to_string(),
+            ColumnType::Date => "DATE".to_string(),
+            ColumnType::DateTime => "TIMESTAMP".to_string(),
+            ColumnType::Time => "TIME".to_string(),
+            ColumnType::Json => "JSONB".to_string(),
+            ColumnType::Uuid => "UUID".to_string(),
+            ColumnType::Blob => "BYTEA".to_string(),
+            // PostgreSQL 没有无符号整数
+            ColumnType::UnsignedInt | ColumnType::UnsignedSmallInt | ColumnType::Auto | ColumnType::ForeignKey => {
+                return Err(UnsupportedCast::new("PostgreSQL", column_type));
+            },
+        };
+        Ok(name)
+    }
+
+    // bytea 的十六进制格式
+    fn render_binary(&self, v: &[u8]) -> String {
+        format!("'\\x{}'::bytea", hex(v))
+    }
 }
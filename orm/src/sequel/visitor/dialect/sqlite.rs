@@ -1,6 +1,10 @@
-use crate::sequel::visitor::dialect::caps::{Capability, CountDistinctCap, IndexCap, IndexFormat};
-use crate::sequel::visitor::dialect::{Dialect, PlaceHolderStyle};
+use crate::model::columns::ColumnType;
+use crate::sequel::visitor::dialect::caps::{
+    Capability, CountDistinctCap, DeleteJoinCap, IndexCap, IndexFormat, UpsertCap, ValuesTableCap,
+};
+use crate::sequel::visitor::dialect::{Dialect, PlaceHolderStyle, UnsupportedCast};
 
+#[derive(Clone)]
 pub struct SQLite {}
 impl Dialect for SQLite {
     #[inline]
@@ -9,6 +13,11 @@ impl Dialect for SQLite {
             returning: true,
             standalone_offset: true,
             count_distinct: CountDistinctCap::Rewrite,
+            delete_join: DeleteJoinCap::Subquery,
+            upsert: UpsertCap::OnConflict,
+            cte_materialized: true,
+            recursive_keyword: true,
+            groups_frame: true,
             index_cap: IndexCap {
                 force: Some(IndexFormat {
                     before: "INDEXED BY",
@@ -22,6 +31,8 @@ impl Dialect for SQLite {
                     support_multiple: false,
                 }),
             },
+            max_params: Some(32766),
+            values_table: ValuesTableCap::SelectColumns,
             ..Capability::default()
         }
     }
@@ -38,4 +49,23 @@ impl Dialect for SQLite {
     fn bool_str(&self, v: bool) -> &'static str {
         if v { "1" } else { "0" }
     }
+
+    // SQLite 只有存储类：INTEGER、REAL、TEXT、BLOB、NUMERIC
+    fn cast_type(&self, column_type: &ColumnType) -> Result<String, UnsupportedCast> {
+        let name = match column_type {
+            ColumnType::Char { .. } | ColumnType::Text | ColumnType::Json | ColumnType::Uuid => "TEXT",
+            ColumnType::Date | ColumnType::DateTime | ColumnType::Time => "TEXT",
+            ColumnType::Int
+            | ColumnType::BigInt
+            | ColumnType::SmallInt
+            | ColumnType::UnsignedInt
+            | ColumnType::UnsignedSmallInt
+            | ColumnType::Bool => "INTEGER",
+            ColumnType::Float | ColumnType::Double => "REAL",
+            ColumnType::Decimal { .. } => "NUMERIC",
+            ColumnType::Blob => "BLOB",
+            ColumnType::Auto | ColumnType::ForeignKey => return Err(UnsupportedCast::new("SQLite", column_type)),
+        };
+        Ok(name.to_string())
+    }
 }
@@ -0,0 +1,39 @@
+use crate::sequel::visitor::dialect::UnsupportedCast;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum RenderError {
+    // 方言不支持的子句，宽松模式下会被丢弃
+    #[error("RenderError: {clause} is not supported, {reason}")]
+    Unsupported { clause: &'static str, reason: &'static str },
+
+    #[error("RenderError: {0}")]
+    Cast(#[from] UnsupportedCast),
+
+    // 语句本身不完整或自相矛盾，任何方言都无法渲染
+    #[error("RenderError: {clause} is invalid, {reason}")]
+    Invalid { clause: &'static str, reason: &'static str },
+
+    // 同名参数在一条语句中取值不同
+    #[error("RenderError: named parameter {0} is bound to different values")]
+    ConflictingParam(String),
+}
+
+impl RenderError {
+    pub fn unsupported(clause: &'static str, reason: &'static str) -> Self {
+        Self::Unsupported { clause, reason }
+    }
+
+    pub fn invalid(clause: &'static str, reason: &'static str) -> Self {
+        Self::Invalid { clause, reason }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RenderMode {
+    // 丢弃方言不支持的子句，保持现有行为
+    #[default]
+    Lenient,
+    // 方言不支持的子句一律报错
+    Strict,
+}
@@ -0,0 +1,119 @@
+use crate::sequel::parser::lexer::{Token, TokenKind, tokenize};
+use crate::sequel::visitor::dialect::Dialect;
+use std::collections::HashMap;
+
+// 查询结构的指纹，字面量取值、IN 列表长度、自动生成的别名不同的查询指纹相同
+//   let fp = mysql().fingerprint(&stmt);
+//   metrics.entry(fp.hash).or_default().push(elapsed);
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fingerprint {
+    pub hash: u64,
+    pub sql: String,
+}
+
+impl Fingerprint {
+    pub fn new(sql: String) -> Self {
+        Self { hash: fnv1a(&sql), sql }
+    }
+}
+
+// 64 位 FNV-1a，不依赖标准库 Hasher 的实现，跨进程、跨版本稳定
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf29ce484222325, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+enum Piece<'a> {
+    Literal,
+    Text(&'a str),
+}
+
+// aliases 为渲染后的自动别名到表名的映射，e.g. `users0` -> `users`
+pub(crate) fn normalize(sql: &str, dialect: &impl Dialect, aliases: &HashMap<String, String>) -> String {
+    let Ok(tokens) = tokenize(sql, dialect) else {
+        return sql.to_string();
+    };
+    let pieces = pieces(sql, &tokens, aliases);
+
+    let style = dialect.placeholder_style();
+    let mut out = String::with_capacity(sql.len());
+    let mut n = 0;
+    let mut i = 0;
+    while i < pieces.len() {
+        let (piece, space) = &pieces[i];
+        if *space && !out.is_empty() {
+            out.push(' ');
+        }
+        match piece {
+            Piece::Literal => {
+                n += 1;
+                out.push_str(&style.render(n));
+            },
+            Piece::Text(text) => {
+                out.push_str(text);
+                // IN (1, 2, 3) 与 IN (1) 折叠为同一形式
+                if text.eq_ignore_ascii_case("IN")
+                    && let Some(end) = literal_list_end(&pieces, i + 1)
+                {
+                    out.push_str(" (...)");
+                    i = end;
+                }
+            },
+        }
+        i += 1;
+    }
+    out
+}
+
+fn pieces<'a>(sql: &'a str, tokens: &[Token], aliases: &'a HashMap<String, String>) -> Vec<(Piece<'a>, bool)> {
+    let mut pieces = Vec::with_capacity(tokens.len());
+    let mut prev_end = 0;
+    let mut i = 0;
+    while tokens[i].kind != TokenKind::Eof {
+        let token = &tokens[i];
+        let text = sql[token.offset..tokens[i + 1].offset].trim_end();
+        let space = token.offset > prev_end;
+        prev_end = token.offset + text.len();
+        let piece = match &token.kind {
+            // 紧跟数字的负号属于字面量，e.g. -5
+            TokenKind::Symbol("-")
+                if matches!(tokens[i + 1].kind, TokenKind::Number(_)) && tokens[i + 1].offset == prev_end =>
+            {
+                i += 1;
+                prev_end = tokens[i].offset + sql[tokens[i].offset..tokens[i + 1].offset].trim_end().len();
+                Piece::Literal
+            },
+            TokenKind::Number(_)
+            | TokenKind::String(_)
+            | TokenKind::Hex(_)
+            | TokenKind::Placeholder(_)
+            | TokenKind::NamedPlaceholder(_) => Piece::Literal,
+            TokenKind::Word(w) if w.eq_ignore_ascii_case("TRUE") || w.eq_ignore_ascii_case("FALSE") => Piece::Literal,
+            TokenKind::Quoted(_) => match aliases.get(text) {
+                Some(name) => Piece::Text(name.as_str()),
+                None => Piece::Text(text),
+            },
+            _ => Piece::Text(text),
+        };
+        pieces.push((piece, space));
+        i += 1;
+    }
+    pieces
+}
+
+// ( 字面量, 字面量, ... ) 的右括号位置
+fn literal_list_end(pieces: &[(Piece, bool)], start: usize) -> Option<usize> {
+    if !matches!(pieces.get(start), Some((Piece::Text("("), _))) {
+        return None;
+    }
+    let mut i = start + 1;
+    loop {
+        if !matches!(pieces.get(i), Some((Piece::Literal, _))) {
+            return None;
+        }
+        match pieces.get(i + 1) {
+            Some((Piece::Text(","), _)) => i += 2,
+            Some((Piece::Text(")"), _)) => return Some(i + 1),
+            _ => return None,
+        }
+    }
+}
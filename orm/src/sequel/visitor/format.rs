@@ -0,0 +1,176 @@
+use crate::sequel::parser::lexer::{Token, TokenKind, tokenize};
+use crate::sequel::visitor::dialect::Dialect;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeywordCase {
+    #[default]
+    Upper,
+    Lower,
+}
+
+// 美化输出只用于日志和排查问题，执行时仍然使用紧凑格式
+//   let visitor = postgre().pretty(Format { indent: 2, ..Format::default() });
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Format {
+    pub indent: usize,
+    pub keyword_case: KeywordCase,
+    pub one_item_per_line: bool,
+    pub indent_subqueries: bool,
+    pub indent_joins: bool,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self {
+            indent: 4,
+            keyword_case: KeywordCase::Upper,
+            one_item_per_line: true,
+            indent_subqueries: true,
+            indent_joins: true,
+        }
+    }
+}
+
+// 另起一行的子句
+const CLAUSES: [&str; 19] = [
+    "SELECT", "FROM", "WHERE", "GROUP", "HAVING", "WINDOW", "QUALIFY", "ORDER", "LIMIT", "OFFSET", "FETCH", "FOR",
+    "UNION", "INTERSECT", "EXCEPT", "SETTINGS", "VALUES", "SET", "RETURNING",
+];
+const JOINS: [&str; 5] = ["INNER", "LEFT", "RIGHT", "FULL", "CROSS"];
+// 只转换这些关键字的大小写，函数名可能区分大小写，e.g. ClickHouse 的 toStartOfMonth
+const KEYWORDS: [&str; 75] = [
+    "SELECT", "FROM", "WHERE", "GROUP", "HAVING", "WINDOW", "QUALIFY", "ORDER", "LIMIT", "OFFSET", "FETCH", "FOR",
+    "UNION", "INTERSECT", "EXCEPT", "SETTINGS", "VALUES", "SET", "RETURNING", "INSERT", "INTO", "UPDATE", "DELETE",
+    "USING", "JOIN", "INNER", "LEFT", "RIGHT", "FULL", "OUTER", "CROSS", "ON", "AS", "AND", "OR", "NOT", "IN", "IS",
+    "NULL", "LIKE", "BETWEEN", "EXISTS", "CASE", "WHEN", "THEN", "ELSE", "END", "DISTINCT", "ALL", "BY", "ASC",
+    "DESC", "WITH", "RECURSIVE", "MATERIALIZED", "TOP", "OVER", "PARTITION", "ROWS", "RANGE", "GROUPS", "CAST",
+    "FIRST", "NEXT", "ONLY", "SHARE", "OF", "NOWAIT", "SKIP", "LOCKED", "CONFLICT", "DO", "NOTHING", "SAMPLE",
+    "FINAL",
+];
+
+// 一层语句或一层括号
+struct Level {
+    // 子查询内的子句按 indent 另起一行，普通括号内不换行
+    statement: bool,
+    indent: usize,
+    // 括号闭合时回到的缩进
+    close_indent: usize,
+    in_select_list: bool,
+    // SELECT 之后第一个列另起一行
+    item_pending: bool,
+}
+
+// 只调整空白和关键字大小写，token 本身原样输出，因此与紧凑格式语义相同
+pub fn pretty(sql: &str, dialect: &impl Dialect, format: &Format) -> String {
+    let Ok(tokens) = tokenize(sql, dialect) else {
+        return sql.to_string();
+    };
+    let mut out = String::with_capacity(sql.len() * 2);
+    let mut line_indent = 0;
+    let mut levels = vec![Level {
+        statement: true,
+        indent: 0,
+        close_indent: 0,
+        in_select_list: false,
+        item_pending: false,
+    }];
+    let mut break_at: Option<usize> = None;
+    let mut prev_end = 0;
+    let mut prev_word = String::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind == TokenKind::Eof {
+            break;
+        }
+        let text = text_of(sql, &tokens, i);
+        let word = match &token.kind {
+            TokenKind::Word(w) => w.to_uppercase(),
+            _ => String::new(),
+        };
+        let level = levels.last_mut().unwrap();
+
+        if level.statement {
+            if CLAUSES.contains(&word.as_str()) {
+                break_at = Some(level.indent);
+                level.in_select_list = word == "SELECT";
+                level.item_pending = level.in_select_list && format.one_item_per_line;
+            } else if is_join(&tokens, i, &prev_word) {
+                break_at = Some(level.indent + format.indent_joins as usize);
+            } else if level.item_pending && !is_select_modifier(&word, &prev_word, &token.kind) {
+                level.item_pending = false;
+                break_at = Some(level.indent + 1);
+            }
+        }
+        if token.kind == TokenKind::Symbol(")") && levels.len() > 1 {
+            let closed = levels.pop().unwrap();
+            if closed.statement {
+                break_at = Some(closed.close_indent);
+            }
+        }
+
+        match break_at.take() {
+            Some(indent) if !out.is_empty() => {
+                out.push('\n');
+                out.push_str(&" ".repeat(indent * format.indent));
+                line_indent = indent;
+            },
+            _ if token.offset > prev_end && !out.is_empty() => out.push(' '),
+            _ => {},
+        }
+        match (&token.kind, format.keyword_case) {
+            (TokenKind::Word(_), KeywordCase::Lower) if KEYWORDS.contains(&word.as_str()) => {
+                out.push_str(&text.to_lowercase())
+            },
+            _ => out.push_str(text),
+        }
+
+        let level = levels.last_mut().unwrap();
+        match token.kind {
+            TokenKind::Symbol(",") if level.statement && level.in_select_list && format.one_item_per_line => {
+                break_at = Some(level.indent + 1);
+            },
+            TokenKind::Symbol("(") => {
+                let subquery = format.indent_subqueries && starts_statement(&tokens, i + 1);
+                levels.push(Level {
+                    statement: subquery,
+                    indent: line_indent + 1,
+                    close_indent: line_indent,
+                    in_select_list: false,
+                    item_pending: false,
+                });
+            },
+            _ => {},
+        }
+        prev_end = token.offset + text.len();
+        prev_word = word;
+    }
+    out
+}
+
+// token 原文，不含之后的空白
+fn text_of<'a>(sql: &'a str, tokens: &[Token], i: usize) -> &'a str {
+    sql[tokens[i].offset..tokens[i + 1].offset].trim_end()
+}
+
+fn starts_statement(tokens: &[Token], i: usize) -> bool {
+    matches!(&tokens[i].kind, TokenKind::Word(w) if w.eq_ignore_ascii_case("SELECT") || w.eq_ignore_ascii_case("WITH"))
+}
+
+// INNER JOIN、LEFT OUTER JOIN 或者单独的 JOIN，LEFT(...) 这样的函数不算
+fn is_join(tokens: &[Token], i: usize, prev_word: &str) -> bool {
+    let is_word = |i: usize, w: &str| matches!(&tokens[i].kind, TokenKind::Word(t) if t.eq_ignore_ascii_case(w));
+    if is_word(i, "JOIN") {
+        return !JOINS.contains(&prev_word) && prev_word != "OUTER";
+    }
+    JOINS.iter().any(|j| is_word(i, j)) && (is_word(i + 1, "JOIN") || is_word(i + 1, "OUTER"))
+}
+
+// SELECT DISTINCT、DISTINCT ON (...)、TOP n 留在 SELECT 所在的行
+fn is_select_modifier(word: &str, prev_word: &str, kind: &TokenKind) -> bool {
+    match kind {
+        TokenKind::Word(_) => word == "DISTINCT" || word == "TOP" || (word == "ON" && prev_word == "DISTINCT"),
+        TokenKind::Number(_) => prev_word == "TOP",
+        TokenKind::Symbol("(") => prev_word == "ON",
+        _ => false,
+    }
+}
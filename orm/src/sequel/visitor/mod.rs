@@ -1,5 +1,12 @@
 mod alias_cache;
 pub mod builder;
 pub mod dialect;
+pub mod error;
+pub mod fingerprint;
+pub mod format;
+pub mod pass;
+pub mod render_cache;
 mod rewriter;
 pub mod visitor;
+
+pub use rewriter::{InListSplit, InListStrategy, split_in_list};
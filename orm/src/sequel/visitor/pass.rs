@@ -0,0 +1,403 @@
+use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::term::expr::Expr;
+use crate::sequel::term::func::Func;
+use crate::sequel::term::ops::{BinaryOp, UnaryOp};
+use crate::sequel::term::param::{Param, ParamData, lit};
+use crate::sequel::term::table::{Table, TableInner};
+use crate::sequel::traverse::visit::{Visit, visit_func, visit_select_statement};
+use crate::sequel::traverse::visit_mut::{VisitMut, visit_expr_mut, visit_select_statement_mut, visit_table_mut};
+use crate::sequel::visitor::dialect::Dialect;
+use crate::sequel::visitor::render_cache::Shape;
+use crate::sequel::visitor::rewriter::{needs_count_distinct_rewrite, normalize, rewrite_count_distinct};
+use std::sync::Arc;
+
+// 渲染前对语句的改写，按注册顺序作用于最外层语句，子查询需要自行遍历
+// INSERT/UPDATE/DELETE 中的每个子查询各自作为最外层语句改写
+// 闭包可以直接作为改写注册，e.g. 为每条语句加上租户条件
+//   postgre().pass(|stmt: &mut SelectStatement, _: &dyn Dialect| {
+//       stmt.where_clause.push(USERS.column("tenant_id").eq(42));
+//   })
+pub trait Pass: Send + Sync {
+    // 所有改写都不适用时直接渲染原语句，不会复制
+    fn applies(&self, _stmt: &SelectStatement, _dialect: &dyn Dialect) -> bool {
+        true
+    }
+
+    fn run(&self, stmt: &mut SelectStatement, dialect: &dyn Dialect);
+}
+
+impl<F> Pass for F
+where
+    F: Fn(&mut SelectStatement, &dyn Dialect) + Send + Sync,
+{
+    fn run(&self, stmt: &mut SelectStatement, dialect: &dyn Dialect) {
+        self(stmt, dialect)
+    }
+}
+
+// 方言不支持多列 COUNT(DISTINCT a, b) 时改写为子查询，默认启用
+pub struct CountDistinct;
+
+impl Pass for CountDistinct {
+    fn applies(&self, stmt: &SelectStatement, dialect: &dyn Dialect) -> bool {
+        any_statement(stmt, |s| needs_count_distinct_rewrite(s, dialect))
+    }
+
+    fn run(&self, stmt: &mut SelectStatement, dialect: &dyn Dialect) {
+        struct Rewrite<'d>(&'d dyn Dialect);
+        impl VisitMut for Rewrite<'_> {
+            fn visit_select_statement_mut(&mut self, stmt: &mut SelectStatement) {
+                visit_select_statement_mut(self, stmt);
+                if needs_count_distinct_rewrite(stmt, self.0) {
+                    *stmt = rewrite_count_distinct(stmt, self.0);
+                }
+            }
+        }
+        Rewrite(dialect).visit_select_statement_mut(stmt);
+    }
+}
+
+// COUNT() 补全为 COUNT(*)
+pub struct CountWildcard;
+
+impl Pass for CountWildcard {
+    fn applies(&self, stmt: &SelectStatement, _dialect: &dyn Dialect) -> bool {
+        struct Empty(bool);
+        impl Visit<'_> for Empty {
+            fn visit_func(&mut self, func: &Func) {
+                self.0 |= func.name.eq_ignore_ascii_case("count") && func.args.is_empty();
+                visit_func(self, func);
+            }
+        }
+        let mut empty = Empty(false);
+        empty.visit_select_statement(stmt);
+        empty.0
+    }
+
+    fn run(&self, stmt: &mut SelectStatement, _dialect: &dyn Dialect) {
+        normalize(stmt);
+    }
+}
+
+// 两侧都是字面量的算术运算直接求值，e.g. 1 + 2 => 3
+// 溢出、除零、整数除不尽和浮点数运算保留原样，各数据库的结果不一致
+pub struct FoldConstants;
+
+impl Pass for FoldConstants {
+    fn run(&self, stmt: &mut SelectStatement, _dialect: &dyn Dialect) {
+        struct Fold;
+        impl VisitMut for Fold {
+            fn visit_expr_mut(&mut self, expr: &mut Expr) {
+                visit_expr_mut(self, expr);
+                if let Expr::Binary { left, op, right } = expr
+                    && let (Expr::Param(Param::Literal(l)), Expr::Param(Param::Literal(r))) = (&**left, &**right)
+                    && let Some(data) = arithmetic(*op, l, r)
+                {
+                    *expr = Expr::Param(Param::Literal(data));
+                }
+            }
+        }
+        Fold.visit_select_statement_mut(stmt);
+    }
+}
+
+// IN () 恒为假，NOT IN () 恒为真
+pub struct EmptyIn;
+
+impl Pass for EmptyIn {
+    fn run(&self, stmt: &mut SelectStatement, _dialect: &dyn Dialect) {
+        struct Collapse;
+        impl VisitMut for Collapse {
+            fn visit_expr_mut(&mut self, expr: &mut Expr) {
+                visit_expr_mut(self, expr);
+                if let Expr::In { list, negated, .. } = expr
+                    && list.is_empty()
+                {
+                    *expr = always(*negated);
+                }
+            }
+        }
+        Collapse.visit_select_statement_mut(stmt);
+    }
+}
+
+// 以下改写只作用于 WHERE、HAVING、QUALIFY 和 JOIN ON 中的条件
+// MySQL 等把布尔值当作整数，SELECT NOT NOT 5 的结果是 1，不能在条件以外化简
+
+// NOT NOT x => x
+pub struct DoubleNegation;
+
+impl Pass for DoubleNegation {
+    fn run(&self, stmt: &mut SelectStatement, _dialect: &dyn Dialect) {
+        predicates(stmt, double_negation, |_| {});
+    }
+}
+
+// a AND (b AND c) => a AND b AND c，顶层的 AND 拆为多个条件
+pub struct FlattenLogic;
+
+impl Pass for FlattenLogic {
+    fn run(&self, stmt: &mut SelectStatement, _dialect: &dyn Dialect) {
+        predicates(stmt, flatten, |list| {
+            *list = std::mem::take(list)
+                .into_iter()
+                .flat_map(|e| {
+                    let mut conjuncts = Vec::new();
+                    operands(e, BinaryOp::And, &mut conjuncts);
+                    conjuncts
+                })
+                .collect();
+        });
+    }
+}
+
+// TRUE AND x => x，FALSE OR x => x，恒为真的条件直接去掉
+pub struct DropTrue;
+
+impl Pass for DropTrue {
+    fn run(&self, stmt: &mut SelectStatement, _dialect: &dyn Dialect) {
+        predicates(stmt, drop_identity, |list| list.retain(|e| truth(e) != Some(true)));
+    }
+}
+
+// 去掉结构和取值都相同的重复条件，保留第一次出现的
+pub struct DedupeFilters;
+
+impl Pass for DedupeFilters {
+    fn run(&self, stmt: &mut SelectStatement, _dialect: &dyn Dialect) {
+        predicates(stmt, |_| {}, dedupe);
+    }
+}
+
+// 在所有语句中查找，包括子查询
+fn any_statement(stmt: &SelectStatement, pred: impl Fn(&SelectStatement) -> bool) -> bool {
+    struct Find<P>(P, bool);
+    impl<P: Fn(&SelectStatement) -> bool> Visit<'_> for Find<P> {
+        fn visit_select_statement(&mut self, stmt: &SelectStatement) {
+            self.1 |= (self.0)(stmt);
+            visit_select_statement(self, stmt);
+        }
+    }
+    let mut find = Find(pred, false);
+    find.visit_select_statement(stmt);
+    find.1
+}
+
+// 自底向上改写每个条件中的节点，再整体处理条件列表；JOIN ON 只有一个条件，只改写节点
+fn predicates(stmt: &mut SelectStatement, node: impl FnMut(&mut Expr), list: impl FnMut(&mut Vec<Expr>)) {
+    struct Predicates<N, L> {
+        node: N,
+        list: L,
+    }
+    impl<N: FnMut(&mut Expr), L: FnMut(&mut Vec<Expr>)> Predicates<N, L> {
+        fn rewrite(&mut self, expr: &mut Expr) {
+            Nodes(&mut self.node).visit_expr_mut(expr);
+        }
+
+        fn rewrite_list(&mut self, exprs: &mut Vec<Expr>) {
+            exprs.iter_mut().for_each(|e| self.rewrite(e));
+            (self.list)(exprs);
+        }
+    }
+    impl<N: FnMut(&mut Expr), L: FnMut(&mut Vec<Expr>)> VisitMut for Predicates<N, L> {
+        fn visit_select_statement_mut(&mut self, stmt: &mut SelectStatement) {
+            visit_select_statement_mut(self, stmt);
+            self.rewrite_list(&mut stmt.where_clause);
+            self.rewrite_list(&mut stmt.having_clause);
+            self.rewrite_list(&mut stmt.qualify_clause);
+        }
+
+        fn visit_table_mut(&mut self, table: &mut Table) {
+            visit_table_mut(self, table);
+            if matches!(table.inner.as_ref(), TableInner::Join(_))
+                && let TableInner::Join(join) = Arc::make_mut(&mut table.inner)
+                && let Some(on) = &mut join.on
+            {
+                self.rewrite(on);
+            }
+        }
+    }
+
+    // 子查询是单独的语句，由外层的遍历处理
+    struct Nodes<'f, N>(&'f mut N);
+    impl<N: FnMut(&mut Expr)> VisitMut for Nodes<'_, N> {
+        fn visit_select_statement_mut(&mut self, _: &mut SelectStatement) {}
+
+        fn visit_expr_mut(&mut self, expr: &mut Expr) {
+            visit_expr_mut(self, expr);
+            (self.0)(expr);
+        }
+    }
+
+    Predicates { node, list }.visit_select_statement_mut(stmt);
+}
+
+fn arithmetic(op: BinaryOp, l: &ParamData, r: &ParamData) -> Option<ParamData> {
+    match (l, r) {
+        (ParamData::Int(a), ParamData::Int(b)) => match op {
+            BinaryOp::Add => a.checked_add(*b),
+            BinaryOp::Sub => a.checked_sub(*b),
+            BinaryOp::Mul => a.checked_mul(*b),
+            // MySQL 的 / 返回小数，只折叠整除的
+            BinaryOp::Div if *b != 0 && a % b == 0 => a.checked_div(*b),
+            BinaryOp::Rem if *b != 0 => a.checked_rem(*b),
+            _ => None,
+        }
+        .map(ParamData::Int),
+        // 0.1 + 0.2 在 PostgreSQL、MySQL 中按精确的 numeric 计算，浮点数不折叠
+        // 除法结果的精度各数据库不同，不折叠
+        (ParamData::Decimal(a), ParamData::Decimal(b)) => match op {
+            BinaryOp::Add => a.checked_add(*b),
+            BinaryOp::Sub => a.checked_sub(*b),
+            BinaryOp::Mul => a.checked_mul(*b),
+            _ => None,
+        }
+        .map(ParamData::Decimal),
+        _ => None,
+    }
+}
+
+// 恒为真时是 1 = 1，恒为假时是 1 = 0；单独的布尔参数在条件中会渲染为 p = p
+fn always(value: bool) -> Expr {
+    Expr::Binary {
+        left: Box::new(Expr::Param(lit(1))),
+        op: BinaryOp::Eq,
+        right: Box::new(Expr::Param(lit(value as i64))),
+    }
+}
+
+// 布尔值或整数字面量的比较，结果与数据库无关
+fn truth(expr: &Expr) -> Option<bool> {
+    match expr {
+        Expr::Param(Param::Literal(ParamData::Bool(b)) | Param::Value(ParamData::Bool(b))) => Some(*b),
+        Expr::Binary {
+            left,
+            op: BinaryOp::Eq,
+            right,
+        } => match (&**left, &**right) {
+            (Expr::Param(Param::Literal(ParamData::Int(l))), Expr::Param(Param::Literal(ParamData::Int(r)))) => {
+                Some(l == r)
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn take(expr: &mut Expr) -> Expr {
+    std::mem::replace(expr, Expr::Param(Param::Null))
+}
+
+fn double_negation(expr: &mut Expr) {
+    if let Expr::Unary {
+        op: UnaryOp::Not,
+        expr: inner,
+    } = expr
+        && let Expr::Unary {
+            op: UnaryOp::Not,
+            expr: x,
+        } = inner.as_mut()
+    {
+        *expr = take(x);
+    }
+}
+
+fn flatten(expr: &mut Expr) {
+    let Expr::Binary {
+        op: op @ (BinaryOp::And | BinaryOp::Or),
+        ..
+    } = expr
+    else {
+        return;
+    };
+    let op = *op;
+    let mut exprs = Vec::new();
+    operands(take(expr), op, &mut exprs);
+    if let Some(flat) = exprs.into_iter().reduce(|left, right| Expr::Binary {
+        left: Box::new(left),
+        op,
+        right: Box::new(right),
+    }) {
+        *expr = flat;
+    }
+}
+
+// 按 op 连接的各个操作数，e.g. (a AND b) AND c => [a, b, c]
+fn operands(expr: Expr, op: BinaryOp, out: &mut Vec<Expr>) {
+    match expr {
+        Expr::Binary { left, op: o, right } if o == op => {
+            operands(*left, op, out);
+            operands(*right, op, out);
+        },
+        expr => out.push(expr),
+    }
+}
+
+fn drop_identity(expr: &mut Expr) {
+    let Expr::Binary { left, op, right } = expr else {
+        return;
+    };
+    // AND 的单位元是 TRUE，OR 的单位元是 FALSE
+    let identity = match op {
+        BinaryOp::And => true,
+        BinaryOp::Or => false,
+        _ => return,
+    };
+    if truth(left) == Some(identity) {
+        *expr = take(right);
+    } else if truth(right) == Some(identity) {
+        *expr = take(left);
+    }
+}
+
+const VOLATILE: [&str; 12] = [
+    "RANDOM",
+    "RAND",
+    "NOW",
+    "UUID",
+    "GEN_RANDOM_UUID",
+    "UUID_GENERATE_V4",
+    "NEWID",
+    "SYS_GUID",
+    "NEXTVAL",
+    "RANDOMBLOB",
+    "CLOCK_TIMESTAMP",
+    "SYSDATETIME",
+];
+
+fn is_volatile(expr: &Expr) -> bool {
+    struct Volatile(bool);
+    impl Visit<'_> for Volatile {
+        fn visit_func(&mut self, func: &Func) {
+            self.0 |= VOLATILE.iter().any(|name| func.name.eq_ignore_ascii_case(name));
+            visit_func(self, func);
+        }
+    }
+    let mut volatile = Volatile(false);
+    volatile.visit_expr(expr);
+    volatile.0
+}
+
+fn dedupe(exprs: &mut Vec<Expr>) {
+    let keep: Vec<bool> = {
+        let mut shape = Shape::new();
+        let mut seen = Vec::new();
+        exprs
+            .iter()
+            .map(|e| {
+                // 每次求值结果不同，两个 RANDOM() < 0.5 不是重复的条件
+                if is_volatile(e) {
+                    return true;
+                }
+                let key = shape.expr_of(e);
+                let first = !seen.contains(&key);
+                if first {
+                    seen.push(key);
+                }
+                first
+            })
+            .collect()
+    };
+    let mut keep = keep.into_iter();
+    exprs.retain(|_| keep.next().unwrap_or(true));
+}
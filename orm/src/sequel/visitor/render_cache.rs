@@ -0,0 +1,408 @@
+use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::term::column::Column;
+use crate::sequel::term::distinct::Distinct;
+use crate::sequel::term::expr::Expr;
+use crate::sequel::term::func::FuncArg;
+use crate::sequel::term::lock::Lock;
+use crate::sequel::term::param::{Param, ParamData};
+use crate::sequel::term::table::{Table, TableInner};
+use crate::sequel::term::window::Window;
+use crate::sequel::visitor::builder::ParamMap;
+use crate::sequel::visitor::dialect::Dialect;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Write;
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock};
+
+// 按语句结构缓存渲染结果，命中时只收集参数
+// 每种方言（包括派生方言的每种配置）各用一个缓存
+// 最多保存 capacity 种结构，超出时淘汰最早写入的
+//   static CACHE: LazyLock<RenderCache<PostgreSQL>> = LazyLock::new(RenderCache::new);
+//   let (sql, params, _) = postgre().visit_select_statement_cached(&stmt, &CACHE).finish();
+pub struct RenderCache<D: Dialect> {
+    entries: RwLock<Entries>,
+    capacity: usize,
+    dialect: PhantomData<fn() -> D>,
+}
+
+#[derive(Default)]
+struct Entries {
+    map: HashMap<String, Arc<Entry>>,
+    // 写入顺序
+    order: VecDeque<String>,
+}
+
+pub(crate) struct Entry {
+    pub sql: String,
+    // 第 i 个占位符的值是结构遍历顺序中的第 plan[i] 个参数
+    pub plan: Vec<usize>,
+    pub names: ParamMap,
+    pub slot_names: Vec<Option<String>>,
+}
+
+impl<D: Dialect> RenderCache<D> {
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    pub fn new() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: RwLock::new(Entries::default()),
+            capacity,
+            dialect: PhantomData,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap().map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        let mut entries = self.entries.write().unwrap();
+        entries.map.clear();
+        entries.order.clear();
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<Arc<Entry>> {
+        self.entries.read().unwrap().map.get(key).cloned()
+    }
+
+    pub(crate) fn insert(&self, key: String, entry: Entry) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.write().unwrap();
+        if entries.map.insert(key.clone(), Arc::new(entry)).is_some() {
+            return;
+        }
+        entries.order.push_back(key);
+        while entries.order.len() > self.capacity {
+            if let Some(oldest) = entries.order.pop_front() {
+                entries.map.remove(&oldest);
+            }
+        }
+    }
+}
+
+impl<D: Dialect> Default for RenderCache<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 语句的结构：除 Param::Value 的取值以外，影响渲染结果的内容都编码进 key
+// 表按 Arc 第一次出现的顺序编号，同一张表的多次引用与不同表的区别会影响别名
+pub(crate) struct Shape<'a> {
+    pub key: String,
+    pub values: Vec<&'a ParamData>,
+    // 未命名参数在 values 中的位置
+    unnamed: Vec<usize>,
+    named: Vec<(&'a str, usize)>,
+    tables: HashMap<usize, usize>,
+    expanded: HashSet<usize>,
+}
+
+impl<'a> Shape<'a> {
+    pub fn new() -> Self {
+        Self {
+            key: String::with_capacity(256),
+            values: Vec::new(),
+            unnamed: Vec::new(),
+            named: Vec::new(),
+            tables: HashMap::new(),
+            expanded: HashSet::new(),
+        }
+    }
+
+    pub fn of(stmt: &'a SelectStatement) -> Self {
+        let mut shape = Self::new();
+        shape.stmt(stmt);
+        shape
+    }
+
+    // 单个表达式的结构和取值，同一个 Shape 中的表编号保持一致
+    pub fn expr_of(&mut self, expr: &'a Expr) -> (String, Vec<&'a ParamData>) {
+        self.expanded.clear();
+        let (key, values) = (self.key.len(), self.values.len());
+        self.expr(expr);
+        (self.key[key..].to_string(), self.values[values..].to_vec())
+    }
+
+    // 占位符可以复用时，取值相同的未命名参数共用一个位置
+    // 因此取值是否相同也是结构的一部分
+    pub fn with_reuse(mut self) -> Self {
+        self.key.push('#');
+        for (i, &a) in self.unnamed.iter().enumerate() {
+            let first = self.unnamed[..i].iter().position(|&b| self.values[b] == self.values[a]).unwrap_or(i);
+            let _ = write!(self.key, "{},", first);
+        }
+        self
+    }
+
+    // 同名参数取值不同，渲染会报错，不能走缓存
+    pub fn has_conflict(&self) -> bool {
+        self.named.iter().enumerate().any(|(i, &(name, a))| {
+            self.named[..i].iter().any(|&(other, b)| other == name && self.values[b] != self.values[a])
+        })
+    }
+
+    // 渲染时绑定的参数地址换算为遍历顺序，有不属于原语句的参数时无法缓存
+    pub fn plan(&self, bound: &[usize]) -> Option<Vec<usize>> {
+        let index: HashMap<usize, usize> = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (*v as *const ParamData as usize, i))
+            .collect();
+        bound.iter().map(|addr| index.get(addr).copied()).collect()
+    }
+
+    fn stmt(&mut self, s: &'a SelectStatement) {
+        self.key.push_str("S(");
+        for cte in &s.with_clause {
+            let _ = write!(self.key, "W{:?}{:?}{:?}", cte.name, cte.columns, cte.materialized);
+            self.stmt(&cte.query);
+        }
+        let _ = write!(self.key, "R{}", s.recursive);
+        match &s.distinct {
+            Distinct::None => {},
+            Distinct::All => self.key.push('D'),
+            Distinct::On(exprs) => {
+                self.key.push_str("DO");
+                self.exprs(exprs);
+            },
+        }
+        self.key.push('|');
+        for item in &s.select_clause {
+            self.expr(&item.expr);
+            let _ = write!(self.key, "{:?},", item.alias);
+        }
+        self.key.push('F');
+        self.table(&s.from_clause);
+        let _ = write!(self.key, "{}{:?}{:?}", s.final_modifier, s.sample, s.indexes);
+        self.key.push('W');
+        self.exprs(&s.where_clause);
+        self.key.push('G');
+        self.exprs(&s.groups);
+        let _ = write!(self.key, "{}H", s.group_by_all);
+        self.exprs(&s.having_clause);
+        for (name, window) in &s.windows {
+            let _ = write!(self.key, "N{:?}", name);
+            self.window(window);
+        }
+        self.key.push('Q');
+        self.exprs(&s.qualify_clause);
+        self.key.push('O');
+        for order in &s.orders {
+            self.expr(&order.expr);
+            let _ = write!(self.key, "{},", order.is_asc());
+        }
+        if let Some((n, exprs)) = &s.limit_by {
+            let _ = write!(self.key, "B{}", n);
+            self.exprs(exprs);
+        }
+        let _ = write!(self.key, "L{:?}{:?}", s.limit, s.offset);
+        if let Some(locking) = &s.locking {
+            match &locking.lock {
+                None => {},
+                Some(Lock::Update) => self.key.push('U'),
+                Some(Lock::Share) => self.key.push_str("Sh"),
+                Some(Lock::UpdateOf(tables)) => {
+                    self.key.push_str("UO");
+                    for table in tables {
+                        self.table(table);
+                    }
+                },
+                Some(Lock::UpdateOfColumns(columns)) => {
+                    self.key.push_str("UC");
+                    for column in columns {
+                        self.column(column);
+                    }
+                },
+            }
+            let _ = write!(self.key, "{:?}", locking.wait);
+        }
+        for set_op in &s.set_ops {
+            let _ = write!(self.key, "{:?}", set_op.op);
+            self.stmt(&set_op.stmt);
+        }
+        for (name, value) in &s.settings {
+            let _ = write!(self.key, "E{:?}", name);
+            self.param(value);
+        }
+        self.key.push(')');
+    }
+
+    fn table(&mut self, table: &'a Table) {
+        let (id, first) = self.table_id(&table.inner);
+        let _ = write!(self.key, "T{}{:?}", id, table.alias);
+        if !first {
+            return;
+        }
+        match table.inner.as_ref() {
+            TableInner::Named(name) => {
+                let _ = write!(self.key, "{:?}", name);
+            },
+            TableInner::Subquery(sq) => self.stmt(sq),
+            TableInner::Values(values) => {
+                let _ = write!(self.key, "V{:?}", values.columns);
+                for row in &values.rows {
+                    self.exprs(row);
+                }
+            },
+            TableInner::Join(join) => {
+                self.table(&join.left);
+                let _ = write!(self.key, "J{:?}", join.join_type);
+                self.table(&join.right);
+                if let Some(on) = &join.on {
+                    self.expr(on);
+                }
+            },
+        }
+    }
+
+    // 返回编号以及是否需要展开表的内容
+    fn table_id(&mut self, inner: &Arc<TableInner>) -> (usize, bool) {
+        let addr = Arc::as_ptr(inner) as usize;
+        let next = self.tables.len();
+        let id = *self.tables.entry(addr).or_insert(next);
+        (id, self.expanded.insert(addr))
+    }
+
+    fn column(&mut self, column: &Column) {
+        let addr = Arc::as_ptr(&column.table_inner) as usize;
+        let next = self.tables.len();
+        let id = *self.tables.entry(addr).or_insert(next);
+        let _ = write!(self.key, "C{}{:?}", id, column.name);
+        // 列所属的表不在 FROM 中时按表名渲染
+        if !self.expanded.contains(&addr)
+            && let TableInner::Named(name) = column.table_inner.as_ref()
+        {
+            let _ = write!(self.key, "{:?}", name);
+        }
+    }
+
+    fn exprs(&mut self, exprs: &'a [Expr]) {
+        for expr in exprs {
+            self.expr(expr);
+            self.key.push(',');
+        }
+        self.key.push(';');
+    }
+
+    fn expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Column(column) => self.column(column),
+            Expr::Param(param) => self.param(param),
+            Expr::Between {
+                expr,
+                low,
+                high,
+                negated,
+            } => {
+                let _ = write!(self.key, "Bt{}(", negated);
+                self.expr(expr);
+                self.expr(low);
+                self.expr(high);
+                self.key.push(')');
+            },
+            Expr::In { expr, list, negated } => {
+                let _ = write!(self.key, "In{}(", negated);
+                self.expr(expr);
+                self.exprs(list);
+                self.key.push(')');
+            },
+            Expr::Unary { op, expr } => {
+                let _ = write!(self.key, "{:?}(", op);
+                self.expr(expr);
+                self.key.push(')');
+            },
+            Expr::Binary { left, op, right } => {
+                let _ = write!(self.key, "{:?}(", op);
+                self.expr(left);
+                self.expr(right);
+                self.key.push(')');
+            },
+            Expr::Func(func) => {
+                let _ = write!(self.key, "Fn{:?}{}(", func.name, func.distinct);
+                for arg in &func.args {
+                    match arg {
+                        FuncArg::Wildcard => self.key.push('*'),
+                        FuncArg::Expr(expr) => self.expr(expr),
+                    }
+                    self.key.push(',');
+                }
+                self.key.push(')');
+                if let Some(window) = &func.over {
+                    self.window(window);
+                }
+            },
+            Expr::Case(case) => {
+                self.key.push_str("Case(");
+                if let Some(operand) = &case.operand {
+                    self.expr(operand);
+                }
+                for (condition, then) in &case.conditions {
+                    self.key.push_str("When");
+                    self.expr(condition);
+                    self.expr(then);
+                }
+                if let Some(else_expr) = &case.else_expr {
+                    self.key.push_str("Else");
+                    self.expr(else_expr);
+                }
+                self.key.push(')');
+            },
+            Expr::Cast { expr, column_type } => {
+                let _ = write!(self.key, "Cast{:?}(", column_type);
+                self.expr(expr);
+                self.key.push(')');
+            },
+            Expr::Subquery(sq) => self.stmt(sq),
+            Expr::Excluded(column) => {
+                self.key.push_str("Ex");
+                self.column(column);
+            },
+        }
+    }
+
+    fn window(&mut self, window: &'a Window) {
+        let _ = write!(self.key, "Over{:?}(", window.name);
+        self.exprs(&window.partition_by);
+        for order in &window.orders {
+            self.expr(&order.expr);
+            let _ = write!(self.key, "{},", order.is_asc());
+        }
+        let _ = write!(self.key, "{:?})", window.frame);
+    }
+
+    fn param(&mut self, param: &'a Param) {
+        match param {
+            Param::Null => self.key.push_str("Null"),
+            // 字面量内联在 SQL 中，取值也是结构的一部分
+            Param::Literal(data) => {
+                let _ = write!(self.key, "{:?}", data);
+            },
+            Param::Value(data) => {
+                self.key.push('?');
+                self.unnamed.push(self.values.len());
+                self.values.push(data);
+            },
+            Param::Named(name, data) => {
+                let _ = write!(self.key, ":{:?}", name);
+                self.named.push((name, self.values.len()));
+                self.values.push(data);
+            },
+        }
+    }
+}
@@ -2,21 +2,30 @@ use crate::sequel::statement::select::SelectStatement;
 use crate::sequel::term::distinct::Distinct;
 use crate::sequel::term::expr::Expr;
 use crate::sequel::term::func::{Func, FuncArg};
+use crate::sequel::term::ops::BinaryOp;
+use crate::sequel::term::param::{Param, ParamData};
 use crate::sequel::term::select_item::SelectItem;
 use crate::sequel::term::table::Table;
+use crate::sequel::term::values::Values;
+use crate::sequel::traverse::visit::{Visit, visit_func};
+use crate::sequel::traverse::visit_mut::{VisitMut, visit_func_mut};
 use crate::sequel::visitor::dialect::Dialect;
-use crate::sequel::visitor::dialect::caps::CountDistinctCap;
-
-pub fn normalize(stmt: &SelectStatement) -> SelectStatement {
-    let mut stmt = stmt.clone();
-    for item in &mut stmt.select_clause {
-        if let Expr::Func(ref mut f) = item.expr {
-            if f.name.eq_ignore_ascii_case("count") && f.args.is_empty() {
-                f.args.push(FuncArg::Wildcard);
+use crate::sequel::visitor::dialect::caps::{CountDistinctCap, ValuesTableCap};
+use crate::sequel::visitor::render_cache::Shape;
+use std::collections::HashSet;
+
+// COUNT() 补全为 COUNT(*)，包括嵌套在表达式和子查询中的
+pub fn normalize(stmt: &mut SelectStatement) {
+    struct CountWildcard;
+    impl VisitMut for CountWildcard {
+        fn visit_func_mut(&mut self, func: &mut Func) {
+            if func.name.eq_ignore_ascii_case("count") && func.args.is_empty() {
+                func.args.push(FuncArg::Wildcard);
             }
+            visit_func_mut(self, func);
         }
     }
-    stmt
+    CountWildcard.visit_select_statement_mut(stmt);
 }
 
 fn find_count_distinct_multi(stmt: &SelectStatement) -> Option<(usize, &Func)> {
@@ -57,6 +66,12 @@ fn collect_outer_columns(stmt: &SelectStatement) -> Vec<Expr> {
         })
         .collect()
 }
+// 方言支持多列 COUNT(DISTINCT ...) 或语句中没有时不需要改写
+pub fn needs_count_distinct_rewrite(stmt: &SelectStatement, dialect: &dyn Dialect) -> bool {
+    ![CountDistinctCap::Extend, CountDistinctCap::Merge].contains(&dialect.caps().count_distinct)
+        && find_multi_count(stmt).is_some()
+}
+
 pub fn rewrite_count_distinct(stmt: &SelectStatement, dialect: &dyn Dialect) -> SelectStatement {
     if [CountDistinctCap::Extend, CountDistinctCap::Merge].contains(&dialect.caps().count_distinct) {
         return stmt.clone();
@@ -71,6 +86,10 @@ pub fn rewrite_count_distinct(stmt: &SelectStatement, dialect: &dyn Dialect) ->
     sub_cols.append(&mut outer_cols.clone());
 
     let mut subquery = stmt.clone();
+    // WITH 子句只保留在外层
+    subquery.with_clause = Vec::new();
+    subquery.recursive = false;
+    subquery.set_ops = Vec::new();
     subquery.select_clause = sub_cols.into_iter().map(|e| SelectItem::from(e)).collect();
     subquery.distinct = Distinct::All;
     subquery.from_clause = stmt.from_clause.clone();
@@ -97,3 +116,180 @@ pub fn rewrite_count_distinct(stmt: &SelectStatement, dialect: &dyn Dialect) ->
 
     outer
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InListStrategy {
+    // 参数个数没有超出方言的限制，或者无法拆分
+    Unchanged,
+    // 按 IN 列表拆成多条语句，由调用方合并各条语句的结果
+    Chunks,
+    // IN 列表改为与 VALUES 临时表 JOIN，取值以字面量内联，不再占用参数
+    ValuesJoin,
+}
+
+#[derive(Debug, Clone)]
+pub struct InListSplit {
+    pub strategy: InListStrategy,
+    pub statements: Vec<SelectStatement>,
+}
+
+// 参数个数超出方言的限制时处理 WHERE 中最长的 IN 列表：
+// 结果逐行独立的查询优先拆成多条语句，否则改为与 VALUES 临时表 JOIN
+//   let split = split_in_list(&stmt, &SQLite {});
+//   for stmt in &split.statements { ... }
+pub fn split_in_list(stmt: &SelectStatement, dialect: &dyn Dialect) -> InListSplit {
+    let unchanged = || InListSplit {
+        strategy: InListStrategy::Unchanged,
+        statements: vec![stmt.clone()],
+    };
+    let caps = dialect.caps();
+    let Some(limit) = caps.max_params else {
+        return unchanged();
+    };
+    let total = Shape::of(stmt).values.len();
+    if total <= limit {
+        return unchanged();
+    }
+    let Some(index) = longest_in_list(stmt) else {
+        return unchanged();
+    };
+    let Expr::In { expr, list, .. } = &stmt.where_clause[index] else {
+        return unchanged();
+    };
+
+    let others = total
+        - list
+            .iter()
+            .filter(|e| matches!(e, Expr::Param(Param::Value(_))))
+            .count();
+    // 去重，避免 JOIN 产生重复的行，拆分时也少占参数
+    let mut seen = HashSet::new();
+    let list: Vec<Expr> = list
+        .iter()
+        .filter(|e| match e {
+            Expr::Param(p) => p.data().is_none_or(|data| seen.insert(format!("{:?}", data))),
+            _ => true,
+        })
+        .cloned()
+        .collect();
+    if others < limit && is_row_wise(stmt) {
+        let statements = list
+            .chunks(limit - others)
+            .map(|chunk| {
+                let mut chunked = stmt.clone();
+                chunked.where_clause[index] = Expr::In {
+                    expr: expr.clone(),
+                    list: chunk.to_vec(),
+                    negated: false,
+                };
+                chunked
+            })
+            .collect();
+        return InListSplit {
+            strategy: InListStrategy::Chunks,
+            statements,
+        };
+    }
+
+    // FOR UPDATE 不能作用于 VALUES
+    if caps.values_table == ValuesTableCap::None || stmt.locking.as_ref().is_some_and(|l| l.lock.is_some()) {
+        return unchanged();
+    }
+    // 内联后只剩字符串或数字字面量，UUID、日期等与列的类型不匹配，e.g. PostgreSQL 的 uuid = text
+    let inlinable = list.iter().all(|e| {
+        matches!(
+            e,
+            Expr::Param(p) if matches!(
+                p.data(),
+                Some(ParamData::Int(_) | ParamData::Float(_) | ParamData::Decimal(_) | ParamData::String(_))
+            )
+        )
+    });
+    if !inlinable {
+        return unchanged();
+    }
+    let rows = list
+        .iter()
+        .filter_map(|e| match e {
+            Expr::Param(p) => p.data(),
+            _ => None,
+        })
+        .map(|data| vec![Expr::Param(Param::Literal(data.clone()))])
+        .collect();
+    let values = Table::from(Values::new(["v"], rows));
+    let mut joined = stmt.clone();
+    joined.where_clause.remove(index);
+    let on = Expr::Binary {
+        left: expr.clone(),
+        op: BinaryOp::Eq,
+        right: Box::new(Expr::Column(values.column("v"))),
+    };
+    joined.from_clause = joined.from_clause.join(&values, on);
+    InListSplit {
+        strategy: InListStrategy::ValuesJoin,
+        statements: vec![joined],
+    }
+}
+
+// WHERE 中由参数组成的最长 IN 列表，NOT IN 拆分后语义不同，不处理
+fn longest_in_list(stmt: &SelectStatement) -> Option<usize> {
+    stmt.where_clause
+        .iter()
+        .enumerate()
+        .filter_map(|(i, e)| match e {
+            Expr::In {
+                list, negated: false, ..
+            } if list
+                .iter()
+                .all(|e| matches!(e, Expr::Param(Param::Value(_) | Param::Literal(_)))) =>
+            {
+                Some((i, list.len()))
+            },
+            _ => None,
+        })
+        .max_by_key(|(_, len)| *len)
+        .map(|(i, _)| i)
+}
+
+// 每行结果只取决于自身的查询，拆成多条语句后把结果拼接起来即可
+fn is_row_wise(stmt: &SelectStatement) -> bool {
+    matches!(stmt.distinct, Distinct::None)
+        && stmt.groups.is_empty()
+        && !stmt.group_by_all
+        && stmt.having_clause.is_empty()
+        && stmt.qualify_clause.is_empty()
+        && stmt.orders.is_empty()
+        && stmt.limit_by.is_none()
+        && stmt.limit.is_none()
+        && stmt.offset.is_none()
+        && stmt.set_ops.is_empty()
+        && !stmt.select_clause.iter().any(|item| has_aggregate(&item.expr))
+}
+
+const AGGREGATES: [&str; 9] = [
+    "COUNT",
+    "SUM",
+    "AVG",
+    "MIN",
+    "MAX",
+    "GROUP_CONCAT",
+    "STRING_AGG",
+    "ARRAY_AGG",
+    "LISTAGG",
+];
+
+// 聚合函数或窗口函数，结果取决于多行；子查询自成一体，不计入
+fn has_aggregate(expr: &Expr) -> bool {
+    struct Aggregate(bool);
+    impl Visit<'_> for Aggregate {
+        fn visit_select_statement(&mut self, _: &SelectStatement) {}
+
+        fn visit_func(&mut self, func: &Func) {
+            self.0 |= func.over.is_some() || AGGREGATES.iter().any(|name| func.name.eq_ignore_ascii_case(name));
+            visit_func(self, func);
+        }
+    }
+    let mut aggregate = Aggregate(false);
+    aggregate.visit_expr(expr);
+    aggregate.0
+}
@@ -1,5 +1,12 @@
+use crate::model::columns::ColumnType;
+use crate::sequel::parser::lexer::{TokenKind, tokenize};
+use crate::sequel::statement::delete::DeleteStatement;
+use crate::sequel::statement::insert::{ConflictAction, ConflictTarget, InsertSource, InsertStatement, OnConflict};
 use crate::sequel::statement::select::SelectStatement;
+use crate::sequel::statement::update::{Assignment, UpdateStatement};
+use crate::sequel::term::case::Case;
 use crate::sequel::term::column::Column;
+use crate::sequel::term::cte::Cte;
 use crate::sequel::term::distinct::Distinct;
 use crate::sequel::term::expr::Expr;
 use crate::sequel::term::func::{Func, FuncArg};
@@ -8,17 +15,39 @@ use crate::sequel::term::join::{Join, JoinType};
 use crate::sequel::term::lock::{Lock, Locking, Wait};
 use crate::sequel::term::ops::{BinaryOp, UnaryOp};
 use crate::sequel::term::order::Order;
-use crate::sequel::term::param::{Param, ParamData};
+use crate::sequel::term::param::{Param, ParamData, lit};
+use crate::sequel::term::sample::Sample;
 use crate::sequel::term::select_item::SelectItem;
-use crate::sequel::term::table::{Table, TableInner};
+use crate::sequel::term::set_op::{SetOp, SetOperation};
+use crate::sequel::term::table::{Table, TableInner, TableName};
+use crate::sequel::term::values::Values;
+use crate::sequel::term::window::{FrameBound, FrameUnit, Window};
+use crate::sequel::traverse::visit::{Visit, visit_table};
+use crate::sequel::traverse::visit_mut::{VisitMut, visit_expr_mut};
 use crate::sequel::visitor::alias_cache::AliasCache;
-use crate::sequel::visitor::builder::Builder;
+use crate::sequel::visitor::builder::{Builder, ParamMap};
 use crate::sequel::visitor::dialect::Dialect;
-use crate::sequel::visitor::dialect::caps::{CountDistinctCap, IndexFormat};
+use crate::sequel::visitor::dialect::caps::{
+    CastCap, CountDistinctCap, DeleteJoinCap, IndexFormat, LimitCap, LockOfCap, SampleCap, UpdateJoinCap, UpsertCap,
+    ValuesTableCap,
+};
+use crate::sequel::visitor::dialect::clickhouse::ClickHouse;
+use crate::sequel::visitor::dialect::duckdb::DuckDB;
+use crate::sequel::visitor::dialect::mssql::MsSql;
 use crate::sequel::visitor::dialect::mysql::MySQL;
+use crate::sequel::visitor::dialect::oracle::Oracle;
 use crate::sequel::visitor::dialect::postgre::PostgreSQL;
 use crate::sequel::visitor::dialect::sqlite::SQLite;
-use crate::sequel::visitor::rewriter::rewrite_count_distinct;
+use crate::sequel::visitor::error::{RenderError, RenderMode};
+use crate::sequel::visitor::fingerprint::{Fingerprint, normalize};
+use crate::sequel::visitor::format::{Format, pretty};
+use crate::sequel::visitor::pass::{
+    CountDistinct, DedupeFilters, DoubleNegation, DropTrue, EmptyIn, FlattenLogic, FoldConstants, Pass,
+};
+use crate::sequel::visitor::render_cache::{Entry, RenderCache, Shape};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
 
 pub fn mysql() -> Visitor<MySQL> {
     Visitor::new(MySQL {})
@@ -32,6 +61,22 @@ pub fn sqlite() -> Visitor<SQLite> {
     Visitor::new(SQLite {})
 }
 
+pub fn mssql() -> Visitor<MsSql> {
+    Visitor::new(MsSql {})
+}
+
+pub fn oracle() -> Visitor<Oracle> {
+    Visitor::new(Oracle {})
+}
+
+pub fn duckdb() -> Visitor<DuckDB> {
+    Visitor::new(DuckDB {})
+}
+
+pub fn clickhouse() -> Visitor<ClickHouse> {
+    Visitor::new(ClickHouse {})
+}
+
 enum FilterScope {
     Where,
     Having,
@@ -40,6 +85,22 @@ pub struct Visitor<D> {
     builder: Builder,
     dialect: D,
     alias_cache: AliasCache,
+    mode: RenderMode,
+    errors: Vec<RenderError>,
+    // IN 列表整体绑定为一个数组参数
+    array_binding: bool,
+    // 为 None 时输出紧凑的单行 SQL
+    format: Option<Format>,
+    // 填充缓存时记录绑定参数在语句中的地址
+    bound: Option<Vec<usize>>,
+    // 待渲染的表提示，key 为 TableInner 的地址
+    table_hints: HashMap<usize, String>,
+    // ON CONFLICT DO UPDATE 中目标表的列按表名限定，值为目标表 TableInner 的地址
+    upsert_target: Option<usize>,
+    // 渲染前依次执行的改写
+    passes: Vec<Arc<dyn Pass>>,
+    // 正在渲染改写后的 SELECT，其中的子查询已经一并改写
+    rewritten: bool,
 }
 
 impl<D: Dialect> Visitor<D> {
@@ -48,6 +109,164 @@ impl<D: Dialect> Visitor<D> {
             builder: Builder::new(),
             dialect,
             alias_cache: AliasCache::new(),
+            mode: RenderMode::default(),
+            errors: Vec::new(),
+            array_binding: false,
+            format: None,
+            bound: None,
+            table_hints: HashMap::new(),
+            upsert_target: None,
+            passes: vec![Arc::new(CountDistinct)],
+            rewritten: false,
+        }
+    }
+
+    pub fn mode(mut self, mode: RenderMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    // e.g. sqlite().strict().try_visit_select_statement(&stmt)
+    pub fn strict(self) -> Self {
+        self.mode(RenderMode::Strict)
+    }
+
+    // e.g. postgre().array_binding()，IN ($1, $2, ...) 改为 = ANY($1)；方言不支持数组参数时不生效
+    pub fn array_binding(mut self) -> Self {
+        self.array_binding = true;
+        self
+    }
+
+    // e.g. postgre().reuse_params()，$n、@pn 等可以复用的占位符中取值相同的未命名参数共用一个位置
+    // 默认不复用：同一个值绑定到不同类型的列时，PostgreSQL 会推断出冲突的参数类型
+    pub fn reuse_params(mut self) -> Self {
+        self.builder.reuse = true;
+        self
+    }
+
+    // e.g. postgre().pretty(Format::default())，只影响 finish 返回的 SQL 文本
+    pub fn pretty(mut self, format: Format) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    // 追加一个渲染前的改写，e.g. postgre().pass(Tenant(42))
+    pub fn pass(mut self, pass: impl Pass + 'static) -> Self {
+        self.passes.push(Arc::new(pass));
+        self
+    }
+
+    // 清空改写，包括默认的 COUNT(DISTINCT a, b) 改写
+    pub fn without_passes(mut self) -> Self {
+        self.passes.clear();
+        self
+    }
+
+    // 化简条件：折叠常量、空 IN 列表、双重否定、嵌套的 AND/OR、恒为真的条件和重复的条件
+    pub fn simplify(self) -> Self {
+        self.pass(FoldConstants)
+            .pass(EmptyIn)
+            .pass(DoubleNegation)
+            .pass(FlattenLogic)
+            .pass(DropTrue)
+            .pass(DedupeFilters)
+    }
+
+    // 没有适用的改写时借用原语句，否则复制一份再改写
+    fn run_passes<'s>(&self, select_stmt: &'s SelectStatement) -> Cow<'s, SelectStatement> {
+        let mut stmt = Cow::Borrowed(select_stmt);
+        for pass in &self.passes {
+            if pass.applies(&stmt, &self.dialect) {
+                pass.run(stmt.to_mut(), &self.dialect);
+            }
+        }
+        stmt
+    }
+
+    // 字面量与参数换成占位符，IN 列表折叠为 IN (...)，自动生成的别名还原为表名
+    // 在新的 Visitor 中按相同的设置渲染，不影响当前 Visitor 中的内容
+    pub fn fingerprint(&self, select_stmt: &SelectStatement) -> Fingerprint
+    where
+        D: Clone,
+    {
+        let mut visitor = Visitor::new(self.dialect.clone()).mode(self.mode);
+        visitor.passes = self.passes.clone();
+        // 改写后再收起 IN 列表，长列表不会被拆成多个 IN，也不会绑定为数组
+        let mut select_stmt = visitor.run_passes(select_stmt).into_owned();
+        collapse_in_lists(&mut select_stmt);
+        visitor.rewritten_statement(&select_stmt);
+        let aliases = visitor
+            .alias_cache
+            .generated()
+            .iter()
+            .map(|(alias, name)| (visitor.quoted(alias), visitor.quoted(name)))
+            .collect();
+        Fingerprint::new(normalize(&visitor.builder.buff, &visitor.dialect, &aliases))
+    }
+
+    // 同一结构的语句只渲染一次，之后按提取计划收集参数；只对新建的 Visitor 生效
+    pub fn visit_select_statement_cached(
+        &mut self,
+        select_stmt: &SelectStatement,
+        cache: &RenderCache<D>,
+    ) -> &mut Self {
+        let fresh = self.builder.buff.is_empty() && self.builder.binder.is_empty() && self.errors.is_empty();
+        // 数组参数不在原语句中，无法生成提取计划
+        if !fresh || self.array_binding {
+            return self.visit_select_statement(select_stmt);
+        }
+        // 按改写后的语句计算结构，渲染时不再重复改写
+        let select_stmt = self.run_passes(select_stmt);
+        let mut shape = Shape::of(&select_stmt);
+        if shape.has_conflict() {
+            return self.rewritten_statement(&select_stmt);
+        }
+        if self.builder.reuse && self.dialect.placeholder_style().reusable() {
+            shape = shape.with_reuse();
+        }
+        // 宽松模式下渲染时可能丢掉子句而不记录错误，两种模式的结果不能共用
+        shape.key.push_str(&format!("{:?}", self.mode));
+        if let Some(entry) = cache.get(&shape.key) {
+            self.builder.push(&entry.sql);
+            self.builder.binder.extend(entry.plan.iter().map(|&i| shape.values[i].clone()));
+            self.builder.names = entry.names.clone();
+            self.builder.slot_names = entry.slot_names.clone();
+            return self;
+        }
+        self.bound = Some(Vec::new());
+        self.rewritten_statement(&select_stmt);
+        let bound = self.bound.take().unwrap_or_default();
+        if self.errors.is_empty()
+            && let Some(plan) = shape.plan(&bound)
+        {
+            let entry = Entry {
+                sql: self.builder.buff.clone(),
+                plan,
+                names: self.builder.names.clone(),
+                slot_names: self.builder.slot_names.clone(),
+            };
+            cache.insert(shape.key, entry);
+        }
+        self
+    }
+
+    pub fn try_visit_select_statement(&mut self, select_stmt: &SelectStatement) -> Result<&mut Self, RenderError> {
+        self.visit_select_statement(select_stmt).check()
+    }
+    pub fn try_visit_insert_statement(&mut self, insert_stmt: &InsertStatement) -> Result<&mut Self, RenderError> {
+        self.visit_insert_statement(insert_stmt).check()
+    }
+    pub fn try_visit_update_statement(&mut self, update_stmt: &UpdateStatement) -> Result<&mut Self, RenderError> {
+        self.visit_update_statement(update_stmt).check()
+    }
+    pub fn try_visit_delete_statement(&mut self, delete_stmt: &DeleteStatement) -> Result<&mut Self, RenderError> {
+        self.visit_delete_statement(delete_stmt).check()
+    }
+
+    fn check(&mut self) -> Result<&mut Self, RenderError> {
+        match self.errors.first() {
+            Some(e) => Err(e.clone()),
+            None => Ok(self),
         }
     }
 
@@ -69,38 +288,27 @@ impl<D: Dialect> Visitor<D> {
         for having_expr in &stmt.having_clause {
             self.register_table_from_expr(having_expr);
         }
+
+        for qualify_expr in &stmt.qualify_clause {
+            self.register_table_from_expr(qualify_expr);
+        }
     }
 
+    // 表达式中的子查询各自注册 FROM 中的表
     fn register_table_from_expr(&mut self, expr: &Expr) {
-        match expr {
-            Expr::Subquery(sq) => self.register_tables(sq),
-            Expr::Func(func) => {
-                for arg in &func.args {
-                    match arg {
-                        FuncArg::Expr(expr) => self.register_table_from_expr(expr),
-                        FuncArg::Wildcard => {},
-                    }
-                }
-            },
-            Expr::Binary { left, right, .. } => {
-                self.register_table_from_expr(left);
-                self.register_table_from_expr(right);
-            },
-            Expr::Unary { expr, .. } => self.register_table_from_expr(expr),
-            Expr::In { expr, list, .. } => {
-                self.register_table_from_expr(expr);
-                for e in list {
-                    self.register_table_from_expr(e);
-                }
-            },
-            _ => {},
+        struct Subqueries<'v, D: Dialect>(&'v mut Visitor<D>);
+        impl<D: Dialect> Visit<'_> for Subqueries<'_, D> {
+            fn visit_select_statement(&mut self, stmt: &SelectStatement) {
+                self.0.register_tables(stmt);
+            }
         }
+        Subqueries(self).visit_expr(expr);
     }
     fn register_table_inner(&mut self, table: &Table) {
         let inner = &table.inner;
         match &inner.as_ref() {
             TableInner::Named(name) => {
-                self.alias_cache.add(inner, name.clone(), table.alias.clone());
+                self.alias_cache.add(inner, name.name.clone(), table.alias.clone());
             },
             TableInner::Subquery(sq) => {
                 self.alias_cache.add(inner, "sq".to_string(), None);
@@ -111,65 +319,578 @@ impl<D: Dialect> Visitor<D> {
                 self.register_table_inner(&join.left);
                 self.register_table_inner(&join.right);
             },
+            TableInner::Values(_) => {
+                self.alias_cache.add(inner, "v".to_string(), table.alias.clone());
+            },
         };
     }
 
     pub fn visit_select_statement(&mut self, select_stmt: &SelectStatement) -> &mut Self {
-        // let select_stmt = normalize(select_stmt);
-        let select_stmt = rewrite_count_distinct(select_stmt, &self.dialect);
+        let select_stmt = self.run_passes(select_stmt);
+        self.rewritten_statement(&select_stmt)
+    }
+
+    fn rewritten_statement(&mut self, select_stmt: &SelectStatement) -> &mut Self {
+        let outer = std::mem::replace(&mut self.rewritten, true);
+        self.select_statement(select_stmt);
+        self.rewritten = outer;
+        self
+    }
+
+    // INSERT/UPDATE/DELETE 中的子查询没有经过改写，渲染前先改写
+    fn subquery(&mut self, select_stmt: &SelectStatement) -> &mut Self {
+        if self.rewritten {
+            return self.select_statement(select_stmt);
+        }
+        self.visit_select_statement(select_stmt)
+    }
+
+    // 渲染改写后的语句，子查询、CTE 和集合运算已经在改写时处理过
+    fn select_statement(&mut self, select_stmt: &SelectStatement) -> &mut Self {
+        if let Some(at) = intersect_after_other(&select_stmt.set_ops) {
+            return self.select_statement(&group_set_ops(select_stmt, at));
+        }
+        for cte in &select_stmt.with_clause {
+            self.alias_cache.add_cte(&cte.name);
+        }
+        self.register_tables(select_stmt);
 
-        self.register_tables(&select_stmt);
+        let caps = self.dialect.caps();
+        if caps.table_hints {
+            self.register_table_hints(select_stmt);
+        }
+        // 只有 LIMIT 时使用 SELECT TOP n
+        let top = match select_stmt.limit {
+            Some(n) if caps.limit == LimitCap::Top && select_stmt.offset.is_none() && select_stmt.set_ops.is_empty() => {
+                Some(n)
+            },
+            _ => None,
+        };
 
+        self.visit_with_clause(&select_stmt.with_clause, select_stmt.recursive);
         self.push("SELECT ");
         self.visit_distinct(&select_stmt.distinct);
+        if let Some(n) = top {
+            self.push("TOP ").push(&n.to_string()).push(" ");
+        }
         self.visit_select_clause(&select_stmt.select_clause);
 
         self.push(" FROM ").visit_table(&select_stmt.from_clause);
+        self.visit_final(select_stmt.final_modifier);
+        if caps.sample == SampleCap::Sample {
+            self.visit_sample(&select_stmt.sample);
+        }
 
-        self.visit_indexes(&select_stmt.indexes);
+        if !caps.table_hints {
+            self.visit_indexes(&select_stmt.indexes);
+        }
         self.visit_where_clause(&select_stmt.where_clause);
-        self.visit_groups(&select_stmt.groups);
+        if select_stmt.group_by_all {
+            self.visit_group_by_all();
+        } else {
+            self.visit_groups(&select_stmt.groups);
+        }
         self.visit_having_clause(&select_stmt.having_clause);
-        self.visit_orders(&select_stmt.orders);
-        self.visit_limit_and_offset(select_stmt.limit, select_stmt.offset);
-        self.visit_locking(&select_stmt.locking);
+        self.visit_windows(&select_stmt.windows);
+        self.visit_qualify_clause(&select_stmt.qualify_clause);
+        if caps.sample != SampleCap::Sample {
+            self.visit_sample(&select_stmt.sample);
+        }
+
+        if select_stmt.set_ops.is_empty() {
+            self.visit_orders(&select_stmt.orders);
+            self.visit_limit_by(&select_stmt.limit_by);
+            if top.is_none() {
+                self.visit_paging(&select_stmt.orders, select_stmt.limit, select_stmt.offset);
+            }
+            self.visit_locking(&select_stmt.locking);
+        } else {
+            for set_op in &select_stmt.set_ops {
+                self.visit_set_operation(set_op);
+            }
+            self.visit_compound_orders(select_stmt, &select_stmt.orders);
+            self.visit_paging(&select_stmt.orders, select_stmt.limit, select_stmt.offset);
+            if has_lock(&select_stmt.locking) {
+                self.unsupported("FOR UPDATE", "a compound query can not be locked");
+            }
+        }
+        self.visit_settings(&select_stmt.settings);
+        self
+    }
+
+    fn visit_final(&mut self, final_modifier: bool) -> &mut Self {
+        if !final_modifier {
+            return self;
+        }
+        if self.dialect.caps().final_modifier {
+            self.push(" FINAL")
+        } else {
+            self.unsupported("FINAL", "the dialect has no FINAL modifier")
+        }
+    }
+
+    fn visit_sample(&mut self, sample: &Option<Sample>) -> &mut Self {
+        let Some(sample) = sample else {
+            return self;
+        };
+        match (self.dialect.caps().sample, sample) {
+            (SampleCap::UsingSample, Sample::Percent(p)) => self.push(" USING SAMPLE ").push(&p.to_string()).push("%"),
+            (SampleCap::UsingSample, Sample::Rows(n)) => self.push(" USING SAMPLE ").push(&n.to_string()).push(" ROWS"),
+            // ClickHouse 用 0~1 的比例表示百分比
+            (SampleCap::Sample, Sample::Percent(p)) => self.push(" SAMPLE ").push(&(p / 100.0).to_string()),
+            (SampleCap::Sample, Sample::Rows(n)) => self.push(" SAMPLE ").push(&n.to_string()),
+            (SampleCap::None, _) => self.unsupported("SAMPLE", "the dialect has no SAMPLE clause"),
+        }
+    }
+
+    fn visit_group_by_all(&mut self) -> &mut Self {
+        if self.dialect.caps().group_by_all {
+            self.push(" GROUP BY ALL")
+        } else {
+            // 丢掉分组会改变结果，宽松模式下同样报错
+            self.fail(RenderError::unsupported("GROUP BY ALL", "the dialect has no GROUP BY ALL"))
+        }
+    }
+
+    pub fn visit_qualify_clause(&mut self, qualify_clause: &Vec<Expr>) -> &mut Self {
+        if qualify_clause.is_empty() {
+            return self;
+        }
+        if !self.dialect.caps().qualify {
+            // 丢掉过滤条件会改变结果，宽松模式下同样报错
+            return self.fail(RenderError::unsupported("QUALIFY", "the dialect has no QUALIFY clause"));
+        }
+        self.push(" QUALIFY ");
+        let mut iter = qualify_clause.iter();
+        if let Some(expr) = iter.next() {
+            self.visit_expr(expr, BinaryOp::And.precedence());
+        }
+        for expr in iter {
+            self.visit_binary_op(&BinaryOp::And).visit_expr(expr, BinaryOp::And.precedence());
+        }
         self
     }
 
+    fn visit_limit_by(&mut self, limit_by: &Option<(usize, Vec<Expr>)>) -> &mut Self {
+        let Some((n, cols)) = limit_by else {
+            return self;
+        };
+        if !self.dialect.caps().limit_by {
+            return self.fail(RenderError::unsupported("LIMIT BY", "the dialect has no LIMIT BY clause"));
+        }
+        self.push(" LIMIT ").push(&n.to_string()).push(" BY ").visit_expr_list(cols, 0)
+    }
+
+    fn visit_settings(&mut self, settings: &[(String, Param)]) -> &mut Self {
+        if settings.is_empty() {
+            return self;
+        }
+        if !self.dialect.caps().settings {
+            return self.unsupported("SETTINGS", "the dialect has no SETTINGS clause");
+        }
+        self.push(" SETTINGS ");
+        let mut iter = settings.iter();
+        if let Some((name, value)) = iter.next() {
+            self.push(name).push(" = ").visit_param(value);
+        }
+        for (name, value) in iter {
+            self.push(", ").push(name).push(" = ").visit_param(value);
+        }
+        self
+    }
+
+    fn visit_set_operation(&mut self, set_op: &SetOperation) -> &mut Self {
+        self.push(" ").push(set_op.op.as_str()).push(" ");
+        let stmt = &set_op.stmt;
+        if !stmt.has_own_ordering() && stmt.set_ops.is_empty() && stmt.with_clause.is_empty() {
+            return self.select_statement(stmt);
+        }
+        // 自带 ORDER BY/LIMIT 或嵌套组合的操作数需要单独成组：
+        // 支持括号的方言直接加括号，否则（SQLite）包成派生表
+        if self.dialect.caps().parenthesized_set_operand {
+            self.push("(").select_statement(stmt).push(")")
+        } else {
+            let wrapped = SelectStatement::from(&Table::from(stmt.clone()));
+            self.select_statement(&wrapped)
+        }
+    }
+
+    // 组合结果的 ORDER BY 只能引用结果列，不能带表名
+    fn visit_compound_orders(&mut self, select_stmt: &SelectStatement, orders: &[Order]) -> &mut Self {
+        let mut iter = orders.iter();
+        if let Some(item) = iter.next() {
+            self.push(" ORDER BY ").visit_compound_order(select_stmt, item);
+            for item in iter {
+                self.push(", ").visit_compound_order(select_stmt, item);
+            }
+        }
+        self
+    }
+
+    fn visit_compound_order(&mut self, select_stmt: &SelectStatement, order: &Order) -> &mut Self {
+        match &order.expr {
+            Expr::Column(col) => self.push_quote(&result_column(select_stmt, col)),
+            expr => self.visit_expr(expr, 0),
+        };
+        if order.is_desc() {
+            self.push(" DESC");
+        }
+        self
+    }
+
+    pub fn visit_with_clause(&mut self, with_clause: &[Cte], recursive: bool) -> &mut Self {
+        let mut iter = with_clause.iter();
+        if let Some(cte) = iter.next() {
+            let caps = self.dialect.caps();
+            if recursive
+                && caps.recursive_cte_columns
+                && with_clause.iter().any(|cte| cte.columns.is_empty() && references_table(&cte.query, &cte.name))
+            {
+                self.unsupported("WITH RECURSIVE", "a recursive CTE needs a column list in the dialect");
+            }
+            self.push(if recursive && caps.recursive_keyword { "WITH RECURSIVE " } else { "WITH " });
+            self.visit_cte(cte);
+            for cte in iter {
+                self.push(", ").visit_cte(cte);
+            }
+            self.push(" ");
+        }
+        self
+    }
+
+    fn visit_cte(&mut self, cte: &Cte) -> &mut Self {
+        self.push_quote(&cte.name);
+        let mut iter = cte.columns.iter();
+        if let Some(col) = iter.next() {
+            self.push(" (").push_quote(col);
+            for col in iter {
+                self.push(", ").push_quote(col);
+            }
+            self.push(")");
+        }
+        self.push(" AS ");
+        if self.dialect.caps().cte_materialized {
+            match cte.materialized {
+                Some(true) => self.push("MATERIALIZED "),
+                Some(false) => self.push("NOT MATERIALIZED "),
+                None => self.noop(),
+            };
+        } else if cte.materialized.is_some() {
+            self.unsupported("MATERIALIZED", "the dialect has no CTE materialization hint");
+        }
+        self.push("(").select_statement(&cte.query).push(")")
+    }
+
+    pub fn visit_insert_statement(&mut self, insert_stmt: &InsertStatement) -> &mut Self {
+        // MySQL 没有 ON CONFLICT DO NOTHING，用 INSERT IGNORE 代替
+        let ignore = self.dialect.caps().upsert == UpsertCap::OnDuplicateKey
+            && matches!(
+                insert_stmt.on_conflict,
+                Some(OnConflict {
+                    action: ConflictAction::DoNothing,
+                    ..
+                })
+            );
+        self.push(if ignore { "INSERT IGNORE INTO " } else { "INSERT INTO " });
+        self.visit_target_table(&insert_stmt.table);
+
+        if !insert_stmt.columns.is_empty() {
+            self.push(" (").visit_column_names(&insert_stmt.columns).push(")");
+        }
+
+        match &insert_stmt.source {
+            InsertSource::Values(rows) => {
+                if rows.is_empty() {
+                    self.fail(RenderError::invalid("VALUES", "an INSERT needs at least one row"));
+                }
+                // 未指定列时以第一行为准
+                let width = match insert_stmt.columns.len() {
+                    0 => rows.first().map_or(0, Vec::len),
+                    n => n,
+                };
+                if rows.iter().any(|row| row.len() != width) {
+                    self.fail(RenderError::invalid("VALUES", "every row must have one value per column"));
+                }
+                let mut iter = rows.iter();
+                if let Some(row) = iter.next() {
+                    self.push(" VALUES (").visit_expr_list(row, 0).push(")");
+                    for row in iter {
+                        self.push(", (").visit_expr_list(row, 0).push(")");
+                    }
+                }
+            },
+            InsertSource::Select(stmt) => {
+                self.push(" ").visit_select_statement(stmt);
+            },
+        }
+        self.visit_on_conflict(&insert_stmt.table, &insert_stmt.on_conflict);
+        // INSERT ... SELECT 中表的别名在 RETURNING 中不可见，RETURNING 的列不加限定
+        let aliases = std::mem::replace(&mut self.alias_cache, AliasCache::new());
+        self.visit_returning(&insert_stmt.returning);
+        self.alias_cache = aliases;
+        self
+    }
+
+    fn visit_on_conflict(&mut self, table: &Table, on_conflict: &Option<OnConflict>) -> &mut Self {
+        let Some(on_conflict) = on_conflict else {
+            return self;
+        };
+        match self.dialect.caps().upsert {
+            UpsertCap::OnConflict => {
+                self.push(" ON CONFLICT");
+                match &on_conflict.target {
+                    Some(ConflictTarget::Columns(cols)) => self.push(" (").visit_column_names(cols).push(")"),
+                    Some(ConflictTarget::Constraint(name)) if self.dialect.caps().conflict_constraint => {
+                        self.push(" ON CONSTRAINT ").push_quote(name)
+                    },
+                    Some(ConflictTarget::Constraint(_)) => self.fail(RenderError::unsupported(
+                        "ON CONFLICT ON CONSTRAINT",
+                        "the dialect only accepts conflict columns",
+                    )),
+                    None => self.noop(),
+                };
+                match &on_conflict.action {
+                    ConflictAction::DoNothing => self.push(" DO NOTHING"),
+                    ConflictAction::DoUpdate(assignments) => {
+                        if on_conflict.target.is_none() {
+                            self.fail(RenderError::invalid("ON CONFLICT DO UPDATE", "it needs a conflict target"));
+                        }
+                        // EXCLUDED 与目标表有同名列，目标表的列必须带上表名
+                        self.upsert_target = Some(Arc::as_ptr(&table.inner) as usize);
+                        self.push(" DO UPDATE SET ").visit_assignments(assignments, false);
+                        self.upsert_target = None;
+                        self
+                    },
+                }
+            },
+            // 冲突目标由表上的唯一键决定，DO NOTHING 已经渲染为 INSERT IGNORE
+            UpsertCap::OnDuplicateKey => match &on_conflict.action {
+                ConflictAction::DoNothing => self.noop(),
+                ConflictAction::DoUpdate(assignments) => {
+                    self.push(" ON DUPLICATE KEY UPDATE ").visit_assignments(assignments, false)
+                },
+            },
+            UpsertCap::None => self.fail(RenderError::unsupported("ON CONFLICT", "the dialect has no upsert")),
+        }
+    }
+
+    fn visit_column_names(&mut self, columns: &[Column]) -> &mut Self {
+        let mut iter = columns.iter();
+        if let Some(col) = iter.next() {
+            self.push_quote(&col.name);
+        }
+        for col in iter {
+            self.push(", ").push_quote(&col.name);
+        }
+        self
+    }
+
+    pub fn visit_update_statement(&mut self, update_stmt: &UpdateStatement) -> &mut Self {
+        self.register_write_tables(&update_stmt.table, &update_stmt.joins);
+        for assignment in &update_stmt.assignments {
+            self.register_table_from_expr(&assignment.value);
+        }
+        for where_expr in &update_stmt.where_clause {
+            self.register_table_from_expr(where_expr);
+        }
+
+        let caps = self.dialect.caps();
+        let multi_table = !update_stmt.joins.is_empty();
+        if multi_table && caps.update_join == UpdateJoinCap::None {
+            self.fail(RenderError::unsupported("UPDATE ... JOIN", "the dialect has no multi-table UPDATE"));
+        }
+        match caps.update_join {
+            UpdateJoinCap::Join => {
+                self.push("UPDATE ").visit_table(&update_stmt.table);
+                for (table, on) in &update_stmt.joins {
+                    self.push(" INNER JOIN ").visit_table(table).push(" ON ").visit_expr(on, 0);
+                }
+                // 多表时 SET 的列必须带上表名，否则可能产生歧义
+                self.push(" SET ").visit_assignments(&update_stmt.assignments, multi_table);
+                self.visit_where_clause(&update_stmt.where_clause);
+            },
+            UpdateJoinCap::From | UpdateJoinCap::None => {
+                self.push("UPDATE ").visit_table(&update_stmt.table);
+                self.push(" SET ").visit_assignments(&update_stmt.assignments, false);
+                let mut iter = update_stmt.joins.iter();
+                if let Some((table, _)) = iter.next() {
+                    self.push(" FROM ").visit_table(table);
+                    for (table, _) in iter {
+                        self.push(", ").visit_table(table);
+                    }
+                }
+                // 连接条件并入 WHERE
+                let mut filters: Vec<Expr> = update_stmt.joins.iter().map(|(_, on)| on.clone()).collect();
+                filters.extend(update_stmt.where_clause.iter().cloned());
+                self.visit_where_clause(&filters);
+            },
+            UpdateJoinCap::AliasFrom => {
+                let alias = self.alias_cache.alias_of(&update_stmt.table.inner).unwrap_or_default();
+                self.push("UPDATE ").push_quote(&alias);
+                self.push(" SET ").visit_assignments(&update_stmt.assignments, false);
+                self.push(" FROM ").visit_table(&update_stmt.table);
+                for (table, on) in &update_stmt.joins {
+                    self.push(" INNER JOIN ").visit_table(table).push(" ON ").visit_expr(on, 0);
+                }
+                self.visit_where_clause(&update_stmt.where_clause);
+            },
+        }
+
+        // MySQL 的多表 UPDATE 不允许 ORDER BY / LIMIT
+        self.visit_write_limit(&update_stmt.orders, update_stmt.limit, caps.write_with_limit && !multi_table);
+        self.visit_returning(&update_stmt.returning)
+    }
+
+    pub fn visit_delete_statement(&mut self, delete_stmt: &DeleteStatement) -> &mut Self {
+        self.register_write_tables(&delete_stmt.table, &delete_stmt.joins);
+        for where_expr in &delete_stmt.where_clause {
+            self.register_table_from_expr(where_expr);
+        }
+
+        let caps = self.dialect.caps();
+        if delete_stmt.joins.is_empty() && caps.delete_join != DeleteJoinCap::AliasFrom {
+            self.push("DELETE FROM ").visit_table(&delete_stmt.table);
+            self.visit_where_clause(&delete_stmt.where_clause);
+            self.visit_write_limit(&delete_stmt.orders, delete_stmt.limit, caps.write_with_limit);
+            return self.visit_returning(&delete_stmt.returning);
+        }
+
+        match caps.delete_join {
+            DeleteJoinCap::Using => {
+                self.push("DELETE FROM ").visit_table(&delete_stmt.table).push(" USING ");
+                let mut iter = delete_stmt.joins.iter();
+                if let Some((table, _)) = iter.next() {
+                    self.visit_table(table);
+                }
+                for (table, _) in iter {
+                    self.push(", ").visit_table(table);
+                }
+                let mut filters: Vec<Expr> = delete_stmt.joins.iter().map(|(_, on)| on.clone()).collect();
+                filters.extend(delete_stmt.where_clause.iter().cloned());
+                self.visit_where_clause(&filters);
+            },
+            DeleteJoinCap::Join | DeleteJoinCap::AliasFrom => {
+                let alias = self.alias_cache.alias_of(&delete_stmt.table.inner).unwrap_or_default();
+                self.push("DELETE ").push_quote(&alias);
+                self.push(" FROM ").visit_table(&delete_stmt.table);
+                for (table, on) in &delete_stmt.joins {
+                    self.push(" INNER JOIN ").visit_table(table).push(" ON ").visit_expr(on, 0);
+                }
+                self.visit_where_clause(&delete_stmt.where_clause);
+            },
+            DeleteJoinCap::Subquery => {
+                // 改写为相关子查询：DELETE FROM a WHERE EXISTS (SELECT 1 FROM b WHERE <on> AND <filters>)
+                let mut iter = delete_stmt.joins.iter();
+                let (first, _) = iter.next().unwrap();
+                let mut subquery = SelectStatement::from(first).select(lit(1));
+                for (table, _) in iter {
+                    subquery = subquery.cross_join(table);
+                }
+                for (_, on) in &delete_stmt.joins {
+                    subquery = subquery.filter(on.clone());
+                }
+                for where_expr in &delete_stmt.where_clause {
+                    subquery = subquery.filter(where_expr.clone());
+                }
+                self.push("DELETE FROM ").visit_table(&delete_stmt.table);
+                self.push(" WHERE EXISTS (").select_statement(&subquery).push(")");
+            },
+        }
+        self.visit_write_limit(&delete_stmt.orders, delete_stmt.limit, false);
+        self.visit_returning(&delete_stmt.returning)
+    }
+
+    fn register_write_tables(&mut self, table: &Table, joins: &[(Table, Expr)]) {
+        self.register_table_inner(table);
+        for (table, on) in joins {
+            self.register_table_inner(table);
+            self.register_table_from_expr(on);
+        }
+    }
+
+    // 丢掉 ORDER BY / LIMIT 会改写所有匹配的行，两种模式下都报错
+    fn visit_write_limit(&mut self, orders: &Vec<Order>, limit: Option<usize>, allowed: bool) -> &mut Self {
+        if !allowed {
+            if !orders.is_empty() || limit.is_some() {
+                self.fail(RenderError::unsupported(
+                    "ORDER BY / LIMIT",
+                    "the dialect does not allow them in this UPDATE / DELETE",
+                ));
+            }
+            return self;
+        }
+        self.visit_orders(orders);
+        if let Some(n) = limit {
+            self.push(" LIMIT ").push(&n.to_string());
+        }
+        self
+    }
+
+    fn visit_assignments(&mut self, assignments: &[Assignment], qualified: bool) -> &mut Self {
+        let mut iter = assignments.iter();
+        if let Some(assignment) = iter.next() {
+            self.visit_assignment(assignment, qualified);
+        }
+        for assignment in iter {
+            self.push(", ").visit_assignment(assignment, qualified);
+        }
+        self
+    }
+
+    fn visit_assignment(&mut self, assignment: &Assignment, qualified: bool) -> &mut Self {
+        if qualified {
+            self.visit_column_ref(&assignment.column);
+        } else {
+            self.push_quote(&assignment.column.name);
+        }
+        self.push(" = ").visit_expr(&assignment.value, 0)
+    }
+
+    pub fn visit_returning(&mut self, returning: &[SelectItem]) -> &mut Self {
+        if returning.is_empty() {
+            return self;
+        }
+        if self.dialect.caps().returning {
+            self.push(" RETURNING ").visit_select_clause(returning);
+        } else {
+            self.unsupported("RETURNING", "the dialect has no RETURNING clause");
+        }
+        self
+    }
+
+    // 写语句的目标表：不参与别名，直接输出表名
+    fn visit_target_table(&mut self, table: &Table) -> &mut Self {
+        match &table.inner.as_ref() {
+            TableInner::Named(name) => self.visit_table_name(name),
+            _ => self.visit_table(table),
+        }
+    }
+
     pub fn visit_locking(&mut self, locking: &Option<Locking>) -> &mut Self {
         if self.dialect.caps().select_with_locking {
             if let Some(locking) = locking {
-                if let Some(lock) = &locking.lock {
-                    match lock {
-                        Lock::Share => self.push(" FOR SHARE"),
-                        Lock::Update => self.push(" FOR UPDATE"),
-                        Lock::UpdateOf(tables) => {
-                            let mut iter = tables.iter();
-                            if let Some(table) = iter.next() {
-                                self.push(" FOR UPDATE OF ");
-                                self.push_quote(&table.visible_name());
-                            }
-                            for table in iter {
-                                self.push(", ").push_quote(&table.visible_name());
-                            }
-                            self.noop()
-                        },
-                    };
-                    if let Some(wait) = &locking.wait {
-                        match wait {
-                            Wait::Default => self.noop(),
-                            Wait::NoWait => self.push(" NOWAIT"),
-                            Wait::SkipLocked => self.push(" SKIP LOCKED"),
-                        };
+                if locking.lock.is_some() || locking.wait.is_some() {
+                    // 只指定了等待方式时按 FOR UPDATE 处理
+                    let mut lock = locking.lock.as_ref().unwrap_or(&Lock::Update);
+                    if matches!(lock, Lock::Share) && !self.dialect.caps().share_lock {
+                        // 退化为排他锁，不能丢掉锁
+                        self.unsupported("FOR SHARE", "the dialect only has FOR UPDATE");
+                        lock = &Lock::Update;
                     }
-                } else if let Some(wait) = &locking.wait {
-                    match wait {
-                        Wait::Default => self.push(" FOR UPDATE"),
-                        Wait::NoWait => self.push(" FOR UPDATE NOWAIT"),
-                        Wait::SkipLocked => self.push(" FOR UPDATE SKIP LOCKED"),
+                    let of = match lock {
+                        Lock::UpdateOf(tables) => self.lock_of_tables(tables),
+                        Lock::UpdateOfColumns(columns) => Some(self.lock_of_columns(columns)),
+                        _ => None,
                     };
+                    let wait = locking.wait.as_ref().unwrap_or(&Wait::Default);
+                    let sql = self.dialect.render_lock(lock, of.as_deref(), wait);
+                    self.push(&sql);
                 }
             }
+        } else if self.dialect.caps().table_hints {
+            // 已作为表提示渲染，见 register_table_hints
+        } else if has_lock(locking) {
+            self.unsupported("FOR UPDATE", "the dialect has no row-level locking");
         }
         self
     }
@@ -194,23 +915,87 @@ impl<D: Dialect> Visitor<D> {
         self
     }
     pub fn visit_orders(&mut self, orders: &Vec<Order>) -> &mut Self {
+        if orders.is_empty() {
+            return self;
+        }
+        self.push(" ORDER BY ").visit_order_list(orders)
+    }
+    fn visit_order_list(&mut self, orders: &[Order]) -> &mut Self {
         let mut iter = orders.iter();
         if let Some(item) = iter.next() {
-            self.push(" ORDER BY ");
-            self.visit_expr(&item.expr, 0);
-            if item.is_desc() {
-                self.push(" DESC");
-            }
+            self.visit_order(item);
             for item in iter {
                 self.push(", ");
-                self.visit_expr(&item.expr, 0);
-                if item.is_desc() {
-                    self.push(" DESC");
-                }
+                self.visit_order(item);
             }
         }
         self
     }
+    fn visit_order(&mut self, order: &Order) -> &mut Self {
+        self.visit_expr(&order.expr, 0);
+        if order.is_desc() {
+            self.push(" DESC");
+        }
+        self
+    }
+
+    pub fn visit_windows(&mut self, windows: &[(String, Window)]) -> &mut Self {
+        let mut iter = windows.iter();
+        if let Some((name, window)) = iter.next() {
+            self.push(" WINDOW ").push_quote(name).push(" AS ").visit_window(window);
+            for (name, window) in iter {
+                self.push(", ").push_quote(name).push(" AS ").visit_window(window);
+            }
+        }
+        self
+    }
+
+    // (base PARTITION BY ... ORDER BY ... ROWS BETWEEN ... AND ...)
+    pub fn visit_window(&mut self, window: &Window) -> &mut Self {
+        self.push("(");
+        let mut sep = "";
+        if let Some(name) = &window.name {
+            self.push_quote(name);
+            sep = " ";
+        }
+        if !window.partition_by.is_empty() {
+            self.push(sep).push("PARTITION BY ").visit_expr_list(&window.partition_by, 0);
+            sep = " ";
+        }
+        if !window.orders.is_empty() {
+            self.push(sep).push("ORDER BY ").visit_order_list(&window.orders);
+            sep = " ";
+        }
+        if let Some(frame) = &window.frame {
+            // 换成其他单位会改变帧的范围，两种模式下都报错
+            if frame.unit == FrameUnit::Groups && !self.dialect.caps().groups_frame {
+                self.fail(RenderError::unsupported("GROUPS", "the dialect has no GROUPS window frame"));
+            }
+            self.push(sep).push(frame.unit.as_str()).push(" ");
+            match &frame.end {
+                Some(end) => {
+                    self.push("BETWEEN ")
+                        .visit_frame_bound(&frame.start)
+                        .push(" AND ")
+                        .visit_frame_bound(end);
+                },
+                None => {
+                    self.visit_frame_bound(&frame.start);
+                },
+            }
+        }
+        self.push(")")
+    }
+
+    fn visit_frame_bound(&mut self, bound: &FrameBound) -> &mut Self {
+        match bound {
+            FrameBound::UnboundedPreceding => self.push("UNBOUNDED PRECEDING"),
+            FrameBound::Preceding(n) => self.push(&n.to_string()).push(" PRECEDING"),
+            FrameBound::CurrentRow => self.push("CURRENT ROW"),
+            FrameBound::Following(n) => self.push(&n.to_string()).push(" FOLLOWING"),
+            FrameBound::UnboundedFollowing => self.push("UNBOUNDED FOLLOWING"),
+        }
+    }
     pub fn visit_where_clause(&mut self, where_clause: &Vec<Expr>) -> &mut Self {
         self.visit_filter_clause(where_clause, FilterScope::Where)
     }
@@ -223,24 +1008,26 @@ impl<D: Dialect> Visitor<D> {
             FilterScope::Having => " HAVING ",
             FilterScope::Where => " WHERE ",
         };
+        // 多个条件以 AND 连接，其中的 OR 需要加括号
+        let precedence = if filter_clause.len() > 1 { BinaryOp::And.precedence() } else { 0 };
         let mut iter = filter_clause.iter();
         if let Some(f) = iter.next() {
             self.push(prefix);
             match f {
                 Expr::Param(lit) => self.visit_param(lit).push(" = ").visit_param(lit),
-                _ => self.visit_expr(f, 0),
+                _ => self.visit_expr(f, precedence),
             };
             for f in iter {
                 self.push(" AND ");
                 match f {
                     Expr::Param(lit) => self.visit_param(lit).push(" = ").visit_param(lit),
-                    _ => self.visit_expr(f, 0),
+                    _ => self.visit_expr(f, precedence),
                 };
             }
         }
         self
     }
-    pub fn visit_select_clause(&mut self, select_clause: &Vec<SelectItem>) -> &mut Self {
+    pub fn visit_select_clause(&mut self, select_clause: &[SelectItem]) -> &mut Self {
         let mut iter = select_clause.iter();
         if let Some(item) = iter.next() {
             self.visit_select_item(item);
@@ -254,25 +1041,128 @@ impl<D: Dialect> Visitor<D> {
         self
     }
 
-    pub fn visit_limit_and_offset(&mut self, limit: Option<usize>, offset: Option<usize>) {
-        if let Some(n) = limit {
-            self.push(" LIMIT ").push(&n.to_string());
+    // OFFSET ... FETCH 必须跟在 ORDER BY 之后
+    fn visit_paging(&mut self, orders: &[Order], limit: Option<usize>, offset: Option<usize>) {
+        let paged = limit.is_some() || offset.is_some();
+        if paged && orders.is_empty() && self.dialect.caps().limit == LimitCap::Top {
+            self.push(" ORDER BY (SELECT NULL)");
         }
-        if self.dialect.caps().standalone_offset || limit.is_some() {
-            if let Some(n) = offset {
-                self.push(" OFFSET ").push(&n.to_string());
+        self.visit_limit_and_offset(limit, offset);
+    }
+
+    // FOR UPDATE OF 的目标列表，返回 None 时退化为 FOR UPDATE
+    fn lock_of_tables(&mut self, tables: &[Table]) -> Option<String> {
+        if self.dialect.caps().lock_of == LockOfCap::Column {
+            // 退化为锁住所有表，不能丢掉锁
+            self.unsupported("FOR UPDATE OF table", "the dialect locks by column, use for_update_of_columns");
+            return None;
+        }
+        let names: Vec<String> = tables
+            .iter()
+            .map(|t| self.alias_cache.alias_of(&t.inner).unwrap_or_else(|| t.visible_name()))
+            .collect();
+        Some(self.quoted_list(&names))
+    }
+
+    fn lock_of_columns(&self, columns: &[Column]) -> String {
+        if self.dialect.caps().lock_of == LockOfCap::Column {
+            let names: Vec<String> = columns.iter().map(|col| self.column_ref(col)).collect();
+            return names.join(", ");
+        }
+        // 按表加锁的方言锁住列所在的表
+        let mut names: Vec<String> = Vec::new();
+        for col in columns {
+            if let Some(alias) = self.alias_cache.alias_of(&col.table_inner) {
+                if !names.contains(&alias) {
+                    names.push(alias);
+                }
             }
         }
+        self.quoted_list(&names)
+    }
+
+    fn quoted_list(&self, names: &[String]) -> String {
+        names.iter().map(|name| self.quoted(name)).collect::<Vec<_>>().join(", ")
+    }
+
+    pub fn visit_limit_and_offset(&mut self, limit: Option<usize>, offset: Option<usize>) {
+        match self.dialect.render_limit(limit, offset) {
+            Ok(sql) => self.push(&sql),
+            Err(e) => self.reject(e),
+        };
     }
 
     pub fn visit_table(&mut self, table: &Table) -> &mut Self {
+        let alias = self.alias_cache.alias_of(&table.inner);
         match &table.inner.as_ref() {
-            TableInner::Named(name) => self.push_quote(name),
-            TableInner::Subquery(subquery) => self.push("(").visit_select_statement(subquery).push(")"),
-            TableInner::Join(join) => self.visit_join(join),
+            TableInner::Named(name) => {
+                self.visit_table_name(name);
+                // 引用 CTE 时别名就是 CTE 名本身，无需 AS
+                if name.is_qualified() || alias.as_ref() != Some(&name.name) {
+                    self.visit_table_alias(&alias);
+                }
+                return self.visit_table_hint(&table.inner);
+            },
+            TableInner::Subquery(subquery) => {
+                self.push("(").subquery(subquery).push(")");
+            },
+            TableInner::Join(join) => {
+                self.visit_join(join);
+            },
+            TableInner::Values(values) => return self.visit_values_table(values, &alias),
         };
-        let alias = self.alias_cache.alias_of(&table.inner);
-        self.visit_alias(&alias)
+        self.visit_table_alias(&alias)
+    }
+
+    fn visit_values_table(&mut self, values: &Values, alias: &Option<String>) -> &mut Self {
+        match self.dialect.caps().values_table {
+            ValuesTableCap::ColumnList => {
+                self.push("(")
+                    .visit_values_rows(&values.rows)
+                    .push(")")
+                    .visit_table_alias(alias);
+                self.push(" (");
+                self.visit_identifiers(&values.columns).push(")")
+            },
+            // SQLite 的表别名不能带列名，VALUES 的列固定为 column1、column2 ...
+            ValuesTableCap::SelectColumns => {
+                self.push("(SELECT ");
+                for (i, column) in values.columns.iter().enumerate() {
+                    if i > 0 {
+                        self.push(", ");
+                    }
+                    self.push_quote(&format!("column{}", i + 1))
+                        .push(" AS ")
+                        .push_quote(column);
+                }
+                self.push(" FROM (")
+                    .visit_values_rows(&values.rows)
+                    .push("))")
+                    .visit_table_alias(alias)
+            },
+            ValuesTableCap::None => self.unsupported("VALUES table", "the dialect has no VALUES table constructor"),
+        }
+    }
+
+    fn visit_values_rows(&mut self, rows: &[Vec<Expr>]) -> &mut Self {
+        self.push("VALUES ");
+        for (i, row) in rows.iter().enumerate() {
+            if i > 0 {
+                self.push(", ");
+            }
+            self.push("(").visit_expr_list(row, 0).push(")");
+        }
+        self
+    }
+
+    fn visit_identifiers(&mut self, names: &[String]) -> &mut Self {
+        for (i, name) in names.iter().enumerate() {
+            if i > 0 {
+                self.push(", ");
+            }
+            self.push_quote(name);
+        }
+        self
     }
 
     pub fn visit_join(&mut self, join: &Join) -> &mut Self {
@@ -326,12 +1216,7 @@ impl<D: Dialect> Visitor<D> {
                     .visit_binary_op(op)
                     .visit_expr(right, current_precedence),
             },
-            Expr::In { expr, list, negated } => self
-                .visit_expr(expr, current_precedence)
-                .visit_binary_op(if *negated { &BinaryOp::NotIn } else { &BinaryOp::In })
-                .push("(")
-                .visit_expr_list(list, 0)
-                .push(")"),
+            Expr::In { expr, list, negated } => self.visit_in(expr, list, *negated, current_precedence),
             Expr::Between { expr, low, high, negated } => self
                 .visit_expr(expr, current_precedence)
                 .push(if *negated { " NOT BETWEEN " } else { " BETWEEN " })
@@ -340,25 +1225,87 @@ impl<D: Dialect> Visitor<D> {
                 .visit_expr(high, current_precedence),
             Expr::Unary { op, expr } => self.visit_unary_op(op).visit_expr(expr, current_precedence),
             Expr::Func(f) => self.visit_func(f),
-            Expr::Subquery(sq) => self.push("(").visit_select_statement(sq).push(")"),
+            Expr::Case(case) => self.visit_case(case),
+            Expr::Cast { expr, column_type } => self.visit_cast(expr, column_type),
+            Expr::Subquery(sq) => self.push("(").subquery(sq).push(")"),
+            Expr::Excluded(col) => match self.dialect.caps().upsert {
+                UpsertCap::OnConflict => self.push("EXCLUDED.").push_quote(&col.name),
+                UpsertCap::OnDuplicateKey => self.push("VALUES(").push_quote(&col.name).push(")"),
+                UpsertCap::None => self.fail(RenderError::unsupported("EXCLUDED", "the dialect has no upsert")),
+            },
         };
         if need_parens {
             self.push(")");
         }
         self
     }
+    pub fn visit_cast(&mut self, expr: &Expr, column_type: &ColumnType) -> &mut Self {
+        let type_name = match self.dialect.cast_type(column_type) {
+            Ok(type_name) => type_name,
+            Err(e) => return self.fail(e.into()),
+        };
+        match self.dialect.caps().cast {
+            CastCap::Function => self
+                .push("CAST(")
+                .visit_expr(expr, 0)
+                .push(" AS ")
+                .push(&type_name)
+                .push(")"),
+            CastCap::DoubleColon => {
+                // :: 的优先级最高，非原子表达式需要括号
+                let atomic = matches!(
+                    expr,
+                    Expr::Column(_) | Expr::Param(_) | Expr::Func(_) | Expr::Case(_) | Expr::Cast { .. } | Expr::Subquery(_)
+                );
+                if atomic {
+                    self.visit_expr(expr, 0);
+                } else {
+                    self.push("(").visit_expr(expr, 0).push(")");
+                }
+                self.push("::").push(&type_name)
+            },
+        }
+    }
+
+    pub fn visit_case(&mut self, case: &Case) -> &mut Self {
+        if case.conditions.is_empty() {
+            self.fail(RenderError::invalid("CASE", "it needs at least one WHEN"));
+        }
+        self.push("CASE");
+        if let Some(operand) = &case.operand {
+            self.push(" ").visit_expr(operand, 0);
+        }
+        for (condition, then) in &case.conditions {
+            self.push(" WHEN ").visit_expr(condition, 0).push(" THEN ").visit_expr(then, 0);
+        }
+        if let Some(else_expr) = &case.else_expr {
+            self.push(" ELSE ").visit_expr(else_expr, 0);
+        }
+        self.push(" END")
+    }
+
     pub fn visit_func(&mut self, f: &Func) -> &mut Self {
+        self.visit_func_call(f);
+        match &f.over {
+            Some(window) if window.is_name_only() => self.push(" OVER ").push_quote(window.name.as_ref().unwrap()),
+            Some(window) => self.push(" OVER ").visit_window(window),
+            None => self,
+        }
+    }
+
+    fn visit_func_call(&mut self, f: &Func) -> &mut Self {
+        let name = self.dialect.render_func_name(&f.name);
         if !f.distinct {
             // isn't distinct
-            return self.push(&f.name).push("(").push_func_args(&f.args).push(")");
+            return self.push(&name).push("(").push_func_args(&f.args).push(")");
         }
 
         if f.args.len() <= 1 || !f.name.eq_ignore_ascii_case("count") {
             // distinct, but not count and multiple columns。
-            return self.push(&f.name).push("(DISTINCT ").push_func_args(&f.args).push(")");
+            return self.push(&name).push("(DISTINCT ").push_func_args(&f.args).push(")");
         }
         // count distinct multiple columns
-        self.push(&f.name).push("(DISTINCT ");
+        self.push(&name).push("(DISTINCT ");
         match self.dialect.caps().count_distinct {
             CountDistinctCap::Merge => {
                 self.push("(").push_func_args(&f.args).push(")");
@@ -366,7 +1313,13 @@ impl<D: Dialect> Visitor<D> {
             CountDistinctCap::Extend => {
                 self.push_func_args(&f.args);
             },
-            _ => unreachable!(),
+            // 只有 SELECT 列表顶层的 COUNT(DISTINCT a, b) 会被 rewrite_count_distinct 改写
+            CountDistinctCap::Rewrite => {
+                self.fail(RenderError::unsupported(
+                    "COUNT(DISTINCT) with multiple columns",
+                    "it can only be rewritten as a top-level select item",
+                ));
+            },
         }
         self.push(")")
     }
@@ -390,6 +1343,37 @@ impl<D: Dialect> Visitor<D> {
         }
     }
 
+    fn visit_in(&mut self, expr: &Expr, list: &Vec<Expr>, negated: bool, precedence: i32) -> &mut Self {
+        if let Some(max) = self.dialect.caps().max_in_list
+            && list.len() > max
+        {
+            // 拆成 (x IN (...) OR x IN (...))，NOT IN 用 AND 连接
+            let joiner = if negated { " AND " } else { " OR " };
+            self.push("(");
+            for (i, chunk) in list.chunks(max).enumerate() {
+                if i > 0 {
+                    self.push(joiner);
+                }
+                self.visit_in(expr, &chunk.to_vec(), negated, precedence);
+            }
+            return self.push(")");
+        }
+        self.visit_expr(expr, precedence);
+        if self.array_binding
+            && self.dialect.caps().array_binding
+            && let Some(array) = array_of(list)
+        {
+            return self
+                .push(if negated { " <> ALL(" } else { " = ANY(" })
+                .visit_param(&Param::Value(array))
+                .push(")");
+        }
+        self.visit_binary_op(if negated { &BinaryOp::NotIn } else { &BinaryOp::In })
+            .push("(")
+            .visit_expr_list(list, 0)
+            .push(")")
+    }
+
     pub fn visit_expr_list(&mut self, expr_list: &Vec<Expr>, parent_precedence: i32) -> &mut Self {
         let mut iter = expr_list.iter();
         if let Some(expr) = iter.next() {
@@ -417,44 +1401,102 @@ impl<D: Dialect> Visitor<D> {
             Distinct::On(cols) if self.dialect.caps().distinct_on => {
                 self.push("DISTINCT ON (").visit_expr_list(cols, 0).push(") ")
             },
-            _ => self.push("DISTINCT "),
+            Distinct::On(_) => self
+                .unsupported("DISTINCT ON", "the dialect falls back to a plain DISTINCT")
+                .push("DISTINCT "),
         }
     }
 
     pub fn visit_column_ref(&mut self, col: &Column) -> &mut Self {
-        let alias = self.alias_cache.alias_of(&col.table_inner);
-        if let Some(alias) = alias {
-            self.push_quote(&alias).push(".");
+        let sql = self.column_ref(col);
+        self.push(&sql)
+    }
+
+    fn column_ref(&self, col: &Column) -> String {
+        if self.upsert_target == Some(Arc::as_ptr(&col.table_inner) as usize)
+            && let TableInner::Named(name) = col.table_inner.as_ref()
+        {
+            return format!("{}.{}", self.table_name(name), self.quoted(&col.name));
+        }
+        match (self.alias_cache.alias_of(&col.table_inner), col.table_inner.as_ref()) {
+            (Some(alias), _) => format!("{}.{}", self.quoted(&alias), self.quoted(&col.name)),
+            // 不在 FROM 中、带 schema 的表无别名可用，按完整表名限定
+            (None, TableInner::Named(name)) if name.is_qualified() => {
+                format!("{}.{}", self.table_name(name), self.quoted(&col.name))
+            },
+            (None, _) => self.quoted(&col.name),
         }
-        self.push_quote(&col.name)
+    }
+
+    // e.g. "analytics"."events"
+    fn table_name(&self, name: &TableName) -> String {
+        name.parts().map(|part| self.quoted(part)).collect::<Vec<_>>().join(".")
+    }
+
+    fn visit_table_name(&mut self, name: &TableName) -> &mut Self {
+        let sql = self.table_name(name);
+        self.push(&sql)
     }
 
     pub fn visit_param(&mut self, p: &Param) -> &mut Self {
         match p {
             Param::Null => self.push("NULL"),
-            Param::Value(data) => self.bind(data.clone()),
-            Param::Literal(data) => match data {
-                ParamData::Int(v) => self.push(&v.to_string()),
-                ParamData::Float(v) => self.push(&v.to_string()),
-                ParamData::Bool(v) => self.push(self.dialect.bool_str(*v)),
-                ParamData::String(v) => self.push("'").push_escape(&v).push("'"),
-                ParamData::Date(v) => self.push("'").push(&v.to_string()).push("'"),
-                ParamData::DateTime(v) => self.push("'").push(&v.to_string()).push("'"),
-                ParamData::Time(v) => self.push("'").push(&v.to_string()).push("'"),
-                ParamData::Decimal(v) => self.push(&v.to_string()),
-                ParamData::Uuid(v) => self.push("'").push(&v.to_string()).push("'"),
-                ParamData::Json(v) => self.push("'").push_escape(&v.to_string()).push("'"),
-                ParamData::Binary(v) => {
-                    self.push("X'");
-                    for byte in v {
-                        self.push(&format!("{:02x}", byte));
-                    }
-                    self.push("'")
+            Param::Value(data) => {
+                if self.builder.bind(data.clone(), &self.dialect) {
+                    self.record_bound(data);
+                }
+                self
+            },
+            Param::Named(name, data) => {
+                match self.builder.bind_named(name, data.clone(), &self.dialect) {
+                    Ok(true) => self.record_bound(data),
+                    Ok(false) => {},
+                    Err(e) => {
+                        self.fail(e);
+                    },
                 }
+                self
+            },
+            Param::Literal(data) => {
+                let sql = self.literal(data);
+                self.push(&sql)
+            },
+        }
+    }
+
+    // 字面量的 SQL 文本，visit_param 与 to_debug_sql 共用
+    fn literal(&self, data: &ParamData) -> String {
+        match data {
+            ParamData::Int(v) => v.to_string(),
+            ParamData::Float(v) => v.to_string(),
+            ParamData::Bool(v) => self.dialect.bool_str(*v).to_string(),
+            ParamData::String(v) => self.dialect.render_string(v),
+            ParamData::Date(v) => format!("'{}'", v),
+            ParamData::DateTime(v) => format!("'{}'", v),
+            ParamData::Time(v) => format!("'{}'", v),
+            ParamData::Decimal(v) => v.to_string(),
+            ParamData::Uuid(v) => format!("'{}'", v),
+            ParamData::Json(v) => self.dialect.render_string(&v.to_string()),
+            ParamData::Binary(v) => self.dialect.render_binary(v),
+            ParamData::Array(v) => {
+                format!(
+                    "ARRAY[{}]",
+                    v.iter().map(|d| self.literal(d)).collect::<Vec<_>>().join(", ")
+                )
             },
         }
     }
 
+    fn visit_table_alias(&mut self, alias: &Option<String>) -> &mut Self {
+        if !self.dialect.caps().bare_table_alias {
+            return self.visit_alias(alias);
+        }
+        if let Some(a) = alias {
+            self.push(" ").push_quote(a);
+        }
+        self
+    }
+
     fn visit_alias(&mut self, alias: &Option<String>) -> &mut Self {
         if let Some(a) = alias {
             self.push(" AS ");
@@ -464,58 +1506,217 @@ impl<D: Dialect> Visitor<D> {
     }
 
     #[inline]
-    pub fn finish(&self) -> (String, Vec<ParamData>) {
-        (self.builder.buff.clone(), self.builder.binder.clone())
+    // 返回 SQL、按位置排列的参数以及参数名到位置的映射
+    // 不检查渲染错误，有错误时 SQL 可能缺少子句；执行前请使用 try_finish，或检查 errors()
+    pub fn finish(&self) -> (String, Vec<ParamData>, ParamMap) {
+        (self.sql(), self.builder.binder.clone(), self.builder.names.clone())
+    }
+
+    pub fn try_finish(&self) -> Result<(String, Vec<ParamData>, ParamMap), RenderError> {
+        match self.errors.first() {
+            Some(e) => Err(e.clone()),
+            None => Ok((self.sql(), self.builder.binder.clone(), self.builder.names.clone())),
+        }
+    }
+
+    fn sql(&self) -> String {
+        match &self.format {
+            Some(format) => pretty(&self.builder.buff, &self.dialect, format),
+            None => self.builder.buff.clone(),
+        }
+    }
+
+    // 仅用于日志和排查问题，不能用于执行：参数以字面量内联，失去了参数化的保护
+    // e.g. -- debug only, not for execution
+    //      SELECT ... WHERE `users0`.`name` = 'bob'
+    pub fn to_debug_sql(&self) -> String {
+        let sql = self.sql();
+        let mut out = String::from("-- debug only, not for execution\n");
+        let Ok(tokens) = tokenize(&sql, &self.dialect) else {
+            out.push_str(&sql);
+            return out;
+        };
+        let (mut last, mut next) = (0, 0);
+        for (i, token) in tokens.iter().enumerate() {
+            let index = match &token.kind {
+                TokenKind::Placeholder(Some(n)) => n - 1,
+                TokenKind::Placeholder(None) => {
+                    next += 1;
+                    next - 1
+                },
+                TokenKind::NamedPlaceholder(name) => match self.builder.names.get(name) {
+                    Some(slots) => slots[0],
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let end = token.offset + sql[token.offset..tokens[i + 1].offset].trim_end().len();
+            out.push_str(&sql[last..token.offset]);
+            match self.builder.binder.get(index) {
+                Some(data) => out.push_str(&self.literal(data)),
+                None => out.push_str(&sql[token.offset..end]),
+            }
+            last = end;
+        }
+        out.push_str(&sql[last..]);
+        out
+    }
+
+    pub fn errors(&self) -> &[RenderError] {
+        &self.errors
+    }
+
+    pub fn dialect(&self) -> &D {
+        &self.dialect
     }
 
     #[inline]
+    // SQL Server 的锁和索引都是表提示：FROM t WITH (INDEX(idx), UPDLOCK, ROWLOCK)
+    fn register_table_hints(&mut self, stmt: &SelectStatement) {
+        let mut lock_hints = Vec::new();
+        let locking = stmt.locking.as_ref().filter(|_| stmt.set_ops.is_empty());
+        if let Some(locking) = locking {
+            match &locking.lock {
+                Some(Lock::Share) => lock_hints.push("HOLDLOCK"),
+                Some(_) => lock_hints.push("UPDLOCK"),
+                None if locking.wait.is_some() => lock_hints.push("UPDLOCK"),
+                None => {},
+            }
+            if !lock_hints.is_empty() {
+                lock_hints.push("ROWLOCK");
+                match locking.wait {
+                    Some(Wait::NoWait) => lock_hints.push("NOWAIT"),
+                    Some(Wait::SkipLocked) => lock_hints.push("READPAST"),
+                    _ => {},
+                }
+            }
+        }
+        // FOR UPDATE OF 只锁指定的表，否则锁 FROM 中的所有表
+        let lock_targets: Option<Vec<usize>> = match locking.and_then(|l| l.lock.as_ref()) {
+            Some(Lock::UpdateOf(tables)) => Some(tables.iter().map(|t| Arc::as_ptr(&t.inner) as usize).collect()),
+            Some(Lock::UpdateOfColumns(columns)) => {
+                Some(columns.iter().map(|c| Arc::as_ptr(&c.table_inner) as usize).collect())
+            },
+            _ => None,
+        };
+
+        let index_hint = match (self.dialect.caps().index_cap.force, stmt.indexes.force.is_empty()) {
+            (Some(render), false) => {
+                let names: Vec<String> = stmt.indexes.force.iter().map(|i| self.quoted(&i.to_string())).collect();
+                Some(format!("{}{}{}", render.before, names.join(", "), render.after))
+            },
+            (None, false) => {
+                self.unsupported("FORCE INDEX", "the dialect has no such index hint");
+                None
+            },
+            _ => None,
+        };
+        if !stmt.indexes.use_.is_empty() {
+            self.unsupported("USE INDEX", "the dialect has no such index hint");
+        }
+        if !stmt.indexes.ignore.is_empty() {
+            self.unsupported("IGNORE INDEX", "the dialect has no such index hint");
+        }
+
+        let mut tables = Vec::new();
+        collect_named_tables(&stmt.from_clause, &mut tables);
+        for (i, addr) in tables.into_iter().enumerate() {
+            let mut hints = Vec::new();
+            // 索引提示只作用于主表
+            if i == 0 {
+                hints.extend(index_hint.clone());
+            }
+            if lock_targets.as_ref().is_none_or(|targets| targets.contains(&addr)) {
+                hints.extend(lock_hints.iter().map(|h| h.to_string()));
+            }
+            if !hints.is_empty() {
+                self.table_hints.insert(addr, hints.join(", "));
+            }
+        }
+    }
+
+    fn visit_table_hint(&mut self, inner: &Arc<TableInner>) -> &mut Self {
+        match self.table_hints.remove(&(Arc::as_ptr(inner) as usize)) {
+            Some(hints) => self.push(" WITH (").push(&hints).push(")"),
+            None => self,
+        }
+    }
+
     fn visit_indexes(&mut self, indexes: &Indexes) -> &mut Self {
         let caps = self.dialect.caps();
-        self._visit_index_by(caps.index_cap.force, &indexes.force);
-        self._visit_index_by(caps.index_cap.use_, &indexes.use_);
-        self._visit_index_by(caps.index_cap.ignore, &indexes.ignore);
+        self._visit_index_by(caps.index_cap.force, &indexes.force, "FORCE INDEX");
+        self._visit_index_by(caps.index_cap.use_, &indexes.use_, "USE INDEX");
+        self._visit_index_by(caps.index_cap.ignore, &indexes.ignore, "IGNORE INDEX");
         self
     }
 
-    fn _visit_index_by(&mut self, render: Option<IndexFormat>, indexes: &Vec<Index>) -> &mut Self {
-        if let Some(render) = render {
-            let mut iter = indexes.iter();
-            if let Some(index) = iter.next() {
-                self.push(" ").push(render.before).push(" ");
-                self.push_quote(&index.to_string());
-                if render.support_multiple {
-                    for index in iter {
-                        self.push(", ").push_quote(&index.to_string());
-                    }
+    fn _visit_index_by(&mut self, render: Option<IndexFormat>, indexes: &Vec<Index>, clause: &'static str) -> &mut Self {
+        if indexes.is_empty() {
+            return self;
+        }
+        let Some(render) = render else {
+            return self.unsupported(clause, "the dialect has no such index hint");
+        };
+        let mut iter = indexes.iter();
+        if let Some(index) = iter.next() {
+            self.push(" ").push(render.before).push(" ");
+            self.push_quote(&index.to_string());
+            if render.support_multiple {
+                for index in iter {
+                    self.push(", ").push_quote(&index.to_string());
                 }
-                self.push(render.after);
+            } else if indexes.len() > 1 {
+                self.unsupported(clause, "the dialect accepts only one index, the rest are dropped");
             }
+            self.push(render.after);
         }
         self
     }
+    // 宽松模式下直接丢弃，严格模式下记录为错误
+    fn unsupported(&mut self, clause: &'static str, reason: &'static str) -> &mut Self {
+        if self.mode == RenderMode::Strict {
+            self.errors.push(RenderError::unsupported(clause, reason));
+        }
+        self
+    }
+
+    // 方言钩子返回的错误，Unsupported 与 unsupported() 一样只在严格模式下记录
+    fn reject(&mut self, e: RenderError) -> &mut Self {
+        match e {
+            RenderError::Unsupported { clause, reason } => self.unsupported(clause, reason),
+            e => self.fail(e),
+        }
+    }
+
+    // 无论哪种模式都无法渲染
+    fn fail(&mut self, e: RenderError) -> &mut Self {
+        self.errors.push(e);
+        self
+    }
+
     #[inline]
     fn push(&mut self, v: &str) -> &mut Self {
         self.builder.push(v);
         self
     }
 
+    // 渲染缓存按参数在语句中的地址生成提取计划
     #[inline]
-    fn bind(&mut self, v: ParamData) -> &mut Self {
-        self.builder.bind(v, &self.dialect);
-        self
+    fn record_bound(&mut self, data: &ParamData) {
+        if let Some(bound) = &mut self.bound {
+            bound.push(data as *const ParamData as usize);
+        }
     }
 
     #[inline]
-    fn push_escape(&mut self, v: &str) -> &mut Self {
-        let v = v.replace("'", "''").replace("\\", "\\\\");
-        self.builder.push(&v);
-        self
+    fn push_quote(&mut self, v: &str) -> &mut Self {
+        let quoted = self.quoted(v);
+        self.push(&quoted)
     }
 
     #[inline]
-    fn push_quote(&mut self, v: &str) -> &mut Self {
-        let char = self.dialect.quote_char();
-        self.push(char).push(v).push(char)
+    fn quoted(&self, v: &str) -> String {
+        self.dialect.render_identifier(v)
     }
 
     #[inline]
@@ -523,3 +1724,118 @@ impl<D: Dialect> Visitor<D> {
         self
     }
 }
+
+// 列表中都是同一类型的参数时合并为数组
+fn array_of(list: &[Expr]) -> Option<ParamData> {
+    let items: Vec<&ParamData> = list
+        .iter()
+        .map(|e| match e {
+            Expr::Param(Param::Value(data)) if !matches!(data, ParamData::Array(_)) => Some(data),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    let kind = std::mem::discriminant(*items.first()?);
+    items
+        .iter()
+        .all(|d| std::mem::discriminant(*d) == kind)
+        .then(|| ParamData::Array(items.into_iter().cloned().collect()))
+}
+
+// INTERSECT 在一些数据库中比 UNION / EXCEPT 结合得更紧，在另一些数据库中则相同；
+// 返回最后一个跟在其他运算之后的 INTERSECT 的位置，它之前的部分需要单独成组
+fn intersect_after_other(set_ops: &[SetOperation]) -> Option<usize> {
+    let first_other = set_ops.iter().position(|set_op| set_op.op != SetOp::Intersect)?;
+    set_ops
+        .iter()
+        .rposition(|set_op| set_op.op == SetOp::Intersect)
+        .filter(|&at| at > first_other)
+}
+
+// a UNION b INTERSECT c => SELECT * FROM (a UNION b) AS sq INTERSECT c，按从左到右的顺序求值
+fn group_set_ops(select_stmt: &SelectStatement, at: usize) -> SelectStatement {
+    let left = SelectStatement {
+        with_clause: Vec::new(),
+        recursive: false,
+        orders: Vec::new(),
+        limit: None,
+        offset: None,
+        locking: None,
+        settings: Vec::new(),
+        set_ops: select_stmt.set_ops[..at].to_vec(),
+        ..select_stmt.clone()
+    };
+    let mut grouped = SelectStatement::from(&Table::from(left));
+    grouped.with_clause = select_stmt.with_clause.clone();
+    grouped.recursive = select_stmt.recursive;
+    grouped.set_ops = select_stmt.set_ops[at..].to_vec();
+    grouped.orders = select_stmt.orders.clone();
+    grouped.limit = select_stmt.limit;
+    grouped.offset = select_stmt.offset;
+    grouped.locking = select_stmt.locking.clone();
+    grouped.settings = select_stmt.settings.clone();
+    grouped
+}
+
+// 组合结果的列名取自第一个操作数的 SELECT 列表，带别名的列只能按别名引用
+fn result_column(select_stmt: &SelectStatement, col: &Column) -> String {
+    for item in &select_stmt.select_clause {
+        if let Expr::Column(c) = &item.expr
+            && c.name == col.name
+            && Arc::ptr_eq(&c.table_inner, &col.table_inner)
+        {
+            return item.alias.clone().unwrap_or_else(|| c.name.clone());
+        }
+    }
+    match (select_stmt.select_clause.is_empty(), select_stmt.from_clause.inner.as_ref()) {
+        (true, TableInner::Subquery(sq)) => result_column(sq, col),
+        _ => col.name.clone(),
+    }
+}
+
+// 由字面量和参数组成的 IN 列表只保留第一个元素，不同长度的列表渲染结果相同
+fn collapse_in_lists(select_stmt: &mut SelectStatement) {
+    struct Collapse;
+    impl VisitMut for Collapse {
+        fn visit_expr_mut(&mut self, expr: &mut Expr) {
+            if let Expr::In { list, .. } = expr
+                && list.iter().all(|e| matches!(e, Expr::Param(Param::Value(_) | Param::Literal(_))))
+            {
+                list.truncate(1);
+            }
+            visit_expr_mut(self, expr);
+        }
+    }
+    Collapse.visit_select_statement_mut(select_stmt);
+}
+
+// 语句中是否引用了名为 name 的表，e.g. 递归 CTE 引用自身
+fn references_table(select_stmt: &SelectStatement, name: &str) -> bool {
+    struct Reference<'n>(&'n str, bool);
+    impl Visit<'_> for Reference<'_> {
+        fn visit_table(&mut self, table: &Table) {
+            if let TableInner::Named(named) = table.inner.as_ref() {
+                self.1 |= !named.is_qualified() && named.name == self.0;
+            }
+            visit_table(self, table);
+        }
+    }
+    let mut reference = Reference(name, false);
+    reference.visit_select_statement(select_stmt);
+    reference.1
+}
+
+fn has_lock(locking: &Option<Locking>) -> bool {
+    locking.as_ref().is_some_and(|l| l.lock.is_some() || l.wait.is_some())
+}
+
+// 按出现顺序收集 FROM 中的具名表
+fn collect_named_tables(table: &Table, tables: &mut Vec<usize>) {
+    match table.inner.as_ref() {
+        TableInner::Named(_) => tables.push(Arc::as_ptr(&table.inner) as usize),
+        TableInner::Join(join) => {
+            collect_named_tables(&join.left, tables);
+            collect_named_tables(&join.right, tables);
+        },
+        TableInner::Subquery(_) | TableInner::Values(_) => {},
+    }
+}